@@ -0,0 +1,97 @@
+use crate::Sbml;
+use biodivine_xml_doc::Document;
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+use std::str::FromStr;
+use zip::ZipArchive;
+
+/// The format identifier used by the archive manifest for SBML files. Specific levels
+/// and versions extend this prefix (e.g. `.../sbml.level-3.version-2`).
+const FORMAT_SBML: &str = "http://identifiers.org/combine.specifications/sbml";
+
+/// A COMBINE archive (usually an `.omex` file): a zip archive containing models, simulation
+/// descriptions and metadata, described by a `manifest.xml` file.
+///
+/// The whole archive is decompressed into memory when it is opened.
+#[derive(Clone, Debug)]
+pub struct CombineArchive {
+    files: HashMap<String, Vec<u8>>,
+}
+
+impl CombineArchive {
+    /// Read a COMBINE archive from a file at the given `path`.
+    pub fn open(path: &str) -> Result<CombineArchive, String> {
+        let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+        CombineArchive::read_bytes(bytes)
+    }
+
+    /// Read a COMBINE archive from the raw bytes of the zip file.
+    pub fn read_bytes(bytes: Vec<u8>) -> Result<CombineArchive, String> {
+        let mut archive = ZipArchive::new(Cursor::new(bytes)).map_err(|e| e.to_string())?;
+        let mut files = HashMap::new();
+        for i in 0..archive.len() {
+            let mut file = archive.by_index(i).map_err(|e| e.to_string())?;
+            if file.is_dir() {
+                continue;
+            }
+            let mut content = Vec::new();
+            file.read_to_end(&mut content)
+                .map_err(|e| format!("Cannot read `{}`: {}", file.name(), e))?;
+            files.insert(file.name().to_string(), content);
+        }
+        Ok(CombineArchive { files })
+    }
+
+    /// The content of the file at the given `location` within the archive. The location can
+    /// use the `./` prefix, as is common in the archive manifest.
+    pub fn file(&self, location: &str) -> Option<&[u8]> {
+        let location = location.strip_prefix("./").unwrap_or(location);
+        self.files.get(location).map(|it| it.as_slice())
+    }
+
+    /// The location of the master SBML file, as declared by the archive manifest.
+    ///
+    /// If no SBML file is marked as `master`, the first SBML file of the manifest is used.
+    /// An error is returned if the archive has no readable manifest or the manifest does
+    /// not declare any SBML file.
+    pub fn master_sbml_location(&self) -> Result<String, String> {
+        let manifest = self
+            .file("manifest.xml")
+            .ok_or("The archive does not contain a `manifest.xml` file.")?;
+        let manifest = std::str::from_utf8(manifest).map_err(|e| e.to_string())?;
+        let doc = Document::from_str(manifest).map_err(|e| e.to_string())?;
+        let root = doc.root_element().ok_or("The archive manifest is empty.")?;
+
+        // Pairs of locations and `master` flags of all SBML entries.
+        let sbml_entries = root
+            .child_elements(&doc)
+            .into_iter()
+            .filter(|it| it.prefix_name(&doc).1 == "content")
+            .filter(|it| {
+                it.attribute(&doc, "format")
+                    .is_some_and(|format| format.starts_with(FORMAT_SBML))
+            })
+            .filter_map(|it| {
+                let location = it.attribute(&doc, "location")?;
+                let master = it.attribute(&doc, "master") == Some("true");
+                Some((location.to_string(), master))
+            })
+            .collect::<Vec<_>>();
+
+        sbml_entries
+            .iter()
+            .find(|(_, master)| *master)
+            .or(sbml_entries.first())
+            .map(|(location, _)| location.clone())
+            .ok_or("The archive manifest does not declare any SBML file.".to_string())
+    }
+
+    /// Read the master SBML file of this archive (see [CombineArchive::master_sbml_location]).
+    pub fn master_sbml(&self) -> Result<Sbml, String> {
+        let location = self.master_sbml_location()?;
+        let bytes = self.file(location.as_str()).ok_or(format!(
+            "The SBML file `{location}` declared in the manifest is missing."
+        ))?;
+        Sbml::read_bytes(bytes)
+    }
+}
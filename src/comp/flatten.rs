@@ -0,0 +1,512 @@
+use crate::constants::namespaces::{
+    URL_MATHML, URL_PACKAGE_COMP, URL_PACKAGE_FBC, URL_PACKAGE_QUAL, URL_SBML_CORE,
+};
+use crate::core::sbase::element_ancestors;
+use crate::xml::{OptionalXmlChild, XmlElement, XmlWrapper};
+use crate::Sbml;
+use biodivine_xml_doc::Element;
+use std::collections::{HashMap, HashSet};
+use std::ops::{Deref, DerefMut};
+
+/// The lists of core components of a model, in the order prescribed by the specification.
+const MODEL_LISTS: [&str; 10] = [
+    "listOfFunctionDefinitions",
+    "listOfUnitDefinitions",
+    "listOfCompartments",
+    "listOfSpecies",
+    "listOfParameters",
+    "listOfInitialAssignments",
+    "listOfRules",
+    "listOfConstraints",
+    "listOfReactions",
+    "listOfEvents",
+];
+
+/// Attributes of core elements which reference an identifier (`SIdRef` or `UnitSIdRef`).
+const REFERENCE_ATTRIBUTES: [&str; 12] = [
+    "compartment",
+    "species",
+    "symbol",
+    "variable",
+    "conversionFactor",
+    "units",
+    "substanceUnits",
+    "timeUnits",
+    "volumeUnits",
+    "areaUnits",
+    "lengthUnits",
+    "extentUnits",
+];
+
+/// Namespaced attributes of package elements which reference an identifier (`SIdRef`).
+const PACKAGE_REFERENCE_ATTRIBUTES: [(&str, &str); 7] = [
+    (URL_PACKAGE_FBC, "lowerFluxBound"),
+    (URL_PACKAGE_FBC, "upperFluxBound"),
+    (URL_PACKAGE_FBC, "reaction"),
+    (URL_PACKAGE_FBC, "geneProduct"),
+    (URL_PACKAGE_FBC, "associatedSpecies"),
+    (URL_PACKAGE_QUAL, "compartment"),
+    (URL_PACKAGE_QUAL, "qualitativeSpecies"),
+];
+
+/// Separates the identifier of a submodel from the identifiers of its elements.
+const SEPARATOR: &str = "__";
+
+/// Create a copy of `document` in which all submodels of the main model are recursively
+/// inlined (see [Sbml::flatten_comp]).
+pub(crate) fn flatten_document(document: &Sbml) -> Result<Sbml, String> {
+    let flat = Sbml::read_str(&document.to_xml_string()?)?;
+    let root = flat.sbml_root.clone();
+
+    let definitions = comp_children(&root, "listOfModelDefinitions")
+        .iter()
+        .flat_map(|list| comp_children(list, "modelDefinition"))
+        .filter_map(|it| Some((core_attribute(&it, "id")?, it)))
+        .collect();
+    let external = comp_children(&root, "listOfExternalModelDefinitions")
+        .iter()
+        .flat_map(|list| comp_children(list, "externalModelDefinition"))
+        .filter_map(|it| it.get_namespaced_attribute("id", URL_PACKAGE_COMP))
+        .collect();
+    let flattener = Flattener {
+        definitions,
+        external,
+    };
+
+    if let Some(model) = flat.model().get() {
+        flattener.flatten_model(model.xml_element(), &mut Vec::new())?;
+    }
+    strip_comp(&root)?;
+    Ok(flat)
+}
+
+struct Flattener {
+    /// The `comp:modelDefinition` elements of the document, indexed by their `id`.
+    definitions: HashMap<String, XmlElement>,
+    /// Identifiers of the `comp:externalModelDefinition` elements of the document.
+    external: HashSet<String>,
+}
+
+impl Flattener {
+    /// Inline all submodels of `model` into `model`. The `stack` contains the identifiers
+    /// of the model definitions which are currently being instantiated.
+    ///
+    /// The `comp` elements and attributes of `model` are kept, since the ports of a model
+    /// definition are needed to resolve references into its instances.
+    fn flatten_model(&self, model: &XmlElement, stack: &mut Vec<String>) -> Result<(), String> {
+        let submodels = comp_children(model, "listOfSubmodels")
+            .iter()
+            .flat_map(|list| comp_children(list, "submodel"))
+            .collect::<Vec<_>>();
+        if submodels.is_empty() {
+            return Ok(());
+        }
+
+        // The replacements have to be collected before any submodel content is merged into
+        // the model, because the merged content may contain (already resolved) replacements.
+        let replacements = model.recursive_child_elements_filtered(|it| {
+            (it.tag_name() == "replacedElement" || it.tag_name() == "replacedBy")
+                && it.namespace_url() == URL_PACKAGE_COMP
+        });
+
+        let mut renames = HashMap::new();
+        let mut replaced = Vec::new();
+        for submodel in submodels {
+            let instance =
+                self.instantiate(&submodel, &replacements, stack, &mut renames, &mut replaced)?;
+            merge_lists(model, &instance)?;
+        }
+
+        for element in replaced {
+            if !element.is_detached() {
+                element.try_detach()?;
+            }
+        }
+        apply_renames(model, &renames, &HashMap::new());
+        Ok(())
+    }
+
+    /// Create a flattened copy of the model referenced by `submodel`, with deletions and
+    /// `replacements` applied and identifiers prefixed by the submodel identifier.
+    ///
+    /// The identifiers of `model` elements replaced by the `submodel` elements are added to
+    /// `renames` and the replaced elements to `replaced`.
+    fn instantiate(
+        &self,
+        submodel: &XmlElement,
+        replacements: &[XmlElement],
+        stack: &mut Vec<String>,
+        renames: &mut HashMap<String, String>,
+        replaced: &mut Vec<XmlElement>,
+    ) -> Result<XmlElement, String> {
+        let id = submodel
+            .get_namespaced_attribute("id", URL_PACKAGE_COMP)
+            .ok_or_else(|| "A <comp:submodel> is missing its [comp:id].".to_string())?;
+        let model_ref = submodel
+            .get_namespaced_attribute("modelRef", URL_PACKAGE_COMP)
+            .ok_or_else(|| {
+                format!("The <comp:submodel> ('{id}') is missing its [comp:modelRef].")
+            })?;
+        for factor in ["timeConversionFactor", "extentConversionFactor"] {
+            if submodel
+                .get_namespaced_attribute(factor, URL_PACKAGE_COMP)
+                .is_some()
+            {
+                return Err(format!(
+                    "The [comp:{factor}] of <comp:submodel> ('{id}') is not supported."
+                ));
+            }
+        }
+        if self.external.contains(&model_ref) {
+            return Err(format!(
+                "The <comp:submodel> ('{id}') references an external model definition \
+                ('{model_ref}'), which is not supported."
+            ));
+        }
+        let Some(definition) = self.definitions.get(&model_ref) else {
+            return Err(format!(
+                "The <comp:submodel> ('{id}') references an unknown model ('{model_ref}')."
+            ));
+        };
+        if stack.contains(&model_ref) {
+            return Err(format!(
+                "The model definition ('{model_ref}') is instantiated recursively."
+            ));
+        }
+
+        let instance = definition.import_into(definition.document());
+        stack.push(model_ref);
+        self.flatten_model(&instance, stack)?;
+        stack.pop();
+
+        let deletions = comp_children(submodel, "listOfDeletions")
+            .iter()
+            .flat_map(|list| comp_children(list, "deletion"))
+            .collect::<Vec<_>>();
+        for deletion in deletions {
+            let target = resolve(&instance, &deletion)
+                .map_err(|e| format!("Invalid deletion in <comp:submodel> ('{id}'): {e}"))?;
+            if !target.is_detached() {
+                target.try_detach()?;
+            }
+        }
+
+        let mut instance_renames = HashMap::new();
+        for replacement in replacements {
+            let submodel_ref =
+                replacement.get_namespaced_attribute("submodelRef", URL_PACKAGE_COMP);
+            if submodel_ref.as_deref() != Some(id.as_str())
+                || replacement
+                    .get_namespaced_attribute("deletion", URL_PACKAGE_COMP)
+                    .is_some()
+            {
+                continue;
+            }
+            if replacement
+                .get_namespaced_attribute("conversionFactor", URL_PACKAGE_COMP)
+                .is_some()
+            {
+                return Err(format!(
+                    "The [comp:conversionFactor] of <comp:replacedElement> is not supported \
+                    (in <comp:submodel> ('{id}'))."
+                ));
+            }
+            let target = resolve(&instance, replacement)
+                .map_err(|e| format!("Invalid replacement in <comp:submodel> ('{id}'): {e}"))?;
+
+            if replacement.tag_name() == "replacedElement" {
+                // The parent is `comp:listOfReplacedElements`, which belongs to the element.
+                let element = replacement.parent().and_then(|it| it.parent());
+                let new_id = element.and_then(|it| core_attribute(&it, "id"));
+                if let (Some(old_id), Some(new_id)) = (core_attribute(&target, "id"), new_id) {
+                    instance_renames.insert(old_id, new_id);
+                }
+                if !target.is_detached() {
+                    target.try_detach()?;
+                }
+            } else if let Some(element) = replacement.parent() {
+                if let (Some(old_id), Some(new_id)) = (
+                    core_attribute(&element, "id"),
+                    core_attribute(&target, "id"),
+                ) {
+                    renames.insert(old_id, format!("{id}{SEPARATOR}{new_id}"));
+                }
+                replaced.push(element);
+            }
+        }
+
+        let mut meta_renames = HashMap::new();
+        for element in instance.recursive_child_elements() {
+            if element.namespace_url() != URL_SBML_CORE {
+                continue;
+            }
+            if let Some(old_id) = core_attribute(&element, "id") {
+                // Local parameters are scoped by their kinetic law.
+                if element.tag_name() != "localParameter" {
+                    let new_id = format!("{id}{SEPARATOR}{old_id}");
+                    set_attribute(&element, "id", new_id.as_str());
+                    instance_renames.entry(old_id).or_insert(new_id);
+                }
+            }
+            if let Some(old_meta_id) = core_attribute(&element, "metaid") {
+                let new_meta_id = format!("{id}{SEPARATOR}{old_meta_id}");
+                set_attribute(&element, "metaid", new_meta_id.as_str());
+                meta_renames.insert(old_meta_id, new_meta_id);
+            }
+        }
+        apply_renames(&instance, &instance_renames, &meta_renames);
+
+        Ok(instance)
+    }
+}
+
+/// Find the element of the `model` referenced by the given `comp:SBaseRef` element (this
+/// includes `comp:port`, `comp:deletion` and the replacement elements).
+fn resolve(model: &XmlElement, reference: &XmlElement) -> Result<XmlElement, String> {
+    if !comp_children(reference, "sBaseRef").is_empty() {
+        return Err("Nested <comp:sBaseRef> references are not supported.".to_string());
+    }
+    if let Some(port_ref) = reference.get_namespaced_attribute("portRef", URL_PACKAGE_COMP) {
+        let port = comp_children(model, "listOfPorts")
+            .iter()
+            .flat_map(|list| comp_children(list, "port"))
+            .find(|it| {
+                it.get_namespaced_attribute("id", URL_PACKAGE_COMP) == Some(port_ref.clone())
+            })
+            .ok_or_else(|| format!("The <comp:port> ('{port_ref}') does not exist."))?;
+        return resolve(model, &port);
+    }
+
+    let references = [
+        ("idRef", "id", None),
+        ("unitRef", "id", Some("unitDefinition")),
+        ("metaIdRef", "metaid", None),
+    ];
+    for (name, attribute, tag_name) in references {
+        let Some(value) = reference.get_namespaced_attribute(name, URL_PACKAGE_COMP) else {
+            continue;
+        };
+        return model
+            .recursive_child_elements_filtered(|it| {
+                it.namespace_url() == URL_SBML_CORE
+                    && it.tag_name() != "localParameter"
+                    && tag_name.map(|tag| it.tag_name() == tag).unwrap_or(true)
+                    && core_attribute(it, attribute).as_deref() == Some(value.as_str())
+            })
+            .into_iter()
+            .next()
+            .ok_or_else(|| {
+                format!("The [comp:{name}] ('{value}') does not reference any element.")
+            });
+    }
+
+    Err(format!(
+        "The <comp:{}> does not reference any element.",
+        reference.tag_name()
+    ))
+}
+
+/// Move the content of the core lists of `instance` into the corresponding lists of `model`.
+/// Other content of `instance` is discarded.
+fn merge_lists(model: &XmlElement, instance: &XmlElement) -> Result<(), String> {
+    for list in instance.child_elements() {
+        let name = list.tag_name();
+        if list.namespace_url() != URL_SBML_CORE || !MODEL_LISTS.contains(&name.as_str()) {
+            continue;
+        }
+        let existing = model
+            .child_elements()
+            .into_iter()
+            .find(|it| it.tag_name() == name && it.namespace_url() == URL_SBML_CORE);
+        if let Some(existing) = existing {
+            for item in list.child_elements() {
+                item.try_detach()?;
+                item.try_attach_at(&existing, None)?;
+            }
+        } else {
+            list.try_detach()?;
+            list.try_attach_at(model, list_position(model, &name))?;
+        }
+    }
+    Ok(())
+}
+
+/// The position (among all child nodes of `model`) where a new list with the given `name`
+/// should be inserted, or `None` if it should be inserted as the last child.
+fn list_position(model: &XmlElement, name: &str) -> Option<usize> {
+    let index = MODEL_LISTS.iter().position(|it| *it == name)?;
+    let next = model.child_elements().into_iter().find(|it| {
+        it.namespace_url() == URL_SBML_CORE
+            && MODEL_LISTS[index + 1..].contains(&it.tag_name().as_str())
+    })?;
+    let doc = model.read_doc();
+    model
+        .raw_element()
+        .children(doc.deref())
+        .iter()
+        .position(|it| it.as_element() == Some(next.raw_element()))
+}
+
+/// Replace all references to the identifiers in `renames` (and meta identifiers in
+/// `meta_renames`) within the subtree of `root`.
+fn apply_renames(
+    root: &XmlElement,
+    renames: &HashMap<String, String>,
+    meta_renames: &HashMap<String, String>,
+) {
+    if renames.is_empty() && meta_renames.is_empty() {
+        return;
+    }
+    let elements = std::iter::once(root.clone()).chain(root.recursive_child_elements());
+    for element in elements {
+        let namespace = element.namespace_url();
+        if namespace == URL_SBML_CORE {
+            for name in REFERENCE_ATTRIBUTES {
+                let new_value = core_attribute(&element, name).and_then(|it| renames.get(&it));
+                if let Some(new_value) = new_value {
+                    set_attribute(&element, name, new_value);
+                }
+            }
+        } else if namespace == URL_MATHML && element.tag_name() == "ci" {
+            let name = element.text_content_trimmed();
+            if let Some(new_name) = renames.get(&name) {
+                if !shadowed_names(&element).contains(&name) {
+                    let mut doc = element.write_doc();
+                    element
+                        .raw_element()
+                        .set_text_content(doc.deref_mut(), new_name.as_str());
+                }
+            }
+        }
+
+        // The `units` of MathML numbers, the `rdf:about` references of annotations and
+        // the references of package elements are prefixed attributes.
+        for (full_name, value) in element.attributes() {
+            let (prefix, name) = Element::separate_prefix_name(full_name.as_str());
+            let attribute_namespace = if prefix.is_empty() {
+                None
+            } else {
+                let doc = element.read_doc();
+                element
+                    .raw_element()
+                    .namespace_for_prefix(doc.deref(), prefix)
+                    .map(|it| it.to_string())
+            };
+            let is_package_reference = attribute_namespace
+                .is_some_and(|url| PACKAGE_REFERENCE_ATTRIBUTES.contains(&(url.as_str(), name)));
+            let new_value = match name {
+                "units" if namespace == URL_MATHML => renames.get(&value).cloned(),
+                "about" => value
+                    .strip_prefix('#')
+                    .and_then(|it| meta_renames.get(it))
+                    .map(|it| format!("#{it}")),
+                _ if is_package_reference => renames.get(&value).cloned(),
+                _ => None,
+            };
+            if let Some(new_value) = new_value {
+                set_attribute(&element, full_name.as_str(), new_value.as_str());
+            }
+        }
+    }
+}
+
+/// Identifiers which are bound within the scope of the given MathML `ci` element, i.e.
+/// arguments of enclosing `lambda` elements and local parameters of an enclosing kinetic law.
+fn shadowed_names(ci: &XmlElement) -> HashSet<String> {
+    let mut names = HashSet::new();
+    for element in element_ancestors(ci) {
+        match element.tag_name().as_str() {
+            "lambda" => {
+                for bvar in element.child_elements() {
+                    if bvar.tag_name() == "bvar" {
+                        names.extend(
+                            bvar.child_elements()
+                                .iter()
+                                .map(|it| it.text_content_trimmed()),
+                        );
+                    }
+                }
+            }
+            "kineticLaw" => {
+                for list in element.child_elements() {
+                    if list.tag_name() == "listOfLocalParameters" {
+                        names.extend(
+                            list.child_elements()
+                                .iter()
+                                .filter_map(|it| core_attribute(it, "id")),
+                        );
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+    names
+}
+
+/// Remove all `comp` elements, attributes and namespace declarations from the subtree
+/// of `root`.
+fn strip_comp(root: &XmlElement) -> Result<(), String> {
+    let elements = root.recursive_child_elements_filtered(|it| {
+        it.namespace_url() == URL_PACKAGE_COMP
+            && it
+                .parent()
+                .map(|parent| parent.namespace_url() != URL_PACKAGE_COMP)
+                .unwrap_or(true)
+    });
+    for element in elements {
+        element.try_detach()?;
+    }
+
+    // Attributes need to be removed first, because their prefixes are resolved using
+    // the namespace declarations.
+    let elements = std::iter::once(root.clone())
+        .chain(root.recursive_child_elements())
+        .collect::<Vec<_>>();
+    let mut doc = root.write_doc();
+    for element in &elements {
+        let raw = element.raw_element();
+        let attributes = raw
+            .attributes(doc.deref())
+            .keys()
+            .filter(|full_name| {
+                let (prefix, _) = Element::separate_prefix_name(full_name.as_str());
+                !prefix.is_empty()
+                    && raw.namespace_for_prefix(doc.deref(), prefix) == Some(URL_PACKAGE_COMP)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        for name in attributes {
+            raw.mut_attributes(doc.deref_mut()).remove(&name);
+        }
+    }
+    for element in &elements {
+        element
+            .raw_element()
+            .mut_namespace_decls(doc.deref_mut())
+            .retain(|_, url| url != URL_PACKAGE_COMP);
+    }
+    Ok(())
+}
+
+/// Collect child elements of `parent` with the given `tag_name` in the comp namespace.
+fn comp_children(parent: &XmlElement, tag_name: &str) -> Vec<XmlElement> {
+    parent
+        .child_elements()
+        .into_iter()
+        .filter(|child| child.tag_name() == tag_name && child.namespace_url() == URL_PACKAGE_COMP)
+        .collect()
+}
+
+/// Read an unprefixed (i.e. core) attribute of `element`.
+fn core_attribute(element: &XmlElement, name: &str) -> Option<String> {
+    element.attributes().get(name).cloned()
+}
+
+/// Write an attribute of `element` given by its full name (including prefix).
+fn set_attribute(element: &XmlElement, name: &str, value: &str) {
+    let mut doc = element.write_doc();
+    element
+        .raw_element()
+        .set_attribute(doc.deref_mut(), name, value);
+}
@@ -0,0 +1,3 @@
+mod flatten;
+
+pub(crate) use flatten::flatten_document;
@@ -13,7 +13,7 @@ const XML_DEFAULT_HEADER: &str = formatcp!(
 );
 
 const SBML_DEFAULT_LEVEL: &str = "3";
-const SBML_DEFAULT_VERSION: &str = "1";
+const SBML_DEFAULT_VERSION: &str = "2";
 const SBML_DEFAULT_NAMESPACE: &str = URL_SBML_CORE;
 // const SBML_DEFAULT_NAMESPACE: &str = formatcp!(
 //     "http://www.sbml.org/sbml/level{}/version{}/core",
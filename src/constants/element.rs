@@ -1,3 +1,5 @@
+use crate::SbmlIssueSeverity;
+use crate::SbmlIssueSeverity::{Error, Warning};
 use phf::{phf_map, Map};
 
 macro_rules! extended_sbase_attributes {
@@ -70,6 +72,19 @@ pub const ATTRIBUTE_TYPES: Map<&str, Map<&str, &str>> = phf_map! {
     "trigger" => phf_map! { "initialValue" => "boolean", "persistent" => "boolean" },
 };
 
+/// The "double" attributes (see [ATTRIBUTE_TYPES]) for which non-finite values (`NaN`, `INF`,
+/// `-INF`), together with the severity of the reported issue. Such values are valid XML Schema
+/// doubles, but they are never meaningful for quantities like stoichiometries or compartment
+/// sizes (reported as errors), and rarely meaningful for parameter values (reported as warnings).
+pub const NON_FINITE_VALUE_ATTRIBUTES: Map<&str, &[(&str, SbmlIssueSeverity)]> = phf_map! {
+    "unit" => &[("exponent", Error), ("multiplier", Error)],
+    "compartment" => &[("spatialDimensions", Error), ("size", Error)],
+    "species" => &[("initialAmount", Error), ("initialConcentration", Error)],
+    "parameter" => &[("value", Warning)],
+    "speciesReference" => &[("stoichiometry", Error)],
+    "localParameter" => &[("value", Warning)],
+};
+
 pub const REQUIRED_ATTRIBUTES: Map<&str, &[&str]> = phf_map! {
     "sbml" => &["level", "version"],
     "model" => &[],
@@ -154,6 +169,18 @@ pub const ALLOWED_CHILDREN: Map<&str, &[&str]> = phf_map! {
     "eventAssignment" => extended_sbase_children!("math")
 };
 
+/// For each supported package (by its default prefix), this lists the child elements from the
+/// package namespace that are permitted in the SBML Core elements extended by the package.
+/// Core elements which are not listed do not permit any children from the package namespace.
+pub const PACKAGE_ALLOWED_CHILDREN: Map<&str, Map<&str, &[&str]>> = phf_map! {
+    "fbc" => phf_map! {
+        "model" => &["listOfObjectives", "listOfGeneProducts"],
+        "reaction" => &["geneProductAssociation"]
+    },
+    "layout" => phf_map! { "model" => &["listOfLayouts"] },
+    "qual" => phf_map! { "model" => &["listOfQualitativeSpecies", "listOfTransitions"] }
+};
+
 /// This lists the (optional) child elements that must be unique in each SBML Core element.
 ///
 /// For the most part, this is the same as [ALLOWED_CHILDREN], except for lists, where the item
@@ -220,8 +247,11 @@ pub const MATHML_ALLOWED_CHILDREN_BY_ATTR: Map<&str, &[&str]> = phf_map! {
     "units" => &["cn"]
 };
 
+/// The **definitionURL** of the *time* **csymbol**.
+pub const MATHML_TIME_DEFINITION_URL: &str = "http://www.sbml.org/sbml/symbols/time";
+
 pub const MATHML_ALLOWED_DEFINITION_URLS: &[&str] = &[
-    "http://www.sbml.org/sbml/symbols/time",
+    MATHML_TIME_DEFINITION_URL,
     "http://www.sbml.org/sbml/symbols/delay",
     "http://www.sbml.org/sbml/symbols/avogadro",
     "http://www.sbml.org/sbml/symbols/rateOf",
@@ -299,37 +329,35 @@ pub const MATHML_BINARY_OPERATORS: &[&str] = &[
     "outerproduct",
 ];
 
-/*
-
-   This is currently unused, but will become relevant once we start implementing
-   the MathML abstraction.
-
-   // source: https://www.w3.org/TR/MathML2/chapter4.html#contm.funopqual
-   pub const MATHML_NARY_OPERATORS: &[&str] = &[
-       "plus",
-       "times",
-       "max",
-       "min",
-       "gcd",
-       "lcm",
-       "mean",
-       "sdev",
-       "variance",
-       "median",
-       "mode",
-       "union",
-       "intersect",
-       "cartesianproduct",
-       "selector",
-       "and",
-       "or",
-       "xor",
-       "eq",
-       "neq",
-       "leq",
-       "lt",
-       "geq",
-       "gt",
-   ];
+// source: https://www.w3.org/TR/MathML2/chapter4.html#contm.funopqual
+pub const MATHML_NARY_OPERATORS: &[&str] = &[
+    "plus",
+    "times",
+    "max",
+    "min",
+    "gcd",
+    "lcm",
+    "mean",
+    "sdev",
+    "variance",
+    "median",
+    "mode",
+    "union",
+    "intersect",
+    "cartesianproduct",
+    "selector",
+    "and",
+    "or",
+    "xor",
+    "eq",
+    "neq",
+    "leq",
+    "lt",
+    "geq",
+    "gt",
+];
 
-*/
+// Operators (of the above) which produce a boolean value.
+pub const MATHML_BOOLEAN_OPERATORS: &[&str] = &[
+    "and", "or", "xor", "not", "implies", "eq", "neq", "leq", "lt", "geq", "gt",
+];
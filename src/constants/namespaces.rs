@@ -11,6 +11,24 @@ pub const URL_HTML: &str = "http://www.w3.org/1999/xhtml";
 /// The URL of the MathML namespace.
 pub const URL_MATHML: &str = "http://www.w3.org/1998/Math/MathML";
 
+/// The URL of the RDF syntax namespace (used by MIRIAM annotations).
+pub const URL_RDF: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+
+/// The URL of the SBML "flux balance constraints" (fbc) package, version 2.
+pub const URL_PACKAGE_FBC: &str = "http://www.sbml.org/sbml/level3/version1/fbc/version2";
+
+/// The URL of the SBML "qualitative models" (qual) package, version 1.
+pub const URL_PACKAGE_QUAL: &str = "http://www.sbml.org/sbml/level3/version1/qual/version1";
+
+/// The URL of the SBML "hierarchical model composition" (comp) package, version 1.
+pub const URL_PACKAGE_COMP: &str = "http://www.sbml.org/sbml/level3/version1/comp/version1";
+
+/// The URL of the SBML "layout" package, version 1.
+pub const URL_PACKAGE_LAYOUT: &str = "http://www.sbml.org/sbml/level3/version1/layout/version1";
+
+/// The URL of the SBML "distributions" (distrib) package, version 1.
+pub const URL_PACKAGE_DISTRIB: &str = "http://www.sbml.org/sbml/level3/version1/distrib/version1";
+
 /// The URL of the "default" empty namespace.
 #[cfg(test)]
 pub const URL_EMPTY: &str = "";
@@ -0,0 +1,174 @@
+use crate::constants::namespaces::{URL_PACKAGE_LAYOUT, URL_SBML_CORE};
+use biodivine_xml_doc::{Document, Element, Node};
+
+/// Lists in which the order of the items is significant, and hence must not be sorted,
+/// identified by their namespace and local name. The core namespace stands for the SBML core
+/// namespace of any level and version (see [is_sbml_core_namespace]).
+const ORDERED_LISTS: &[(&str, &str)] = &[
+    (URL_SBML_CORE, "listOfRules"),
+    (URL_PACKAGE_LAYOUT, "listOfCurveSegments"),
+];
+
+/// Write the canonical form of the given `element` (see [crate::Sbml::canonical_form]).
+///
+/// The tree is traversed using an explicit stack, such that deeply nested elements (e.g. in
+/// `math`) cannot overflow the call stack.
+pub(crate) fn canonical_form(doc: &Document, element: Element) -> String {
+    let mut stack = vec![Frame::new(doc, element, 0)];
+    loop {
+        let frame = stack.last_mut().expect("The stack is never empty here.");
+        if let Some(child) = frame.next_child(doc) {
+            let depth = frame.depth + 1;
+            stack.push(Frame::new(doc, child, depth));
+            continue;
+        }
+        let frame = stack.pop().expect("The stack is never empty here.");
+        let id = local_id(doc, frame.element);
+        let lines = frame.finish(doc);
+        match stack.last_mut() {
+            Some(parent) => parent.children.push((id, lines)),
+            None => return lines.join("\n"),
+        }
+    }
+}
+
+/// An element of the canonical form which is being written, together with the canonical
+/// forms of its already processed children.
+struct Frame {
+    element: Element,
+    depth: usize,
+    /// The index of the next child node to process.
+    next: usize,
+    /// The lines of the canonical forms of the processed children, each with the `id` of the
+    /// child element. The lines are only joined once the whole document is processed, such that
+    /// the content of deeply nested elements is not copied at every level.
+    children: Vec<(Option<String>, Vec<String>)>,
+}
+
+impl Frame {
+    fn new(doc: &Document, element: Element, depth: usize) -> Frame {
+        Frame {
+            element,
+            depth,
+            next: 0,
+            children: Vec::with_capacity(element.children(doc).len()),
+        }
+    }
+
+    /// Process the text child nodes up to the next child element, which is returned (or `None`
+    /// once all children are processed).
+    fn next_child(&mut self, doc: &Document) -> Option<Element> {
+        let nodes = self.element.children(doc);
+        while let Some(node) = nodes.get(self.next) {
+            self.next += 1;
+            match node {
+                Node::Element(child) => return Some(*child),
+                Node::Text(text) | Node::CData(text) => {
+                    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                    if !text.is_empty() {
+                        let indent = "  ".repeat(self.depth + 1);
+                        self.children
+                            .push((None, vec![format!("{indent}{}", escape(&text))]));
+                    }
+                }
+                // Comments, processing instructions and document types carry no SBML semantics.
+                _ => (),
+            }
+        }
+        None
+    }
+
+    /// Write the lines of the canonical form of this element, once all children are processed.
+    fn finish(self, doc: &Document) -> Vec<String> {
+        let Frame {
+            element,
+            depth,
+            mut children,
+            ..
+        } = self;
+        let indent = "  ".repeat(depth);
+        let (_, local_name) = element.prefix_name(doc);
+        let namespace = element.namespace(doc).unwrap_or("");
+        let name = qualified_name(namespace, local_name);
+
+        let mut attributes = element
+            .attributes(doc)
+            .iter()
+            .map(|(full_name, value)| {
+                let (prefix, name) = Element::separate_prefix_name(full_name);
+                // Unprefixed attributes have no namespace (they do not inherit the element
+                // namespace).
+                let namespace = if prefix.is_empty() {
+                    ""
+                } else {
+                    element.namespace_for_prefix(doc, prefix).unwrap_or(prefix)
+                };
+                format!(" {}=\"{}\"", qualified_name(namespace, name), escape(value))
+            })
+            .collect::<Vec<_>>();
+        attributes.sort();
+        let attributes = attributes.concat();
+
+        let list_namespace = if is_sbml_core_namespace(namespace) {
+            URL_SBML_CORE
+        } else {
+            namespace
+        };
+        let is_ordered = ORDERED_LISTS.contains(&(list_namespace, local_name));
+        if local_name.starts_with("listOf") && !is_ordered {
+            // Items are sorted by their id. Items without an id are sorted by their canonical
+            // form.
+            children.sort();
+        }
+
+        if children.is_empty() {
+            vec![format!("{indent}<{name}{attributes}/>")]
+        } else {
+            let mut lines = vec![format!("{indent}<{name}{attributes}>")];
+            lines.extend(children.into_iter().flat_map(|(_, it)| it));
+            lines.push(format!("{indent}</{name}>"));
+            lines
+        }
+    }
+}
+
+/// Check whether `namespace` is the SBML core namespace of any level and version
+/// (e.g. `http://www.sbml.org/sbml/level2/version4`), as opposed to a package namespace.
+fn is_sbml_core_namespace(namespace: &str) -> bool {
+    namespace
+        .strip_prefix("http://www.sbml.org/sbml/level")
+        .is_some_and(|rest| rest.ends_with("/core") || !rest.starts_with('3'))
+}
+
+/// Elements and attributes of SBML core (or without a namespace) use their plain name,
+/// other names are qualified using the namespace URL (e.g. `{http://...}listOfLayouts`),
+/// such that the result does not depend on the chosen prefixes.
+fn qualified_name(namespace: &str, name: &str) -> String {
+    if namespace.is_empty() || namespace == URL_SBML_CORE {
+        name.to_string()
+    } else {
+        format!("{{{namespace}}}{name}")
+    }
+}
+
+/// The `id` of an element, regardless of its namespace (e.g. `id`, or `fbc:id`). The plain
+/// `id` takes precedence, otherwise the lexicographically first namespaced `id` is used.
+fn local_id(doc: &Document, element: Element) -> Option<String> {
+    let attributes = element.attributes(doc);
+    if let Some(id) = attributes.get("id") {
+        return Some(id.clone());
+    }
+    attributes
+        .iter()
+        .filter(|(full_name, _)| Element::separate_prefix_name(full_name).1 == "id")
+        .map(|(_, value)| value.clone())
+        .min()
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
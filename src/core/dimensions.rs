@@ -0,0 +1,226 @@
+use crate::constants::namespaces::URL_SBML_CORE;
+use crate::core::{BaseUnit, KineticLaw, Math, Model, SBase};
+use crate::xml::{
+    OptionalXmlChild, OptionalXmlProperty, RequiredXmlProperty, XmlElement, XmlProperty, XmlWrapper,
+};
+use std::str::FromStr;
+
+/// Exponents of the seven SI base dimensions (see [BaseUnit::si_dimensions]). The exponents
+/// are real numbers, since the *exponent* of a [Unit](crate::core::Unit) can be fractional.
+pub(crate) type Dimensions = [f64; 7];
+
+const DIMENSION_NAMES: [&str; 7] = [
+    "kilogram", "metre", "second", "ampere", "kelvin", "mole", "candela",
+];
+
+/// Returns `true` if the two dimension vectors are equal (up to rounding errors).
+pub(crate) fn same_dimensions(a: &Dimensions, b: &Dimensions) -> bool {
+    a.iter().zip(b.iter()).all(|(x, y)| (x - y).abs() < 1e-9)
+}
+
+/// A human-readable representation of the given dimensions (e.g. `second^-1 * mole`).
+pub(crate) fn format_dimensions(dimensions: &Dimensions) -> String {
+    let parts = DIMENSION_NAMES
+        .iter()
+        .zip(dimensions.iter())
+        .filter(|(_, exponent)| exponent.abs() >= 1e-9)
+        .map(|(name, exponent)| {
+            if (exponent - 1.0).abs() < 1e-9 {
+                name.to_string()
+            } else {
+                format!("{name}^{exponent}")
+            }
+        })
+        .collect::<Vec<_>>();
+    if parts.is_empty() {
+        "dimensionless".to_string()
+    } else {
+        parts.join(" * ")
+    }
+}
+
+/// Computes `a + factor * b`, i.e. the dimensions of `a * b^factor`.
+pub(crate) fn combine(a: &Dimensions, b: &Dimensions, factor: f64) -> Dimensions {
+    let mut result = *a;
+    for (x, y) in result.iter_mut().zip(b.iter()) {
+        *x += factor * y;
+    }
+    result
+}
+
+impl Model {
+    /// Dimensions of the unit with the given identifier, which is either a [BaseUnit]
+    /// or the *id* of a [UnitDefinition](crate::core::UnitDefinition).
+    ///
+    /// Returns `None` if the unit cannot be resolved or one of its [Unit](crate::core::Unit)
+    /// elements is invalid. Note that the *scale* and *multiplier* of units are ignored.
+    pub(crate) fn unit_dimensions(&self, unit: &str) -> Option<Dimensions> {
+        if let Ok(base) = BaseUnit::from_str(unit) {
+            return Some(base.si_dimensions().map(f64::from));
+        }
+        let definition = self
+            .unit_definitions()
+            .get()?
+            .iter()
+            .find(|it| it.id().get().is_some_and(|id| id == unit))?;
+        let mut result = [0.0; 7];
+        for unit in definition.units().get()?.iter() {
+            let kind = unit.kind().get_checked().ok()??;
+            let exponent = unit.exponent().get_checked().ok()??;
+            result = combine(&result, &kind.si_dimensions().map(f64::from), exponent);
+        }
+        Some(result)
+    }
+
+    /// Dimensions of the value represented by the given symbol in a math expression.
+    ///
+    /// Local parameters of the `kinetic_law` take precedence over model symbols. Returns
+    /// `None` when the symbol is unknown or its units are not declared.
+    fn symbol_dimensions(
+        &self,
+        kinetic_law: Option<&KineticLaw>,
+        symbol: &str,
+    ) -> Option<Dimensions> {
+        let local_parameter = kinetic_law
+            .and_then(|it| it.local_parameters().get())
+            .and_then(|list| list.iter().find(|it| it.id().get() == symbol));
+        if let Some(local_parameter) = local_parameter {
+            return self.unit_dimensions(local_parameter.units().get()?.as_str());
+        }
+        if let Some(parameter) = self.find_parameter(symbol) {
+            return self.unit_dimensions(parameter.units().get()?.as_str());
+        }
+        if let Some(compartment) = self.find_compartment(symbol) {
+            return self.compartment_dimensions(&compartment.id().get());
+        }
+        if let Some(species) = self.find_species(symbol) {
+            let substance = match species.substance_units().get() {
+                Some(units) => self.unit_dimensions(units.as_str())?,
+                None => self.unit_dimensions(self.substance_units().get()?.as_str())?,
+            };
+            if species.has_only_substance_units().get_checked().ok()?? {
+                return Some(substance);
+            }
+            let compartment = self.compartment_dimensions(&species.compartment().get())?;
+            return Some(combine(&substance, &compartment, -1.0));
+        }
+        if self.find_species_reference(symbol).is_some() {
+            return Some([0.0; 7]);
+        }
+        if self
+            .reactions()
+            .get()
+            .is_some_and(|list| list.iter().any(|it| it.id().get() == symbol))
+        {
+            let extent = self.unit_dimensions(self.extent_units().get()?.as_str())?;
+            let time = self.unit_dimensions(self.time_units().get()?.as_str())?;
+            return Some(combine(&extent, &time, -1.0));
+        }
+        None
+    }
+
+    /// Dimensions of the compartment with the given *id*, using the model defaults
+    /// (based on *spatialDimensions*) when the compartment does not declare its *units*.
+    fn compartment_dimensions(&self, id: &str) -> Option<Dimensions> {
+        let compartment = self.find_compartment(id)?;
+        if let Some(units) = compartment.units().get() {
+            return self.unit_dimensions(units.as_str());
+        }
+        let spatial_dimensions = compartment.spatial_dimensions().get_checked().ok()??;
+        if spatial_dimensions == 3.0 {
+            self.unit_dimensions(self.volume_units().get()?.as_str())
+        } else if spatial_dimensions == 2.0 {
+            self.unit_dimensions(self.area_units().get()?.as_str())
+        } else if spatial_dimensions == 1.0 {
+            self.unit_dimensions(self.length_units().get()?.as_str())
+        } else {
+            None
+        }
+    }
+}
+
+impl Math {
+    /// Infer the dimensions of this [Math] element in the context of the given `model`.
+    ///
+    /// The inference is intentionally conservative: it only supports identifiers, numbers with
+    /// an explicit `sbml:units` attribute, the `time` csymbol, and the `plus`, `minus`, `times`,
+    /// `divide` and `power` operators (the latter only with a literal exponent). If any part
+    /// of the expression is not supported or its units are not declared, the result is `None`.
+    pub(crate) fn infer_dimensions(&self, model: &Model) -> Option<Dimensions> {
+        let kinetic_law = KineticLaw::for_child_element(self.xml_element());
+        let expression = self.xml_element().child_elements().into_iter().next()?;
+        expression_dimensions(model, kinetic_law.as_ref(), &expression)
+    }
+}
+
+fn expression_dimensions(
+    model: &Model,
+    kinetic_law: Option<&KineticLaw>,
+    expression: &XmlElement,
+) -> Option<Dimensions> {
+    match expression.tag_name().as_str() {
+        "ci" => model.symbol_dimensions(kinetic_law, expression.text_content_trimmed().as_str()),
+        "cn" => {
+            let units = expression.get_namespaced_attribute("units", URL_SBML_CORE)?;
+            model.unit_dimensions(units.as_str())
+        }
+        "csymbol" => {
+            let url = expression.get_attribute("definitionURL")?;
+            if url == "http://www.sbml.org/sbml/symbols/time" {
+                model.unit_dimensions(model.time_units().get()?.as_str())
+            } else {
+                None
+            }
+        }
+        "apply" => {
+            let children = expression.child_elements();
+            let (operator, arguments) = children.split_first()?;
+            let arguments = arguments
+                .iter()
+                .map(|it| expression_dimensions(model, kinetic_law, it));
+            match operator.tag_name().as_str() {
+                "times" => arguments
+                    .collect::<Option<Vec<_>>>()?
+                    .iter()
+                    .try_fold([0.0; 7], |acc, it| Some(combine(&acc, it, 1.0))),
+                "divide" => {
+                    let arguments = arguments.collect::<Option<Vec<_>>>()?;
+                    let [numerator, denominator] = arguments.as_slice() else {
+                        return None;
+                    };
+                    Some(combine(numerator, denominator, -1.0))
+                }
+                "plus" | "minus" => {
+                    let arguments = arguments.collect::<Option<Vec<_>>>()?;
+                    let first = *arguments.first()?;
+                    if arguments.iter().all(|it| same_dimensions(it, &first)) {
+                        Some(first)
+                    } else {
+                        None
+                    }
+                }
+                "power" => {
+                    let [base, exponent] = children.get(1..)? else {
+                        return None;
+                    };
+                    let base = expression_dimensions(model, kinetic_law, base)?;
+                    let exponent = literal_value(exponent)?;
+                    Some(base.map(|it| it * exponent))
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The value of a plain numeric `cn` element (i.e. not a rational or e-notation number).
+fn literal_value(element: &XmlElement) -> Option<f64> {
+    if element.tag_name() != "cn" {
+        return None;
+    }
+    match element.get_attribute("type").as_deref() {
+        None | Some("real") | Some("integer") => element.text_as_f64(),
+        _ => None,
+    }
+}
@@ -1,8 +1,8 @@
 use crate::core::sbase::SbmlUtils;
 use crate::core::Math;
 use crate::xml::{
-    OptionalChild, RequiredProperty, RequiredXmlProperty, XmlDefault, XmlDocument, XmlElement,
-    XmlList,
+    OptionalChild, OptionalXmlChild, RequiredProperty, RequiredXmlProperty, XmlDefault,
+    XmlDocument, XmlElement, XmlList, XmlWrapper,
 };
 use sbml_macros::{SBase, XmlWrapper};
 
@@ -42,6 +42,30 @@ impl Event {
     pub fn event_assignments(&self) -> OptionalChild<XmlList<EventAssignment>> {
         self.optional_sbml_child("listOfEventAssignments")
     }
+
+    /// Returns the identifiers referenced (using **ci**) by the math of the [Trigger]
+    /// of this event, without duplicates and in document order.
+    pub fn trigger_variables(&self) -> Vec<String> {
+        let Some(math) = self.trigger().get().and_then(|it| it.math().get()) else {
+            return Vec::new();
+        };
+        let mut result = Vec::new();
+        for ci in math.recursive_child_elements_filtered(|it| it.tag_name() == "ci") {
+            let value = ci.text_content_trimmed();
+            if !result.contains(&value) {
+                result.push(value);
+            }
+        }
+        result
+    }
+
+    /// Returns the *variable* of every [EventAssignment] of this event, in document order.
+    pub fn assigned_variables(&self) -> Vec<String> {
+        self.event_assignments()
+            .get()
+            .map(|list| list.iter().map(|it| it.variable().get()).collect())
+            .unwrap_or_default()
+    }
 }
 
 #[derive(Clone, Debug, XmlWrapper, SBase)]
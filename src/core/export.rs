@@ -0,0 +1,72 @@
+use crate::core::{Model, SBase};
+use crate::xml::{OptionalXmlChild, OptionalXmlProperty, XmlProperty};
+
+/// Header shared by all tables produced by this module.
+const TABLE_HEADER: &str = "id,name,compartment,initial_value,units,constant";
+
+/// Methods for exporting parts of a [Model] in a tabular (CSV) format.
+impl Model {
+    /// Export the species of this model as a CSV table with columns `id`, `name`,
+    /// `compartment`, `initial_value`, `units` and `constant`.
+    ///
+    /// The initial value is either the *initialAmount* or the *initialConcentration* of
+    /// the species (whichever is set), written exactly as it appears in the document.
+    /// Missing values are exported as empty cells.
+    pub fn species_table_csv(&self) -> String {
+        let mut rows = vec![TABLE_HEADER.to_string()];
+        if let Some(species) = self.species().get() {
+            for species in species.iter() {
+                let initial_value = species
+                    .initial_amount()
+                    .get_raw()
+                    .or_else(|| species.initial_concentration().get_raw());
+                rows.push(csv_row(&[
+                    species.id().get_raw(),
+                    species.name().get(),
+                    species.compartment().get_raw(),
+                    initial_value,
+                    species.substance_units().get(),
+                    species.constant().get_raw(),
+                ]));
+            }
+        }
+        rows.join("\n") + "\n"
+    }
+
+    /// Export the parameters of this model as a CSV table with the same columns as
+    /// [Model::species_table_csv]. The `compartment` column is always empty.
+    pub fn parameter_table_csv(&self) -> String {
+        let mut rows = vec![TABLE_HEADER.to_string()];
+        if let Some(parameters) = self.parameters().get() {
+            for parameter in parameters.iter() {
+                rows.push(csv_row(&[
+                    parameter.id().get_raw(),
+                    parameter.name().get(),
+                    None,
+                    parameter.value().get_raw(),
+                    parameter.units().get(),
+                    parameter.constant().get_raw(),
+                ]));
+            }
+        }
+        rows.join("\n") + "\n"
+    }
+}
+
+/// Format a single CSV row, escaping the individual cells as necessary.
+fn csv_row(cells: &[Option<String>]) -> String {
+    cells
+        .iter()
+        .map(|cell| csv_escape(cell.as_deref().unwrap_or("")))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Quote a CSV cell if it contains a separator, quote, or line break (RFC 4180).
+fn csv_escape(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
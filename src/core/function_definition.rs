@@ -1,6 +1,8 @@
 use crate::core::sbase::SbmlUtils;
 use crate::core::Math;
-use crate::xml::{OptionalChild, XmlDefault, XmlDocument, XmlElement};
+use crate::xml::{
+    OptionalChild, OptionalXmlChild, XmlDefault, XmlDocument, XmlElement, XmlWrapper,
+};
 use sbml_macros::{SBase, XmlWrapper};
 
 /// Individual function definition
@@ -19,6 +21,49 @@ impl FunctionDefinition {
     pub fn math(&self) -> OptionalChild<Math> {
         self.optional_math_child("math")
     }
+
+    /// Returns the names of the arguments of this function, i.e. the values of the **ci**
+    /// elements inside the **bvar** elements of the **lambda**, in declaration order.
+    ///
+    /// Returns an empty vector if the function has no **lambda**.
+    pub fn argument_names(&self) -> Vec<String> {
+        let Some(lambda) = self.lambda() else {
+            return Vec::new();
+        };
+        lambda
+            .child_elements_filtered(|it| it.tag_name() == "bvar")
+            .into_iter()
+            .filter_map(|bvar| bvar.get_child_at(0))
+            .map(|ci| ci.text_content_trimmed())
+            .collect()
+    }
+
+    /// Returns the body of this function, i.e. the first child of the **lambda** element
+    /// which is not a **bvar**.
+    ///
+    /// The body is an arbitrary MathML expression, hence it is returned as a raw [XmlElement]
+    /// rather than as [Math] (which always represents the enclosing **math** element).
+    pub fn body(&self) -> Option<XmlElement> {
+        self.lambda()?
+            .child_elements()
+            .into_iter()
+            .find(|it| it.tag_name() != "bvar")
+    }
+
+    /// Find the **lambda** element of this function. The **lambda** is either an immediate
+    /// child of **math**, or it is wrapped in a **semantics** element.
+    fn lambda(&self) -> Option<XmlElement> {
+        let math = self.math().get()?;
+        let mut child = math.get_child_at(0)?;
+        if child.tag_name() == "semantics" {
+            child = child.get_child_at(0)?;
+        }
+        if child.tag_name() == "lambda" {
+            Some(child)
+        } else {
+            None
+        }
+    }
 }
 
 impl XmlDefault for FunctionDefinition {
@@ -12,3 +12,28 @@ impl XmlDefault for Math {
         unsafe { Math::unchecked_cast(XmlElement::new_quantified(document, "math", NS_MATHML)) }
     }
 }
+
+impl Math {
+    /// The maximal nesting depth of MathML content (see [Math::depth]) accepted by
+    /// [Sbml::validate](crate::Sbml::validate). Use
+    /// [Sbml::validate_with_max_math_depth](crate::Sbml::validate_with_max_math_depth)
+    /// to validate with a different limit.
+    pub const DEFAULT_MAX_DEPTH: usize = 1024;
+
+    /// Compute the nesting depth of the MathML content of this element. A `math` element with
+    /// no children has depth zero, a `math` element with a single `ci` has depth one, etc.
+    ///
+    /// The computation is iterative, so it is safe to use on arbitrarily deep input.
+    pub fn depth(&self) -> usize {
+        let doc = self.read_doc();
+        let mut max_depth = 0;
+        let mut stack = vec![(self.raw_element(), 0usize)];
+        while let Some((element, depth)) = stack.pop() {
+            max_depth = max_depth.max(depth);
+            for child in element.child_elements(&doc) {
+                stack.push((child, depth + 1));
+            }
+        }
+        max_depth
+    }
+}
@@ -1,18 +1,25 @@
+pub(crate) mod canonical;
 mod compartment;
 mod constraint;
+mod dimensions;
 mod event;
+mod export;
 mod function_definition;
 mod initial_assignment;
 mod math;
 mod model;
+mod ode;
 mod parameter;
 mod reaction;
 mod rule;
-mod sbase;
+pub(crate) mod sbase;
+#[cfg(feature = "sbo_terms")]
+mod sbo_terms;
 mod species;
 mod unit;
 mod unit_definition;
 pub(crate) mod validation;
+mod visitor;
 
 pub use compartment::Compartment;
 pub use constraint::Constraint;
@@ -21,13 +28,16 @@ pub use function_definition::FunctionDefinition;
 pub use initial_assignment::InitialAssignment;
 pub use math::Math;
 pub use model::Model;
+pub use ode::OdeSystem;
 pub use parameter::Parameter;
 pub use reaction::{
     KineticLaw, LocalParameter, ModifierSpeciesReference, Reaction, SimpleSpeciesReference,
     SpeciesReference,
 };
 pub use rule::{AbstractRule, AlgebraicRule, AssignmentRule, RateRule, Rule, RuleTypes};
-pub use sbase::SBase;
-pub use species::Species;
+pub use sbase::{MetaId, SBase, SId, SboTerm};
+pub use species::{QuantityKind, Species};
 pub use unit::{BaseUnit, Unit};
 pub use unit_definition::UnitDefinition;
+pub use validation::ValidateSubtree;
+pub use visitor::SbmlVisitor;
@@ -1,18 +1,21 @@
+use std::collections::HashMap;
 use std::ops::Deref;
 
 use embed_doc_image::embed_doc_image;
 use sbml_macros::{SBase, XmlWrapper};
 
+use crate::constants::namespaces::{URL_MATHML, URL_PACKAGE_FBC};
 use crate::core::sbase::SbmlUtils;
 use crate::core::{
     AbstractRule, AlgebraicRule, AssignmentRule, Compartment, Constraint, Event,
-    FunctionDefinition, InitialAssignment, Parameter, Reaction, Rule, SBase, Species,
-    UnitDefinition,
+    FunctionDefinition, InitialAssignment, KineticLaw, Parameter, Reaction, Rule, RuleTypes, SBase,
+    SId, Species, SpeciesReference, UnitDefinition,
 };
 use crate::xml::{
     OptionalChild, OptionalProperty, OptionalXmlChild, OptionalXmlProperty, RequiredXmlProperty,
-    XmlDefault, XmlDocument, XmlElement, XmlList, XmlSupertype, XmlWrapper,
+    XmlDefault, XmlDocument, XmlElement, XmlList, XmlPropertyType, XmlSupertype, XmlWrapper,
 };
+use crate::Sbml;
 
 /// The SBML model object
 /// (Section 4.2; [specification](https://raw.githubusercontent.com/combine-org/combine-specifications/main/specifications/files/sbml.level-3.version-2.core.release-2.pdf)).
@@ -312,17 +315,186 @@ impl Model {
         Self::search_in_parents(child, "model")
     }
 
+    /// Copy this model (including all its content) into the `target` document, replacing
+    /// the current model of `target` (if any). Returns the newly created model.
+    ///
+    /// See [XmlElement::import_into] for details on how the content is copied.
+    pub fn clone_into(&self, target: &Sbml) -> Model {
+        let element = self.xml_element().import_into(target.document());
+        let model = unsafe { Model::unchecked_cast(element) };
+        target.model().set(model.clone());
+        model
+    }
+
+    /// Returns the value of the `fbc:strict` attribute of this model, or `None` if the attribute
+    /// is missing (or is not a valid boolean).
+    ///
+    /// The attribute is only recognized if its prefix resolves to the fbc version 2 namespace
+    /// (`http://www.sbml.org/sbml/level3/version1/fbc/version2`). In strict mode, every [Reaction] must declare flux bounds which
+    /// reference constant parameters with a defined value.
+    pub fn fbc_strict(&self) -> Option<bool> {
+        let value = self.get_namespaced_attribute("strict", URL_PACKAGE_FBC)?;
+        bool::try_get(Some(value.as_str())).ok().flatten()
+    }
+
+    /// Returns the `fbc:listOfObjectives` child element of this model, if present.
+    ///
+    /// The fbc package is not modelled by dedicated types yet, hence the list is returned
+    /// as a raw [XmlElement].
+    pub fn fbc_objectives(&self) -> Option<XmlElement> {
+        self.child_elements().into_iter().find(|child| {
+            child.tag_name() == "listOfObjectives" && child.namespace_url() == URL_PACKAGE_FBC
+        })
+    }
+
+    /// Returns the value of the `fbc:activeObjective` attribute of the `fbc:listOfObjectives`
+    /// element, or `None` if either is missing.
+    pub fn fbc_active_objective(&self) -> Option<String> {
+        self.fbc_objectives()?
+            .get_namespaced_attribute("activeObjective", URL_PACKAGE_FBC)
+    }
+
+    /// Collect every MathML **ci** element in this model whose identifier is not declared in
+    /// its scope, together with the offending identifier.
+    ///
+    /// A **ci** that is the first child of an **apply** must reference a [FunctionDefinition].
+    /// Any other **ci** may reference a [Species], [Compartment], [Parameter],
+    /// [SpeciesReference](crate::core::SpeciesReference) or [Reaction], a
+    /// [LocalParameter](crate::core::LocalParameter) of the enclosing
+    /// [KineticLaw](crate::core::KineticLaw), or a **bvar** of an enclosing **lambda**.
+    ///
+    /// This is the model-wide counterpart of rules 10214 and 10215, intended for tools which
+    /// want to offer a fix (e.g. declaring a missing parameter) rather than a list of issues.
+    pub fn undeclared_symbols(&self) -> Vec<(XmlElement, String)> {
+        let functions = self.function_definition_identifiers();
+        let globals = [
+            self.species_identifiers(),
+            self.compartment_identifiers(),
+            self.parameter_identifiers(),
+            self.species_reference_identifiers(),
+            self.reaction_identifiers(),
+        ]
+        .concat();
+
+        let math_elements = self.recursive_child_elements_filtered(|child| {
+            child.tag_name() == "math" && child.namespace_url() == URL_MATHML
+        });
+
+        let mut result = Vec::new();
+        for math in math_elements {
+            let locals = KineticLaw::for_child_element(&math)
+                .map(|law| law.local_parameter_identifiers())
+                .unwrap_or_default();
+            let b_variables = math
+                .recursive_child_elements_filtered(|child| child.tag_name() == "bvar")
+                .into_iter()
+                .filter_map(|bvar| bvar.get_child_at(0))
+                .map(|ci| ci.text_content_trimmed())
+                .collect::<Vec<_>>();
+
+            for ci in math.recursive_child_elements_filtered(|child| child.tag_name() == "ci") {
+                let parent = ci.parent().unwrap();
+                if parent.tag_name() == "bvar" {
+                    // This is a declaration, not a reference.
+                    continue;
+                }
+                let value = ci.text_content_trimmed();
+                let is_function_call = parent.tag_name() == "apply"
+                    && parent.get_child_at(0).map(|it| it.raw_element()) == Some(ci.raw_element());
+                let is_declared = if is_function_call {
+                    functions.contains(&value)
+                } else {
+                    globals.contains(&value)
+                        || locals.contains(&value)
+                        || b_variables.contains(&value)
+                };
+                if !is_declared {
+                    result.push((ci, value));
+                }
+            }
+        }
+        result
+    }
+
+    /// Returns the [AssignmentRule] objects of this model sorted such that every rule appears
+    /// after all the rules that determine the variables used in its math.
+    ///
+    /// Rules without mutual dependencies keep their document order. If the rules contain
+    /// a dependency cycle (which is forbidden by rule 10906), an error describing the cycle
+    /// is returned instead.
+    pub fn assignment_rule_evaluation_order(&self) -> Result<Vec<AssignmentRule>, String> {
+        let Some(rules) = self.rules().get() else {
+            return Ok(Vec::new());
+        };
+        let rules = rules
+            .iter()
+            .filter_map(|rule| rule.try_downcast::<AssignmentRule>())
+            .collect::<Vec<_>>();
+        let variables = rules
+            .iter()
+            .map(|rule| rule.variable().get())
+            .collect::<Vec<_>>();
+
+        // For every rule, the indices of the rules it depends on.
+        let dependencies = rules
+            .iter()
+            .map(|rule| {
+                let Some(math) = rule.math().get() else {
+                    return Vec::new();
+                };
+                let mut result = math
+                    .recursive_child_elements_filtered(|it| it.tag_name() == "ci")
+                    .into_iter()
+                    .filter_map(|ci| {
+                        let value = ci.text_content_trimmed();
+                        variables.iter().position(|it| *it == value)
+                    })
+                    .collect::<Vec<_>>();
+                result.sort();
+                result.dedup();
+                result
+            })
+            .collect::<Vec<_>>();
+
+        let mut order = Vec::with_capacity(rules.len());
+        let mut done = vec![false; rules.len()];
+        while order.len() < rules.len() {
+            // Pick the first rule (in document order) whose dependencies are all resolved.
+            let next =
+                (0..rules.len()).find(|i| !done[*i] && dependencies[*i].iter().all(|d| done[*d]));
+            let Some(next) = next else {
+                // Every remaining rule depends on another remaining rule, hence there is
+                // a cycle. We reconstruct it by following unresolved dependencies.
+                let mut cycle = Vec::new();
+                let mut current = (0..rules.len()).find(|i| !done[*i]).unwrap();
+                while !cycle.contains(&current) {
+                    cycle.push(current);
+                    current = *dependencies[current].iter().find(|d| !done[**d]).unwrap();
+                }
+                let start = cycle.iter().position(|it| *it == current).unwrap();
+                let mut names = cycle[start..]
+                    .iter()
+                    .map(|i| variables[*i].as_str())
+                    .collect::<Vec<_>>();
+                names.push(variables[current].as_str());
+                return Err(format!(
+                    "Assignment rules contain a dependency cycle: {}.",
+                    names.join(" -> ")
+                ));
+            };
+            done[next] = true;
+            order.push(rules[next].clone());
+        }
+        Ok(order)
+    }
+
     /// Returns a vector of [FunctionDefinition] identifiers (attribute **id**). Function definitions
     /// without IDs are not included in the output.
     pub(crate) fn function_definition_identifiers(&self) -> Vec<String> {
-        if let Some(function_definitions) = self.function_definitions().get() {
-            function_definitions
-                .iter()
-                .filter_map(|def| def.id().get())
-                .collect()
-        } else {
-            Vec::new()
-        }
+        self.function_definitions()
+            .get()
+            .map(|list| list.collect_ids())
+            .unwrap_or_default()
     }
 
     /// Find a [FunctionDefinition] by its *id* and return a number of arguments this function expects.
@@ -356,14 +528,10 @@ impl Model {
     /// Returns a vector of [UnitDefinition] identifiers (attribute **id**). Unit definitions
     /// without IDs are not included in the output.
     pub(crate) fn unit_definition_identifiers(&self) -> Vec<String> {
-        if let Some(unit_definitions) = self.unit_definitions().get() {
-            unit_definitions
-                .iter()
-                .filter_map(|unit| unit.id().get())
-                .collect()
-        } else {
-            Vec::new()
-        }
+        self.unit_definitions()
+            .get()
+            .map(|list| list.collect_ids())
+            .unwrap_or_default()
     }
 
     /// Returns a vector of [LocalParameter] identifiers (attribute **id**).
@@ -380,7 +548,7 @@ impl Model {
                 .get()
                 .and_then(|law| law.local_parameters().get());
             if let Some(local_parameters) = local_parameters {
-                identifiers.extend(local_parameters.iter().map(|param| param.id().get()));
+                identifiers.extend(local_parameters.collect_ids());
             }
         }
 
@@ -389,32 +557,26 @@ impl Model {
 
     /// Returns a vector of [Species] identifiers (attribute **id**).
     pub(crate) fn species_identifiers(&self) -> Vec<String> {
-        if let Some(species) = self.species().get() {
-            species.iter().map(|species| species.id().get()).collect()
-        } else {
-            Vec::new()
-        }
+        self.species()
+            .get()
+            .map(|list| list.collect_ids())
+            .unwrap_or_default()
     }
 
     /// Returns a vector of [Compartment] identifiers (attribute **id**).
     pub(crate) fn compartment_identifiers(&self) -> Vec<String> {
-        if let Some(compartment) = self.compartments().get() {
-            compartment
-                .iter()
-                .map(|compartment| compartment.id().get())
-                .collect()
-        } else {
-            Vec::new()
-        }
+        self.compartments()
+            .get()
+            .map(|list| list.collect_ids())
+            .unwrap_or_default()
     }
 
     /// Returns a vector of [Parameter] identifiers (attribute **id**).
     pub(crate) fn parameter_identifiers(&self) -> Vec<String> {
-        if let Some(parameters) = self.parameters().get() {
-            parameters.iter().map(|param| param.id().get()).collect()
-        } else {
-            vec![]
-        }
+        self.parameters()
+            .get()
+            .map(|list| list.collect_ids())
+            .unwrap_or_default()
     }
 
     /// Returns a vector of [SpeciesReference] identifiers (attribute **id**). Unit definitions
@@ -427,7 +589,7 @@ impl Model {
                 // ...we extract identifiers of reactants and products.
                 for list in &[reaction.reactants(), reaction.products()] {
                     if let Some(list) = list.get() {
-                        identifiers.extend(list.iter().filter_map(|it| it.id().get()));
+                        identifiers.extend(list.collect_ids());
                     }
                 }
             }
@@ -437,14 +599,10 @@ impl Model {
 
     /// Returns a vector of [FunctionDefinition] identifiers (attribute **id**).
     pub(crate) fn reaction_identifiers(&self) -> Vec<String> {
-        if let Some(reactions) = self.reactions().get() {
-            reactions
-                .iter()
-                .map(|reaction| reaction.id().get())
-                .collect::<Vec<String>>()
-        } else {
-            Vec::new()
-        }
+        self.reactions()
+            .get()
+            .map(|list| list.collect_ids())
+            .unwrap_or_default()
     }
 
     /// Returns a vector of all *variables* appearing in all [AssignmentRule] objects.
@@ -495,7 +653,7 @@ impl Model {
                                 false
                             }
                         })
-                        .map(|ci| ci.text_content())
+                        .map(|ci| ci.text_content_trimmed())
                         .collect::<Vec<String>>()
                 })
                 .collect::<Vec<String>>()
@@ -545,6 +703,117 @@ impl Model {
         true
     }
 
+    /// Returns all species located in the compartment with the given *id*.
+    pub fn species_by_compartment(&self, compartment_id: &str) -> Vec<Species> {
+        let Some(species) = self.species().get() else {
+            return Vec::new();
+        };
+        species
+            .iter()
+            .filter(|species| species.compartment().get() == compartment_id)
+            .collect()
+    }
+
+    /// Returns a map of all [Parameter] objects of this model, keyed by their *id*.
+    ///
+    /// The map is a snapshot: it does not reflect later changes of the model (although the
+    /// wrappers still point to the underlying XML elements). Parameters whose *id* is not
+    /// a valid [SId] are omitted, and for duplicate identifiers, the first parameter is used.
+    /// Since [SId] implements `Borrow<str>`, the map can be queried using `&str`.
+    pub fn parameters_map(&self) -> HashMap<SId, Parameter> {
+        self.parameters()
+            .get()
+            .map(|list| collect_by_id(list.iter().map(|it| (it.id().get(), it))))
+            .unwrap_or_default()
+    }
+
+    /// Returns a map of all [Species] objects of this model, keyed by their *id*
+    /// (see [Model::parameters_map] for details).
+    pub fn species_map(&self) -> HashMap<SId, Species> {
+        self.species()
+            .get()
+            .map(|list| collect_by_id(list.iter().map(|it| (it.id().get(), it))))
+            .unwrap_or_default()
+    }
+
+    /// Returns a map of all [Compartment] objects of this model, keyed by their *id*
+    /// (see [Model::parameters_map] for details).
+    pub fn compartments_map(&self) -> HashMap<SId, Compartment> {
+        self.compartments()
+            .get()
+            .map(|list| collect_by_id(list.iter().map(|it| (it.id().get(), it))))
+            .unwrap_or_default()
+    }
+
+    /// Builds a map from [Compartment] *ids* to the *ids* of the compartments that contain them.
+    ///
+    /// SBML Level 3 does not store compartment containment, so the parent of each compartment
+    /// is computed by the given `resolver` (e.g. from an annotation or a naming convention).
+    /// Compartments for which the `resolver` returns `None` are top-level compartments.
+    ///
+    /// An error is returned if a compartment is contained in an identifier which is not
+    /// a compartment of this model, or if the containment relation has a cycle.
+    pub fn compartment_containment(
+        &self,
+        resolver: impl Fn(&Compartment) -> Option<SId>,
+    ) -> Result<HashMap<SId, SId>, String> {
+        let compartments = self.compartments_map();
+        // Compartments are resolved in document order, such that errors are deterministic.
+        let ids = self
+            .compartments()
+            .get()
+            .map(|list| list.collect_ids())
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|id| SId::try_from(id).ok())
+            .collect::<Vec<_>>();
+
+        let mut parents = HashMap::new();
+        for id in &ids {
+            let Some(parent) = resolver(&compartments[id]) else {
+                continue;
+            };
+            if !compartments.contains_key(&parent) {
+                return Err(format!(
+                    "Compartment `{id}` is contained in `{parent}`, which is not a compartment \
+                    of this model."
+                ));
+            }
+            parents.insert(id.clone(), parent);
+        }
+
+        for id in &ids {
+            let mut path = vec![id];
+            while let Some(parent) = parents.get(*path.last().unwrap()) {
+                if let Some(start) = path.iter().position(|it| *it == parent) {
+                    let mut names = path[start..]
+                        .iter()
+                        .map(|it| it.as_str())
+                        .collect::<Vec<_>>();
+                    names.push(parent.as_str());
+                    return Err(format!(
+                        "Compartment containment contains a cycle: {}.",
+                        names.join(" -> ")
+                    ));
+                }
+                path.push(parent);
+            }
+        }
+        Ok(parents)
+    }
+
+    /// Returns all events which assign (using an
+    /// [EventAssignment](crate::core::EventAssignment)) the variable with the given *id*.
+    pub fn events_affecting(&self, id: &str) -> Vec<Event> {
+        let Some(events) = self.events().get() else {
+            return Vec::new();
+        };
+        events
+            .iter()
+            .filter(|event| event.assigned_variables().iter().any(|it| it == id))
+            .collect()
+    }
+
     /// Finds a species with the given *id*. If not found, returns `None`.
     pub(crate) fn find_species(&self, id: &str) -> Option<Species> {
         if let Some(species) = self.species().get() {
@@ -554,6 +823,67 @@ impl Model {
         }
     }
 
+    /// Returns all [AssignmentRule], [RateRule](crate::core::RateRule) and
+    /// [EventAssignment](crate::core::EventAssignment) elements of this model,
+    /// together with the value of their *variable* attribute.
+    pub(crate) fn variable_assignments(&self) -> Vec<(XmlElement, String)> {
+        let mut result = Vec::new();
+        if let Some(rules) = self.rules().get() {
+            for rule in rules.iter() {
+                match rule.cast() {
+                    RuleTypes::Assignment(rule) => {
+                        result.push((rule.xml_element().clone(), rule.variable().get()))
+                    }
+                    RuleTypes::Rate(rule) => {
+                        result.push((rule.xml_element().clone(), rule.variable().get()))
+                    }
+                    _ => (),
+                }
+            }
+        }
+        if let Some(events) = self.events().get() {
+            for event in events.iter() {
+                let Some(assignments) = event.event_assignments().get() else {
+                    continue;
+                };
+                for assignment in assignments.iter() {
+                    let variable = assignment.variable().get();
+                    result.push((assignment.xml_element().clone(), variable));
+                }
+            }
+        }
+        result
+    }
+
+    /// Finds a [SpeciesReference] (reactant or product) with the given *id*. If not found,
+    /// returns `None`.
+    pub(crate) fn find_species_reference(&self, id: &str) -> Option<SpeciesReference> {
+        let reactions = self.reactions().get()?;
+        for reaction in reactions.iter() {
+            for list in [reaction.reactants(), reaction.products()] {
+                let Some(list) = list.get() else {
+                    continue;
+                };
+                if let Some(reference) = list
+                    .iter()
+                    .find(|it| it.id().get().is_some_and(|it| it == id))
+                {
+                    return Some(reference);
+                }
+            }
+        }
+        None
+    }
+
+    /// Finds a parameter with the given *id*. If not found, returns `None`.
+    pub(crate) fn find_parameter(&self, id: &str) -> Option<Parameter> {
+        if let Some(parameters) = self.parameters().get() {
+            parameters.iter().find(|param| param.id().get() == id)
+        } else {
+            None
+        }
+    }
+
     /// Finds a compartment with the given *id*. If not found, returns `None`.
     pub(crate) fn find_compartment(&self, id: &str) -> Option<Compartment> {
         if let Some(compartments) = self.compartments().get() {
@@ -565,3 +895,15 @@ impl Model {
         }
     }
 }
+
+/// Build a map of the given `(id, item)` pairs, skipping invalid identifiers and keeping
+/// the first item of every duplicate identifier.
+fn collect_by_id<T>(items: impl Iterator<Item = (String, T)>) -> HashMap<SId, T> {
+    let mut result = HashMap::new();
+    for (id, item) in items {
+        if let Ok(id) = SId::try_from(id) {
+            result.entry(id).or_insert(item);
+        }
+    }
+    result
+}
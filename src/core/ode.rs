@@ -0,0 +1,320 @@
+use crate::constants::namespaces::NS_MATHML;
+use crate::core::{Math, Model, QuantityKind, Rule, RuleTypes, SBase, SimpleSpeciesReference};
+use crate::xml::{
+    FloatFormat, OptionalProperty, OptionalXmlChild, OptionalXmlProperty, RequiredXmlProperty,
+    XmlDocument, XmlElement, XmlProperty, XmlWrapper,
+};
+use biodivine_xml_doc::{Document, Element, Node};
+use std::collections::{HashMap, HashSet};
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, RwLock};
+
+/// An explicit system of ordinary differential equations derived from a [Model]
+/// (see [Model::to_ode_system]).
+#[derive(Clone, Debug)]
+pub struct OdeSystem {
+    /// Pairs of variable identifiers and the [Math] elements describing their derivatives.
+    ///
+    /// The [Math] elements are detached elements of a separate document which is shared
+    /// by all equations of this system (i.e. they do not modify the original model).
+    pub equations: Vec<(String, Math)>,
+    /// Statically known values of the model components: compartment sizes, parameter values,
+    /// initial species values (amounts or concentrations, see [crate::core::Species::quantity_kind]),
+    /// species reference stoichiometries, and local parameter values.
+    ///
+    /// Local parameters are renamed to `reaction_id.parameter_id` (both in the environment
+    /// and in the equations), such that they cannot clash with any model identifier.
+    /// Values computed by initial assignments or assignment rules are not included.
+    pub environment: HashMap<String, f64>,
+}
+
+impl OdeSystem {
+    /// Find the derivative of the given `variable`, if it is part of this system.
+    pub fn equation(&self, variable: &str) -> Option<&Math> {
+        self.equations
+            .iter()
+            .find(|(id, _)| id == variable)
+            .map(|(_, math)| math)
+    }
+}
+
+impl Model {
+    /// Convert the reactions and rate rules of this model into an explicit [OdeSystem].
+    ///
+    /// For every species that is neither constant, nor a boundary species, nor the variable
+    /// of an assignment or rate rule, the derivative is the sum of the kinetic laws of all
+    /// reactions in which the species participates, multiplied by the (signed) stoichiometry
+    /// of the participation. If the species is concentration-based, the sum is divided by
+    /// the size of its compartment. Variable stoichiometries (species references with an *id*
+    /// and *constant* set to `false`) are referenced by their *id*, and missing
+    /// stoichiometries are treated as `1`. Reactions without a kinetic law are ignored.
+    ///
+    /// Every [RateRule](crate::core::RateRule) is then included as is.
+    pub fn to_ode_system(&self) -> OdeSystem {
+        let target: XmlDocument = Arc::new(RwLock::new(Document::new()));
+        let environment = self.ode_environment();
+
+        let mut rule_variables = HashSet::new();
+        let mut rate_rules = Vec::new();
+        if let Some(rules) = self.rules().get() {
+            for rule in rules.iter() {
+                match rule.cast() {
+                    RuleTypes::Assignment(rule) => {
+                        rule_variables.insert(rule.variable().get());
+                    }
+                    RuleTypes::Rate(rule) => {
+                        rule_variables.insert(rule.variable().get());
+                        rate_rules.push(rule);
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        let mut equations = Vec::new();
+        if let Some(species) = self.species().get() {
+            for species in species.iter() {
+                let id = species.id().get();
+                if species.constant().get()
+                    || species.boundary_condition().get()
+                    || rule_variables.contains(&id)
+                {
+                    continue;
+                }
+                let mut sum = self.species_rate_terms(id.as_str(), &target);
+                if species.quantity_kind() == QuantityKind::Concentration {
+                    let volume = ci(&target, species.compartment().get().as_str());
+                    sum = apply(&target, "divide", vec![sum, volume]);
+                }
+                equations.push((id, wrap_math(&target, sum)));
+            }
+        }
+
+        for rule in rate_rules {
+            let Some(math) = rule.math().get() else {
+                continue;
+            };
+            let math = math.xml_element().import_into(target.clone());
+            let math = unsafe { Math::unchecked_cast(math) };
+            equations.push((rule.variable().get(), math));
+        }
+
+        OdeSystem {
+            equations,
+            environment,
+        }
+    }
+
+    /// Build the (un-normalized) sum of reaction rate terms which affect the given species.
+    fn species_rate_terms(&self, species_id: &str, target: &XmlDocument) -> Element {
+        let mut terms = Vec::new();
+        if let Some(reactions) = self.reactions().get() {
+            for reaction in reactions.iter() {
+                let Some(law) = reaction.kinetic_law().get() else {
+                    continue;
+                };
+                let Some(math) = law.math().get() else {
+                    continue;
+                };
+                let local_parameters = law
+                    .local_parameters()
+                    .get()
+                    .map(|it| it.collect_ids())
+                    .unwrap_or_default();
+                let reaction_id = reaction.id().get();
+
+                for (list, sign) in [(reaction.reactants(), -1.0), (reaction.products(), 1.0)] {
+                    let Some(list) = list.get() else {
+                        continue;
+                    };
+                    for reference in list.iter() {
+                        if reference.species().get() != species_id {
+                            continue;
+                        }
+                        let mut factors = Vec::new();
+                        match reference.id().get() {
+                            Some(id) if !reference.constant().get() => {
+                                if sign < 0.0 {
+                                    factors.push(cn(target, -1.0));
+                                }
+                                factors.push(ci(target, id.as_str()));
+                            }
+                            _ => {
+                                let value = optional_f64(reference.stoichiometry()).unwrap_or(1.0);
+                                factors.push(cn(target, sign * value));
+                            }
+                        }
+                        factors.push(rate_law(
+                            &math,
+                            target,
+                            reaction_id.as_str(),
+                            &local_parameters,
+                        ));
+                        terms.push(apply(target, "times", factors));
+                    }
+                }
+            }
+        }
+
+        match terms.len() {
+            0 => cn(target, 0.0),
+            1 => terms.pop().unwrap(),
+            _ => apply(target, "plus", terms),
+        }
+    }
+
+    /// Collect the statically known values of model components (see [OdeSystem::environment]).
+    fn ode_environment(&self) -> HashMap<String, f64> {
+        let mut environment = HashMap::new();
+        if let Some(compartments) = self.compartments().get() {
+            for compartment in compartments.iter() {
+                if let Some(size) = optional_f64(compartment.size()) {
+                    environment.insert(compartment.id().get(), size);
+                }
+            }
+        }
+        if let Some(parameters) = self.parameters().get() {
+            for parameter in parameters.iter() {
+                if let Some(value) = optional_f64(parameter.value()) {
+                    environment.insert(parameter.id().get(), value);
+                }
+            }
+        }
+        if let Some(species) = self.species().get() {
+            for species in species.iter() {
+                let amount = species.effective_initial_amount(self);
+                let value = match species.quantity_kind() {
+                    QuantityKind::Amount => amount,
+                    QuantityKind::Concentration => optional_f64(species.initial_concentration())
+                        .or_else(|| {
+                            let compartment =
+                                self.find_compartment(&species.compartment().get())?;
+                            Some(amount? / optional_f64(compartment.size())?)
+                        }),
+                };
+                if let Some(value) = value {
+                    environment.insert(species.id().get(), value);
+                }
+            }
+        }
+        if let Some(reactions) = self.reactions().get() {
+            for reaction in reactions.iter() {
+                for list in [reaction.reactants(), reaction.products()] {
+                    let Some(list) = list.get() else {
+                        continue;
+                    };
+                    for reference in list.iter() {
+                        let id = reference.id().get();
+                        let value = optional_f64(reference.stoichiometry());
+                        if let (Some(id), Some(value)) = (id, value) {
+                            environment.insert(id, value);
+                        }
+                    }
+                }
+                let local_parameters = reaction
+                    .kinetic_law()
+                    .get()
+                    .and_then(|law| law.local_parameters().get());
+                if let Some(local_parameters) = local_parameters {
+                    for parameter in local_parameters.iter() {
+                        if let Some(value) = optional_f64(parameter.value()) {
+                            let id = format!("{}.{}", reaction.id().get(), parameter.id().get());
+                            environment.insert(id, value);
+                        }
+                    }
+                }
+            }
+        }
+        environment
+    }
+}
+
+/// Read an optional numeric property, treating invalid values as missing.
+fn optional_f64(property: OptionalProperty<f64>) -> Option<f64> {
+    property.get_checked().ok().flatten()
+}
+
+/// Copy the content of a kinetic law `math` into the `target` document, renaming the references
+/// to local parameters to `reaction_id.parameter_id`.
+fn rate_law(
+    math: &Math,
+    target: &XmlDocument,
+    reaction_id: &str,
+    local_parameters: &[String],
+) -> Element {
+    let copy = math.xml_element().import_into(target.clone());
+    let mut doc = target.write().unwrap();
+    let content = copy.raw_element().child_elements(doc.deref());
+    for ci in copy.raw_element().child_elements_recursive(doc.deref()) {
+        if ci.name(doc.deref()) != "ci" {
+            continue;
+        }
+        let name = ci.text_content(doc.deref()).trim().to_string();
+        if local_parameters.contains(&name) {
+            ci.set_text_content(doc.deref_mut(), format!("{reaction_id}.{name}"));
+        }
+    }
+    // The kinetic law should contain exactly one expression; anything else is invalid anyway.
+    let Some(content) = content.into_iter().next() else {
+        drop(doc);
+        return cn(target, 0.0);
+    };
+    content.detatch(doc.deref_mut()).unwrap();
+    // Keep the prefixed namespace declarations of the original `math` element, such that the
+    // content remains valid once it is moved to a different parent. The default (MathML)
+    // namespace is declared by the new parent.
+    let declarations = copy.raw_element().namespace_decls(doc.deref()).clone();
+    for (prefix, url) in declarations {
+        if !prefix.is_empty() && !content.namespace_decls(doc.deref()).contains_key(&prefix) {
+            content.set_namespace_decl(doc.deref_mut(), prefix, url);
+        }
+    }
+    content
+}
+
+/// Create a detached MathML `math` element containing the given `content`.
+fn wrap_math(target: &XmlDocument, content: Element) -> Math {
+    let math = XmlElement::new_quantified(target.clone(), "math", NS_MATHML);
+    {
+        let mut doc = target.write().unwrap();
+        math.raw_element()
+            .push_child(doc.deref_mut(), Node::Element(content))
+            .unwrap();
+    }
+    unsafe { Math::unchecked_cast(math) }
+}
+
+/// Create an `apply` element of the given `operator` and `arguments`.
+fn apply(target: &XmlDocument, operator: &str, arguments: Vec<Element>) -> Element {
+    let mut doc = target.write().unwrap();
+    let apply = Element::build("apply").finish(doc.deref_mut());
+    let operator = Element::build(operator).finish(doc.deref_mut());
+    apply
+        .push_child(doc.deref_mut(), Node::Element(operator))
+        .unwrap();
+    for argument in arguments {
+        apply
+            .push_child(doc.deref_mut(), Node::Element(argument))
+            .unwrap();
+    }
+    apply
+}
+
+/// Create a `ci` element referencing the given identifier.
+fn ci(target: &XmlDocument, id: &str) -> Element {
+    let mut doc = target.write().unwrap();
+    let ci = Element::build("ci").finish(doc.deref_mut());
+    ci.set_text_content(doc.deref_mut(), id);
+    ci
+}
+
+/// Create a `cn` element with the given numeric value.
+fn cn(target: &XmlDocument, value: f64) -> Element {
+    let mut doc = target.write().unwrap();
+    let cn = Element::build("cn").finish(doc.deref_mut());
+    cn.set_text_content(
+        doc.deref_mut(),
+        FloatFormat::ShortestRoundTrip.format(value),
+    );
+    cn
+}
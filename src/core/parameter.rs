@@ -1,6 +1,8 @@
+use crate::constants::namespaces::URL_PACKAGE_DISTRIB;
 use crate::core::sbase::SbmlUtils;
+use crate::distrib::Uncertainty;
 use crate::xml::{
-    OptionalProperty, RequiredProperty, RequiredXmlProperty, XmlDocument, XmlElement,
+    OptionalChild, OptionalProperty, RequiredProperty, RequiredXmlProperty, XmlDocument, XmlElement,
 };
 use sbml_macros::{SBase, XmlWrapper};
 
@@ -31,4 +33,9 @@ impl Parameter {
     pub fn constant(&self) -> RequiredProperty<bool> {
         self.required_sbml_property("constant")
     }
+
+    /// The uncertainty of this parameter, as defined by the `distrib` package.
+    pub fn uncertainty(&self) -> OptionalChild<Uncertainty> {
+        self.optional_package_child("uncertainty", URL_PACKAGE_DISTRIB)
+    }
 }
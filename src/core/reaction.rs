@@ -1,10 +1,13 @@
+use crate::constants::namespaces::URL_PACKAGE_FBC;
 use crate::core::sbase::SbmlUtils;
-use crate::core::{Math, SBase};
+use crate::core::{Math, Model, Parameter, SBase};
 use crate::xml::{
-    OptionalChild, OptionalProperty, OptionalXmlChild, RequiredProperty, RequiredXmlProperty,
-    XmlDefault, XmlDocument, XmlElement, XmlList,
+    OptionalChild, OptionalProperty, OptionalXmlChild, OptionalXmlProperty, RequiredProperty,
+    RequiredXmlProperty, XmlChildDefault, XmlDefault, XmlDocument, XmlElement, XmlList,
+    XmlProperty, XmlWrapper,
 };
 use sbml_macros::{SBase, XmlWrapper};
+use std::collections::HashMap;
 
 #[derive(Clone, Debug, XmlWrapper, SBase)]
 pub struct Reaction(XmlElement);
@@ -44,6 +47,51 @@ impl Reaction {
     pub fn kinetic_law(&self) -> OptionalChild<KineticLaw> {
         self.optional_sbml_child("kineticLaw")
     }
+
+    /// The [KineticLaw] of this reaction. If it does not exist, an empty kinetic law
+    /// is created.
+    pub fn kinetic_law_or_create(&self) -> KineticLaw {
+        self.kinetic_law().get_or_create()
+    }
+
+    /// The identifier of the parameter referenced by the `fbc:lowerFluxBound` attribute.
+    pub fn fbc_lower_flux_bound(&self) -> Option<String> {
+        self.get_namespaced_attribute("lowerFluxBound", URL_PACKAGE_FBC)
+    }
+
+    /// The identifier of the parameter referenced by the `fbc:upperFluxBound` attribute.
+    pub fn fbc_upper_flux_bound(&self) -> Option<String> {
+        self.get_namespaced_attribute("upperFluxBound", URL_PACKAGE_FBC)
+    }
+
+    /// Check that the atoms of each chemical element are conserved by this reaction, based on
+    /// the `fbc:chemicalFormula` of the reactants and products (see
+    /// [Species::fbc_element_counts](crate::core::Species::fbc_element_counts)).
+    ///
+    /// Returns `None` if the answer cannot be determined, i.e. some involved species does
+    /// not exist in the `model`, lacks a (valid) chemical formula, or some species reference
+    /// does not declare its *stoichiometry*.
+    pub fn is_mass_balanced(&self, model: &Model) -> Option<bool> {
+        let mut balance: HashMap<String, f64> = HashMap::new();
+        for (list, sign) in [(self.reactants(), -1.0), (self.products(), 1.0)] {
+            let Some(list) = list.get() else {
+                continue;
+            };
+            for species_ref in list.iter() {
+                let species = model.find_species(species_ref.species().get().as_str())?;
+                let counts = species.fbc_element_counts()?;
+                let stoichiometry = species_ref.stoichiometry();
+                if !stoichiometry.is_set() {
+                    return None;
+                }
+                let stoichiometry = stoichiometry.get()?;
+                for (element, count) in counts {
+                    *balance.entry(element).or_insert(0.0) += sign * stoichiometry * count as f64;
+                }
+            }
+        }
+        Some(balance.values().all(|it| it.abs() < 1e-9))
+    }
 }
 
 pub trait SimpleSpeciesReference: SBase {
@@ -114,6 +162,35 @@ impl KineticLaw {
         self.optional_sbml_child("listOfLocalParameters")
     }
 
+    /// The list of [LocalParameter] elements of this kinetic law. If it does not exist,
+    /// an empty list is created.
+    pub fn local_parameters_or_create(&self) -> XmlList<LocalParameter> {
+        self.local_parameters().get_or_create()
+    }
+
+    /// Append a new [LocalParameter] with the given `id` to this kinetic law (the list of
+    /// local parameters is created if necessary). Returns the new local parameter.
+    pub fn add_local_parameter(&self, id: &str) -> LocalParameter {
+        let local_parameters = self.local_parameters_or_create();
+        local_parameters.push(LocalParameter::new(self.document(), &id.to_string()));
+        local_parameters.top()
+    }
+
+    /// Pairs of [LocalParameter] elements of this kinetic law and the global [Parameter]
+    /// elements of the `model` that they shadow (i.e. which have the same *id*).
+    pub(crate) fn shadowed_identifiers(&self, model: &Model) -> Vec<(LocalParameter, Parameter)> {
+        let Some(local_parameters) = self.local_parameters().get() else {
+            return Vec::new();
+        };
+        local_parameters
+            .iter()
+            .filter_map(|local| {
+                let global = model.find_parameter(local.id().get().as_str())?;
+                Some((local, global))
+            })
+            .collect()
+    }
+
     pub(crate) fn local_parameter_identifiers(&self) -> Vec<String> {
         if let Some(local_parameters) = self.local_parameters().get() {
             local_parameters
@@ -1,15 +1,125 @@
 // TODO:
-//      At some point, we should probably have an `SId` type instead of using a string here,
-//      because IDs have a special format that should be enforced. This is also related to other
-//      types that are "string like", e.g. meta id and sboTerm.
+//      The `id`, `metaid` and `sboTerm` properties still use plain strings. At some point,
+//      they should use the `SId`, `MetaId` and `SboTerm` types defined below, such that their
+//      format is enforced.
 
 use crate::constants::namespaces::{NS_SBML_CORE, URL_HTML, URL_MATHML, URL_SBML_CORE};
+use crate::core::validation::{
+    matches_sboterm_pattern, matches_sid_pattern, matches_xml_id_pattern,
+};
 use crate::xml::{
-    OptionalChild, OptionalProperty, RequiredProperty, XmlDocument, XmlElement, XmlPropertyType,
-    XmlWrapper,
+    OptionalChild, OptionalProperty, OptionalXmlChild, OptionalXmlProperty, RequiredProperty,
+    XmlChild, XmlDocument, XmlElement, XmlPropertyType, XmlWrapper,
 };
-use biodivine_xml_doc::{Document, Element};
-use std::ops::Deref;
+use std::borrow::Borrow;
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::hash::{BuildHasher, RandomState};
+use std::ops::{Deref, DerefMut};
+
+/// A string which conforms to the **SId** syntax.
+///
+/// An [SId] implements `Borrow<str>`, hence collections of [SId] values can be queried
+/// using plain string slices.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SId(String);
+
+/// A string which conforms to the **XML 1.0 ID** syntax, i.e. a valid `metaid` value.
+///
+/// A [MetaId] implements `Borrow<str>`, hence collections of [MetaId] values can be queried
+/// using plain string slices.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MetaId(String);
+
+/// A string which conforms to the **SBOTerm** syntax, i.e. `SBO:` followed by seven digits.
+///
+/// With the `sbo_terms` feature, [SboTerm::label] and [SboTerm::definition] provide
+/// human-readable descriptions of common terms.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SboTerm(String);
+
+macro_rules! string_identifier {
+    ($name:ident, $is_valid:path, $syntax:literal) => {
+        impl $name {
+            pub fn as_str(&self) -> &str {
+                self.0.as_str()
+            }
+        }
+
+        impl TryFrom<String> for $name {
+            type Error = String;
+
+            fn try_from(value: String) -> Result<Self, Self::Error> {
+                let value = Some(value);
+                if $is_valid(&value) {
+                    Ok($name(value.unwrap()))
+                } else {
+                    Err(format!(
+                        "Value `{}` is not a valid {}.",
+                        value.unwrap(),
+                        $syntax
+                    ))
+                }
+            }
+        }
+
+        impl TryFrom<&str> for $name {
+            type Error = String;
+
+            fn try_from(value: &str) -> Result<Self, Self::Error> {
+                $name::try_from(value.to_string())
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                self.as_str()
+            }
+        }
+
+        impl Borrow<str> for $name {
+            fn borrow(&self) -> &str {
+                self.as_str()
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        /// A conversion between an XML attribute and a validated identifier. Invalid values
+        /// are reported as an error.
+        impl XmlPropertyType for $name {
+            fn try_get(value: Option<&str>) -> Result<Option<Self>, String> {
+                value.map($name::try_from).transpose()
+            }
+
+            fn set(&self) -> Option<String> {
+                Some(self.0.clone())
+            }
+        }
+    };
+}
+
+string_identifier!(SId, matches_sid_pattern, "SId");
+string_identifier!(MetaId, matches_xml_id_pattern, "XML ID");
+string_identifier!(SboTerm, matches_sboterm_pattern, "SBOTerm");
+
+impl SboTerm {
+    /// The numeric part of the term, e.g. `14` for `SBO:0000014`.
+    pub fn number(&self) -> u32 {
+        // The format is checked when the value is created, so this cannot fail.
+        self.0[4..].parse().unwrap()
+    }
+}
 
 /// Abstract class SBase that is the parent of most of the elements in SBML.
 /// Thus, there is no need to implement concrete structure.
@@ -37,8 +147,242 @@ pub trait SBase: XmlWrapper {
     fn annotation(&self) -> OptionalChild<XmlElement> {
         self.optional_sbml_child("annotation")
     }
+
+    /// Iterate over the ancestors of this element, starting with its direct parent.
+    ///
+    /// The iteration stops at the root `sbml` element (which is still included), i.e. the
+    /// internal document container is never returned. A detached element has no ancestors.
+    fn ancestors(&self) -> impl Iterator<Item = XmlElement> {
+        element_ancestors(self.xml_element())
+    }
+
+    /// Find the closest ancestor of this element which has an `id` attribute, returning
+    /// both the identifier and the ancestor element. Like [SBase::ancestors], the search
+    /// stops at the root `sbml` element.
+    fn nearest_ancestor_with_id(&self) -> Option<(String, XmlElement)> {
+        self.ancestors()
+            .find_map(|element| element.get_attribute("id").map(|id| (id, element)))
+    }
+
+    /// Set the `id` of this element, provided `value` conforms to the **SId** syntax.
+    /// Otherwise, the element is left unchanged and an error is returned.
+    fn try_set_id(&self, value: &str) -> Result<(), String> {
+        let value = SId::try_from(value)?;
+        self.id().set_some(&value.into());
+        Ok(())
+    }
+
+    /// Set the `metaid` of this element, provided `value` conforms to the **XML 1.0 ID**
+    /// syntax. Otherwise, the element is left unchanged and an error is returned.
+    fn try_set_meta_id(&self, value: &str) -> Result<(), String> {
+        let value = MetaId::try_from(value)?;
+        self.meta_id().set_some(&value.into());
+        Ok(())
+    }
+
+    /// Return the `metaid` of this element. If the element has no `metaid`, a new random
+    /// `metaid` (e.g. `meta_5f3a09c2e1d4b786`) that is unique within the document is assigned.
+    ///
+    /// See also [SBase::ensure_meta_id_deterministic], which produces the same `metaid` every
+    /// time it is applied to the same document.
+    fn ensure_meta_id(&self) -> String {
+        if let Some(meta_id) = self.meta_id().get() {
+            return meta_id;
+        }
+        let used = document_meta_ids(self.xml_element());
+        let state = RandomState::new();
+        let meta_id = (0u64..)
+            .map(|attempt| format!("meta_{:016x}", state.hash_one(attempt)))
+            .find(|it| !used.contains(it))
+            .unwrap();
+        self.meta_id().set_some(&meta_id);
+        meta_id
+    }
+
+    /// The same as [SBase::ensure_meta_id], but the new `metaid` is derived from the tag name,
+    /// the `id` and the position of this element in the document, such that regenerating the
+    /// `metaid` on the same document always yields the same value.
+    ///
+    /// The `metaid` has the form `meta_<hash>`, where `<hash>` is a stable (FNV-1a) hash of the
+    /// element properties. If this value is already used by another element of the document,
+    /// the first free value of the form `meta_<hash>_<n>` (with `n = 2, 3, ...`) is used
+    /// instead. Note that as a consequence, the result can change if `metaid` values are
+    /// assigned to the elements in a different order.
+    fn ensure_meta_id_deterministic(&self) -> String {
+        if let Some(meta_id) = self.meta_id().get() {
+            return meta_id;
+        }
+        let used = document_meta_ids(self.xml_element());
+        let meta_id = deterministic_meta_id(self.xml_element(), &used);
+        self.meta_id().set_some(&meta_id);
+        meta_id
+    }
+
+    /// Remove this element from its parent (e.g. a [Species] from its
+    /// [XmlList](crate::xml::XmlList)). Returns `false` if the element is already detached,
+    /// or if it is the root `sbml` element, which cannot be removed.
+    ///
+    /// The removed element stays valid in the detached state (see [XmlWrapper::try_detach]),
+    /// so it can be inspected or attached elsewhere later.
+    ///
+    /// [Species]: crate::core::Species
+    fn remove_from_parent(&self) -> bool {
+        if self.tag_name() == "sbml" || self.is_detached() {
+            return false;
+        }
+        self.try_detach().is_ok()
+    }
+
+    /// Find the first child of the `annotation` of this element which belongs to the given
+    /// tool-specific `namespace`.
+    fn tool_annotation(&self, namespace: &str) -> Option<XmlElement> {
+        self.annotation()
+            .get()?
+            .child_elements()
+            .into_iter()
+            .find(|it| it.namespace_url() == namespace)
+    }
+
+    /// Read the value of `key` stored in the [tool annotation](SBase::tool_annotation)
+    /// of the given `namespace`, i.e. the text content of its `<key>value</key>` child.
+    fn get_tool_kv(&self, namespace: &str, key: &str) -> Option<String> {
+        self.tool_annotation(namespace)?
+            .child_elements()
+            .into_iter()
+            .find(|it| it.tag_name() == key && it.namespace_url() == namespace)
+            .map(|it| it.text_content())
+    }
+
+    /// Store `value` under `key` in the [tool annotation](SBase::tool_annotation) of the
+    /// given `namespace`, creating the `annotation` and a `<data>` element in `namespace`
+    /// if necessary. The `key` must be a valid XML tag name.
+    ///
+    /// Only the `<key>` element within the tool annotation is modified. Any other annotation
+    /// content (RDF, other tools) is preserved as is.
+    fn set_tool_kv(&self, namespace: &str, key: &str, value: &str) {
+        let annotation = match self.annotation().get() {
+            Some(annotation) => annotation,
+            None => {
+                let notes = self.notes().get_raw().map(|it| it.raw_element());
+                let (prefix, position) = {
+                    let doc = self.read_doc();
+                    let element = self.raw_element();
+                    let prefix = element
+                        .closest_prefix(doc.deref(), URL_SBML_CORE)
+                        .unwrap_or("")
+                        .to_string();
+                    // The annotation must follow the notes, but precede all other children.
+                    let position = element
+                        .children(doc.deref())
+                        .iter()
+                        .position(|it| notes.is_some() && it.as_element() == notes)
+                        .map(|it| it + 1)
+                        .unwrap_or(0);
+                    (prefix, position)
+                };
+                let annotation = XmlElement::new_quantified(
+                    self.document(),
+                    "annotation",
+                    (prefix.as_str(), URL_SBML_CORE),
+                );
+                annotation.try_attach_at(self, Some(position)).unwrap();
+                annotation
+            }
+        };
+        let tool = self.tool_annotation(namespace).unwrap_or_else(|| {
+            let tool =
+                XmlElement::new_quantified(self.document(), "data", (TOOL_PREFIX, namespace));
+            tool.try_attach_at(&annotation, None).unwrap();
+            tool
+        });
+        let entry = tool
+            .child_elements()
+            .into_iter()
+            .find(|it| it.tag_name() == key && it.namespace_url() == namespace)
+            .unwrap_or_else(|| {
+                let entry =
+                    XmlElement::new_quantified(self.document(), key, (TOOL_PREFIX, namespace));
+                entry.try_attach_at(&tool, None).unwrap();
+                entry
+            });
+        let mut doc = entry.write_doc();
+        entry.raw_element().set_text_content(doc.deref_mut(), value);
+    }
 }
 
+/// Iterate over the ancestors of the given `element` (see [SBase::ancestors]).
+pub(crate) fn element_ancestors(element: &XmlElement) -> impl Iterator<Item = XmlElement> {
+    let start = if element.tag_name() == "sbml" {
+        None
+    } else {
+        element.parent()
+    };
+    std::iter::successors(start, |element| {
+        if element.tag_name() == "sbml" {
+            None
+        } else {
+            element.parent()
+        }
+    })
+}
+
+/// Compute the `metaid` which [SBase::ensure_meta_id_deterministic] assigns to `element`,
+/// given the set of `metaid` values which are already `used` in the document.
+pub(crate) fn deterministic_meta_id(element: &XmlElement, used: &HashSet<String>) -> String {
+    let mut key = format!(
+        "{}|{}|",
+        element.tag_name(),
+        element.get_attribute("id").unwrap_or_default()
+    );
+    let mut current = element.clone();
+    let mut path = Vec::new();
+    while let Some(parent) = current.parent() {
+        let position = parent
+            .child_elements()
+            .iter()
+            .position(|it| it.raw_element() == current.raw_element())
+            .unwrap_or_default();
+        path.push(position.to_string());
+        current = parent;
+    }
+    path.reverse();
+    key.push_str(path.join(".").as_str());
+
+    let hash = key.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    });
+    let base = format!("meta_{hash:016x}");
+    if used.contains(&base) {
+        (2u64..)
+            .map(|n| format!("{base}_{n}"))
+            .find(|it| !used.contains(it))
+            .unwrap()
+    } else {
+        base
+    }
+}
+
+/// Collect all `metaid` values used in the document of the given `element`.
+pub(crate) fn document_meta_ids(element: &XmlElement) -> HashSet<String> {
+    let root = {
+        let doc = element.read_doc();
+        doc.root_element()
+    };
+    let Some(root) = root else {
+        return HashSet::new();
+    };
+    let root = XmlElement::new_raw(element.document(), root);
+    let mut elements = root.recursive_child_elements();
+    elements.push(root);
+    elements
+        .iter()
+        .filter_map(|it| it.get_attribute("metaid"))
+        .collect()
+}
+
+/// The namespace prefix used for newly created tool annotations (see [SBase::set_tool_kv]).
+const TOOL_PREFIX: &str = "tool";
+
 /// TODO:
 ///     In the end, this trait probably should not be accessible from the outside, but we can
 ///     discuss this later.
@@ -49,26 +393,11 @@ pub(crate) trait SbmlUtils: SBase {
     ///
     /// TODO: Currently, this requires SBML core namespace.
     fn search_in_parents(child: &XmlElement, tag_name: &str) -> Option<Self> {
-        let parent = {
-            let read_doc = child.read_doc();
-            fn check_name(doc: &Document, e: Element, tag_name: &str) -> bool {
-                let name = e.name(doc);
-                let Some(namespace) = e.namespace(doc) else {
-                    return false;
-                };
-
-                name == tag_name && namespace == URL_SBML_CORE
-            }
-
-            let mut parent = child.raw_element();
-            while !check_name(read_doc.deref(), parent, tag_name) {
-                parent = parent.parent(read_doc.deref())?;
-            }
-            parent
-        };
-        let model = XmlElement::new_raw(child.document(), parent);
+        let parent = std::iter::once(child.clone())
+            .chain(element_ancestors(child))
+            .find(|it| it.tag_name() == tag_name && it.namespace_url() == URL_SBML_CORE)?;
         // Safe because we checked that the element has the correct tag name and namespace.
-        Some(unsafe { Self::unchecked_cast(model) })
+        Some(unsafe { Self::unchecked_cast(parent) })
     }
 
     /// Create a new instance of `Self` which is just an empty tag with the given `tag_name`
@@ -99,6 +428,17 @@ pub(crate) trait SbmlUtils: SBase {
         OptionalChild::new(self.xml_element(), name, URL_HTML)
     }
 
+    /// Create an instance of [OptionalChild] with the given `name` and using the namespace
+    /// of an SBML package given by `namespace_url`.
+    #[inline(always)]
+    fn optional_package_child<T: XmlWrapper>(
+        &self,
+        name: &'static str,
+        namespace_url: &'static str,
+    ) -> OptionalChild<T> {
+        OptionalChild::new(self.xml_element(), name, namespace_url)
+    }
+
     /// Create an instance of a [RequiredProperty] with the given `name` which adheres to
     /// the SBML namespace.
     #[inline(always)]
@@ -0,0 +1,87 @@
+use crate::core::SboTerm;
+use phf::{phf_map, Map};
+
+/// Labels and (shortened) definitions of commonly used terms of the Systems Biology Ontology.
+///
+/// This is a compact subset of the ontology covering the terms that typically appear in
+/// `sboTerm` attributes of core SBML and `fbc` models. Terms which are not in the table
+/// simply have no label.
+const SBO_TERMS: Map<&str, (&str, &str)> = phf_map! {
+    "SBO:0000000" => ("systems biology representation", "Representation of an entity used in a systems biology knowledge reconstruction, such as a model, pathway or network."),
+    "SBO:0000001" => ("rate law", "Mathematical description that relates quantities of reactants to the reaction velocity."),
+    "SBO:0000002" => ("quantitative systems description parameter", "A numerical value that defines certain characteristics of systems or system functions."),
+    "SBO:0000003" => ("participant role", "The function of a physical or conceptual entity, that is its role, in the execution of an event or process."),
+    "SBO:0000004" => ("modelling framework", "Set of assumptions that underlay a mathematical description."),
+    "SBO:0000009" => ("kinetic constant", "Numerical parameter that quantifies the velocity of a chemical reaction."),
+    "SBO:0000010" => ("reactant", "Substance consumed by a chemical reaction."),
+    "SBO:0000011" => ("product", "Substance that is produced in a reaction."),
+    "SBO:0000013" => ("catalyst", "Substance that accelerates the velocity of a chemical reaction without itself being consumed or transformed."),
+    "SBO:0000014" => ("enzymatic catalyst", "A substance that accelerates the velocity of a chemical reaction without itself being consumed or transformed, by lowering the free energy of the transition state."),
+    "SBO:0000019" => ("modifier", "A substance that changes the velocity of a process without itself being consumed or transformed by the reaction."),
+    "SBO:0000020" => ("inhibitor", "A substance that decreases the probability of a chemical reaction without itself being changed by the reaction."),
+    "SBO:0000021" => ("potentiator", "A substance that increases the probability of a chemical reaction without itself being changed by the reaction."),
+    "SBO:0000027" => ("Michaelis constant", "Substrate concentration at which the velocity of reaction is half its maximum."),
+    "SBO:0000029" => ("Henri-Michaelis-Menten rate law", "Enzymatic rate law assuming the enzyme-substrate complex is in quasi-steady state."),
+    "SBO:0000046" => ("zeroth order rate constant", "Kinetic constant of a reaction whose velocity does not depend on the quantities of its reactants."),
+    "SBO:0000062" => ("continuous framework", "Modelling approach where the quantities of participants are considered continuous."),
+    "SBO:0000063" => ("discrete framework", "Modelling approach where the quantities of participants are considered discrete."),
+    "SBO:0000064" => ("mathematical expression", "Formal representation of a calculus linking parameters and variables of a model."),
+    "SBO:0000167" => ("biochemical or transport reaction", "An event involving one or more chemical entities that modifies the electrochemical structure of at least one of the participants."),
+    "SBO:0000176" => ("biochemical reaction", "An event involving one or more physical entities that modifies the structure, location or free energy of at least one of the participants."),
+    "SBO:0000177" => ("non-covalent binding", "Interaction between several biochemical entities that results in the formation of a non-covalent complex."),
+    "SBO:0000179" => ("degradation", "Complete disappearance of a physical entity."),
+    "SBO:0000180" => ("dissociation", "Transformation of a non-covalent complex that results in the formation of several independent biochemical entities."),
+    "SBO:0000182" => ("conversion", "Biochemical reaction that results in the modification of some covalent bonds."),
+    "SBO:0000185" => ("transport reaction", "Movement of a physical entity without modification of the structure of the entity."),
+    "SBO:0000186" => ("maximal velocity", "Limiting maximal velocity of an enzymatic reaction, reached when the enzyme is saturated by its substrate."),
+    "SBO:0000190" => ("Hill coefficient", "Empirical parameter created to describe the cooperativity of ligand binding."),
+    "SBO:0000196" => ("concentration of an entity pool", "The amount of an entity per unit of volume."),
+    "SBO:0000231" => ("occurring entity representation", "Representation of an entity that manifests, unfolds or develops through time, such as a discrete event or a mutual or reciprocal action or effect."),
+    "SBO:0000236" => ("physical entity representation", "Representation of an entity that exists independently of others."),
+    "SBO:0000240" => ("material entity", "A real thing that is defined by its physico-chemical structure."),
+    "SBO:0000241" => ("functional entity", "A real thing that is defined by its properties or the function it fulfils."),
+    "SBO:0000245" => ("macromolecule", "Molecular entity mainly built-up by the repetition of pseudo-identical units."),
+    "SBO:0000247" => ("simple chemical", "Simple, non-repetitive chemical entity."),
+    "SBO:0000252" => ("polypeptide chain", "Naturally occurring macromolecule formed by the repetition of amino-acid residues linked by peptidic bonds."),
+    "SBO:0000253" => ("non-covalent complex", "Entity composed of several independent components that are not linked by covalent bonds."),
+    "SBO:0000290" => ("physical compartment", "Specific location of space, that can be bounded or not."),
+    "SBO:0000291" => ("empty set", "Entity defined by the absence of any actual object."),
+    "SBO:0000293" => ("non-spatial continuous framework", "Continuous modelling framework where the spatial distribution of the participants is not considered."),
+    "SBO:0000327" => ("non-macromolecular ion", "An atom or small molecule with a net electric charge."),
+    "SBO:0000336" => ("interactor", "An entity that participates in a physical or functional interaction."),
+    "SBO:0000354" => ("informational molecule segment", "A part of a macromolecule that carries genetic information."),
+    "SBO:0000375" => ("process", "A sequential series of actions, motions, or occurrences, such as chemical reactions, that affect one or more entities in a phenomenologically characteristic manner."),
+    "SBO:0000395" => ("encapsulating process", "A process that involves a set of entities and processes at a lower level of description."),
+    "SBO:0000396" => ("uncertain process", "A process that is not known to occur, or whose mechanism is uncertain."),
+    "SBO:0000397" => ("omitted process", "A process that is known to occur, but is not described in detail."),
+    "SBO:0000405" => ("perturbing agent", "An entity that changes the behaviour of a system when applied to it."),
+    "SBO:0000459" => ("stimulator", "A substance that increases the probability or the velocity of a process."),
+    "SBO:0000461" => ("essential activator", "An activator without which the process cannot take place."),
+    "SBO:0000462" => ("non-essential activator", "An activator which is not required for the process to take place."),
+    "SBO:0000545" => ("systems description parameter", "A quantitative or qualitative parameter that describes a system or its components."),
+    "SBO:0000589" => ("genetic production", "Process in which genetic information results in the production of a gene product."),
+    "SBO:0000625" => ("flux bound", "A parameter that limits the flux of a reaction."),
+    "SBO:0000626" => ("default flux bound", "A flux bound that is used when no specific bound is known."),
+    "SBO:0000627" => ("exchange reaction", "A pseudo-reaction which exchanges a substance with the outside of the modelled system."),
+    "SBO:0000628" => ("demand reaction", "A pseudo-reaction which removes a substance from the modelled system."),
+    "SBO:0000629" => ("biomass production", "A pseudo-reaction which converts the building blocks of a cell into biomass."),
+    "SBO:0000630" => ("ATP maintenance", "A pseudo-reaction which consumes ATP to account for the maintenance of the cell."),
+    "SBO:0000631" => ("pseudoreaction", "A reaction which does not describe a real chemical transformation, but is part of the model for technical reasons."),
+    "SBO:0000632" => ("sink reaction", "A pseudo-reaction which allows a substance to be both consumed and produced by the outside of the modelled system."),
+};
+
+impl SboTerm {
+    /// The human-readable label of this term (e.g. `"enzymatic catalyst"` for `SBO:0000014`),
+    /// or `None` if the term is not included in the embedded table.
+    pub fn label(&self) -> Option<&'static str> {
+        SBO_TERMS.get(self.as_str()).map(|(label, _)| *label)
+    }
+
+    /// A short definition of this term, or `None` if the term is not included in the
+    /// embedded table.
+    pub fn definition(&self) -> Option<&'static str> {
+        SBO_TERMS
+            .get(self.as_str())
+            .map(|(_, definition)| *definition)
+    }
+}
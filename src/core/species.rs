@@ -1,8 +1,23 @@
+use crate::constants::namespaces::URL_PACKAGE_FBC;
 use crate::core::sbase::SbmlUtils;
+use crate::core::Model;
 use crate::xml::{
-    OptionalProperty, RequiredProperty, RequiredXmlProperty, XmlDocument, XmlElement,
+    OptionalProperty, RequiredProperty, RequiredXmlProperty, XmlDocument, XmlElement, XmlProperty,
+    XmlWrapper,
 };
 use sbml_macros::{SBase, XmlWrapper};
+use std::collections::HashMap;
+
+/// Determines how the value of a [Species] is interpreted when its identifier appears in
+/// mathematical formulas (see [Species::quantity_kind]).
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+pub enum QuantityKind {
+    /// The species identifier represents the *amount* of the substance.
+    Amount,
+    /// The species identifier represents the *concentration* (density) of the substance,
+    /// i.e. its amount divided by the size of its compartment.
+    Concentration,
+}
 
 /// Individual specie definition
 #[derive(Clone, Debug, XmlWrapper, SBase)]
@@ -55,4 +70,87 @@ impl Species {
     pub fn conversion_factor(&self) -> OptionalProperty<String> {
         self.optional_sbml_property("conversionFactor")
     }
+
+    /// Whether the identifier of this species denotes an amount or a concentration
+    /// in mathematical formulas.
+    ///
+    /// ## Specification
+    ///  - Section 4.6.5: if *hasOnlySubstanceUnits* is `true`, the species identifier denotes
+    ///    the amount of the substance, otherwise it denotes its concentration. This is
+    ///    independent of whether *initialAmount* or *initialConcentration* is used to
+    ///    specify the initial value.
+    pub fn quantity_kind(&self) -> QuantityKind {
+        if self.has_only_substance_units().get() {
+            QuantityKind::Amount
+        } else {
+            QuantityKind::Concentration
+        }
+    }
+
+    /// Shorthand for `self.quantity_kind() == QuantityKind::Concentration`.
+    pub fn is_concentration_based(&self) -> bool {
+        self.quantity_kind() == QuantityKind::Concentration
+    }
+
+    /// The initial *amount* of this species, converting *initialConcentration* using the
+    /// *size* of the species' compartment when necessary.
+    ///
+    /// Returns `None` if neither initial value is set, if the compartment does not exist
+    /// in the `model` or has no *size*, or if the compartment has zero spatial dimensions
+    /// (concentration is undefined in such compartments). Note that values which are
+    /// computed by initial assignments or rules are not considered.
+    pub fn effective_initial_amount(&self, model: &Model) -> Option<f64> {
+        let amount = self.initial_amount().get_checked().ok().flatten();
+        if amount.is_some() {
+            return amount;
+        }
+        let concentration = self.initial_concentration().get_checked().ok().flatten()?;
+        let compartment = model.find_compartment(self.compartment().get().as_str())?;
+        let dimensions = compartment
+            .spatial_dimensions()
+            .get_checked()
+            .ok()
+            .flatten();
+        if dimensions == Some(0.0) {
+            return None;
+        }
+        let size = compartment.size().get_checked().ok().flatten()?;
+        Some(concentration * size)
+    }
+
+    /// The value of the `fbc:chemicalFormula` attribute of this species.
+    pub fn fbc_chemical_formula(&self) -> Option<String> {
+        self.get_namespaced_attribute("chemicalFormula", URL_PACKAGE_FBC)
+    }
+
+    /// The number of atoms of each chemical element according to the `fbc:chemicalFormula`
+    /// of this species (e.g. `C6H12O6` yields `C: 6`, `H: 12` and `O: 6`).
+    ///
+    /// Returns `None` if the formula is missing or does not follow the fbc formula syntax
+    /// (element symbols, each followed by an optional count).
+    pub fn fbc_element_counts(&self) -> Option<HashMap<String, u64>> {
+        let formula = self.fbc_chemical_formula()?;
+        let mut counts = HashMap::new();
+        let mut chars = formula.trim().chars().peekable();
+        while let Some(first) = chars.next() {
+            if !first.is_ascii_uppercase() {
+                return None;
+            }
+            let mut element = first.to_string();
+            while let Some(c) = chars.next_if(|c| c.is_ascii_lowercase()) {
+                element.push(c);
+            }
+            let mut count = String::new();
+            while let Some(c) = chars.next_if(|c| c.is_ascii_digit()) {
+                count.push(c);
+            }
+            let count = if count.is_empty() {
+                1
+            } else {
+                count.parse::<u64>().ok()?
+            };
+            *counts.entry(element).or_insert(0) += count;
+        }
+        Some(counts)
+    }
 }
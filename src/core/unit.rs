@@ -4,9 +4,14 @@ use crate::xml::{
 };
 use sbml_macros::{SBase, XmlWrapper};
 use std::str::FromStr;
-use strum_macros::{Display, EnumString};
+use strum_macros::{Display, EnumIter, EnumString};
 
 /// Unit representation
+///
+/// In SBML Level 3, all of *kind*, *exponent*, *scale* and *multiplier* are required. Reading
+/// them from a [Unit] where they are missing panics, so documents should be type-checked
+/// first (a missing attribute is reported as rule 20421 by [crate::Sbml::validate]). New units
+/// should be created using [XmlDefault::default], which sets all four attributes.
 #[derive(Clone, Debug, XmlWrapper, SBase)]
 pub struct Unit(XmlElement);
 
@@ -41,7 +46,7 @@ impl XmlDefault for Unit {
 }
 
 /// Set of pre-defined base units that are allowed for unit definition
-#[derive(Debug, Display, EnumString, PartialEq)]
+#[derive(Debug, Display, EnumString, EnumIter, PartialEq)]
 pub enum BaseUnit {
     #[strum(serialize = "ampere")]
     Ampere,
@@ -111,6 +116,53 @@ pub enum BaseUnit {
     Weber,
 }
 
+impl BaseUnit {
+    /// Exponents of the seven SI base dimensions that make up this unit, in the order
+    /// *(mass, length, time, current, temperature, amount, luminous intensity)*.
+    ///
+    /// Derived units are expanded into base dimensions (e.g. `newton` is `kg·m·s⁻²`).
+    /// Dimensionless units (`dimensionless`, `item`, `avogadro`, `radian` and `steradian`)
+    /// have all exponents equal to zero. Scaling factors are ignored, i.e. `gram` and
+    /// `kilogram` have the same dimensions.
+    pub fn si_dimensions(&self) -> [i8; 7] {
+        match self {
+            BaseUnit::Ampere => [0, 0, 0, 1, 0, 0, 0],
+            BaseUnit::Avogadro => [0, 0, 0, 0, 0, 0, 0],
+            BaseUnit::Becquerel => [0, 0, -1, 0, 0, 0, 0],
+            BaseUnit::Candela => [0, 0, 0, 0, 0, 0, 1],
+            BaseUnit::Coulomb => [0, 0, 1, 1, 0, 0, 0],
+            BaseUnit::Dimensionless => [0, 0, 0, 0, 0, 0, 0],
+            BaseUnit::Farad => [-1, -2, 4, 2, 0, 0, 0],
+            BaseUnit::Gram => [1, 0, 0, 0, 0, 0, 0],
+            BaseUnit::Gray => [0, 2, -2, 0, 0, 0, 0],
+            BaseUnit::Hertz => [0, 0, -1, 0, 0, 0, 0],
+            BaseUnit::Henry => [1, 2, -2, -2, 0, 0, 0],
+            BaseUnit::Item => [0, 0, 0, 0, 0, 0, 0],
+            BaseUnit::Joule => [1, 2, -2, 0, 0, 0, 0],
+            BaseUnit::Katal => [0, 0, -1, 0, 0, 1, 0],
+            BaseUnit::Kelvin => [0, 0, 0, 0, 1, 0, 0],
+            BaseUnit::Kilogram => [1, 0, 0, 0, 0, 0, 0],
+            BaseUnit::Litre => [0, 3, 0, 0, 0, 0, 0],
+            BaseUnit::Lumen => [0, 0, 0, 0, 0, 0, 1],
+            BaseUnit::Lux => [0, -2, 0, 0, 0, 0, 1],
+            BaseUnit::Metre => [0, 1, 0, 0, 0, 0, 0],
+            BaseUnit::Mole => [0, 0, 0, 0, 0, 1, 0],
+            BaseUnit::Newton => [1, 1, -2, 0, 0, 0, 0],
+            BaseUnit::Ohm => [1, 2, -3, -2, 0, 0, 0],
+            BaseUnit::Pascal => [1, -1, -2, 0, 0, 0, 0],
+            BaseUnit::Radian => [0, 0, 0, 0, 0, 0, 0],
+            BaseUnit::Second => [0, 0, 1, 0, 0, 0, 0],
+            BaseUnit::Siemens => [-1, -2, 3, 2, 0, 0, 0],
+            BaseUnit::Sievert => [0, 2, -2, 0, 0, 0, 0],
+            BaseUnit::Steradian => [0, 0, 0, 0, 0, 0, 0],
+            BaseUnit::Tesla => [1, 0, -2, -1, 0, 0, 0],
+            BaseUnit::Volt => [1, 2, -3, -1, 0, 0, 0],
+            BaseUnit::Watt => [1, 2, -3, 0, 0, 0, 0],
+            BaseUnit::Weber => [1, 2, -2, -1, 0, 0, 0],
+        }
+    }
+}
+
 /// A conversion between an XML attribute and a [BaseUnit] value. Missing attribute value is
 /// interpreted as an error.
 ///
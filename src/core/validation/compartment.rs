@@ -4,7 +4,7 @@ use crate::core::validation::{
     apply_rule_10311, apply_rule_10312, apply_rule_10313, apply_rule_10401, apply_rule_10402,
     SbmlValidable,
 };
-use crate::core::{Compartment, SBase};
+use crate::core::{Compartment, Model, SBase};
 use crate::xml::{
     OptionalXmlChild, OptionalXmlProperty, RequiredXmlProperty, XmlProperty, XmlWrapper,
 };
@@ -36,6 +36,40 @@ impl SbmlValidable for Compartment {
             apply_rule_10401(&annotation, issues);
             apply_rule_10402(&annotation, issues);
         }
+        self.apply_zero_size_check(issues);
+    }
+}
+
+impl Compartment {
+    /// ### Zero size with non-empty species (lint)
+    /// A three-dimensional [Compartment] with `size="0"` which contains species with a non-zero
+    /// *initialAmount* is valid, but any concentration computed for these species is a division
+    /// by zero. Since this typically results in `NaN` values in simulators, we report a warning
+    /// (`lint-zero-size-compartment`).
+    pub(crate) fn apply_zero_size_check(&self, issues: &mut Vec<SbmlIssue>) {
+        let (dimensions, size) = (self.spatial_dimensions(), self.size());
+        if !dimensions.is_set() || !size.is_set() {
+            return;
+        }
+        if dimensions.get() != Some(3.0) || size.get() != Some(0.0) {
+            return;
+        }
+        let Some(model) = Model::for_child_element(self.xml_element()) else {
+            return;
+        };
+        let id = self.id().get();
+        for species in model.species_by_compartment(id.as_str()) {
+            let amount = species.initial_amount();
+            if amount.is_set() && amount.get().is_some_and(|it| it != 0.0) {
+                let message = format!(
+                    "The <species> ('{}') has a non-zero [initialAmount], but its \
+                    three-dimensional <compartment> ('{id}') has zero [size].",
+                    species.id().get()
+                );
+                let rule = "lint-zero-size-compartment";
+                issues.push(SbmlIssue::new_warning(rule, &species, message));
+            }
+        }
     }
 }
 
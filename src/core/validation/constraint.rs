@@ -1,3 +1,7 @@
+use crate::constants::element::{
+    MATHML_BINARY_OPERATORS, MATHML_BOOLEAN_OPERATORS, MATHML_NARY_OPERATORS,
+    MATHML_UNARY_OPERATORS,
+};
 use crate::core::validation::type_check::CanTypeCheck;
 use crate::core::validation::{
     apply_rule_10301, apply_rule_10307, apply_rule_10308, apply_rule_10309, apply_rule_10310,
@@ -33,7 +37,55 @@ impl SbmlValidable for Constraint {
         if let Some(math) = self.math().get() {
             math.validate(issues);
         }
+        self.apply_boolean_math_check(issues);
     }
 }
 
 impl CanTypeCheck for Constraint {}
+
+impl Constraint {
+    /// ### Boolean math (lint)
+    /// The *math* of a [Constraint] must evaluate to a boolean value. We do not infer the type
+    /// of the whole expression, but a numeric constant or an arithmetic operator at the top
+    /// level clearly indicates a value expression instead of a predicate, hence we report
+    /// a warning (`lint-constraint-not-boolean`). Rule 21001 itself is not checked.
+    pub(crate) fn apply_boolean_math_check(&self, issues: &mut Vec<SbmlIssue>) {
+        let Some(math) = self.math().get() else {
+            return;
+        };
+        let Some(expression) = math.xml_element().child_elements().into_iter().next() else {
+            return;
+        };
+        let description = match expression.tag_name().as_str() {
+            "cn" => "a numeric constant".to_string(),
+            "apply" => {
+                let Some(operator) = expression.get_child_at(0) else {
+                    return;
+                };
+                let operator = operator.tag_name();
+                let is_operator = [
+                    MATHML_UNARY_OPERATORS,
+                    MATHML_BINARY_OPERATORS,
+                    MATHML_NARY_OPERATORS,
+                ]
+                .iter()
+                .any(|it| it.contains(&operator.as_str()));
+                if !is_operator || MATHML_BOOLEAN_OPERATORS.contains(&operator.as_str()) {
+                    return;
+                }
+                format!("the arithmetic operator <{operator}>")
+            }
+            _ => return,
+        };
+
+        let message = format!(
+            "(Heuristic) The <math> of <constraint> should be a boolean expression, \
+            but its top-level element is {description}."
+        );
+        issues.push(SbmlIssue::new_warning(
+            "lint-constraint-not-boolean",
+            &math,
+            message,
+        ));
+    }
+}
@@ -1,4 +1,7 @@
-use crate::core::validation::type_check::{internal_type_check, type_check_of_list, CanTypeCheck};
+use crate::constants::namespaces::URL_SBML_CORE;
+use crate::core::validation::type_check::{
+    internal_type_check, tag_to_allowed_child_rule_id, type_check_of_list, CanTypeCheck,
+};
 use crate::core::validation::{
     apply_rule_10301, apply_rule_10307, apply_rule_10308, apply_rule_10309, apply_rule_10310,
     apply_rule_10312, apply_rule_10401, apply_rule_10402, validate_list_of_objects, SbmlValidable,
@@ -36,6 +39,9 @@ impl SbmlValidable for Event {
         if let Some(delay) = self.delay().get() {
             delay.validate(issues, identifiers, meta_ids);
         }
+        self.apply_child_order_check(issues);
+        self.apply_priority_with_delay_check(issues);
+        self.apply_assigned_variable_reference_check(issues);
         if let Some(list_of_event_assignments) = self.event_assignments().get() {
             validate_list_of_objects(&list_of_event_assignments, issues, identifiers, meta_ids);
             Event::apply_rule_10305(&list_of_event_assignments, issues);
@@ -64,6 +70,145 @@ impl CanTypeCheck for Event {
 }
 
 impl Event {
+    /// ### Order of children
+    /// The content model of an [Event] prescribes the order of its children: [Trigger],
+    /// [Priority], [Delay] and the list of [EventAssignment] objects. We report an error for
+    /// every child which appears after a child that should follow it. The specification has
+    /// no dedicated rule for the order of children, hence this is reported under the rule for
+    /// allowed children of an [Event] (the generic rule 10102).
+    pub(crate) fn apply_child_order_check(&self, issues: &mut Vec<SbmlIssue>) {
+        const ORDER: [&str; 4] = ["trigger", "priority", "delay", "listOfEventAssignments"];
+
+        let id = self.id().get().unwrap_or_default();
+        let mut last: Option<(usize, String)> = None;
+        for child in self.xml_element().child_elements() {
+            if child.namespace_url() != URL_SBML_CORE {
+                continue;
+            }
+            let tag = child.tag_name();
+            let Some(position) = ORDER.iter().position(|it| *it == tag) else {
+                continue;
+            };
+            match &last {
+                Some((last_position, last_tag)) if *last_position > position => {
+                    let message = format!(
+                        "The <{tag}> of <event> ('{id}') must appear before its <{last_tag}>."
+                    );
+                    let rule_id = tag_to_allowed_child_rule_id("event").unwrap_or("10102");
+                    issues.push(SbmlIssue::new_error(rule_id, &child, message));
+                }
+                _ => last = Some((position, tag)),
+            }
+        }
+    }
+
+    /// ### Priority with delay (lint)
+    /// A [Priority] only orders events that are *executed* simultaneously. When the event also
+    /// has a [Delay], the priority is evaluated at execution time, which can be long after the
+    /// trigger fired. The combination is valid, but it is subtle and frequently a modeling
+    /// error, hence we only report a warning (`lint-event-priority-with-delay`).
+    pub(crate) fn apply_priority_with_delay_check(&self, issues: &mut Vec<SbmlIssue>) {
+        if self.priority().is_set() && self.delay().is_set() {
+            let id = self.id().get().unwrap_or_default();
+            let message = format!(
+                "The <event> ('{id}') defines both <priority> and <delay>. The priority \
+                is only evaluated once the delay elapses, which is likely unintended."
+            );
+            issues.push(SbmlIssue::new_warning(
+                "lint-event-priority-with-delay",
+                self,
+                message,
+            ));
+        }
+    }
+
+    /// ### Delay and priority referencing assigned variables (lint)
+    /// If the [Delay] or [Priority] math of an [Event] references a variable that the same
+    /// event assigns in one of its [EventAssignment] objects, the value seen by the math depends
+    /// on the order in which (possibly simultaneous) events are executed. This is valid, but
+    /// ambiguous, hence we only report a warning (`lint-event-reads-assigned-variable`).
+    pub(crate) fn apply_assigned_variable_reference_check(&self, issues: &mut Vec<SbmlIssue>) {
+        let Some(event_assignments) = self.event_assignments().get() else {
+            return;
+        };
+        let assigned = event_assignments
+            .iter()
+            .map(|it| it.variable().get())
+            .collect::<HashSet<_>>();
+        let id = self.id().get().unwrap_or_default();
+
+        let maths = [
+            ("delay", self.delay().get().and_then(|it| it.math().get())),
+            (
+                "priority",
+                self.priority().get().and_then(|it| it.math().get()),
+            ),
+        ];
+        for (tag, math) in maths {
+            let Some(math) = math else {
+                continue;
+            };
+            let mut reported = HashSet::new();
+            for ci in math.recursive_child_elements_filtered(|it| it.tag_name() == "ci") {
+                let variable = ci.text_content_trimmed();
+                if assigned.contains(&variable) && reported.insert(variable.clone()) {
+                    let message = format!(
+                        "The <{tag}> of <event> ('{id}') references the variable ('{variable}') \
+                        which is assigned by the same event."
+                    );
+                    issues.push(SbmlIssue::new_warning(
+                        "lint-event-reads-assigned-variable",
+                        &math,
+                        message,
+                    ));
+                }
+            }
+        }
+    }
+
+    /// ### Simultaneous assignments without priority (lint)
+    /// Multiple [Event] objects may assign the same variable. If such events fire
+    /// simultaneously, the result depends on their execution order, which is only determined
+    /// when all of them define a [Priority]. This is not forbidden, but it is a common source of
+    /// nondeterminism, hence we report a warning (`lint-event-conflicting-assignments`) for every
+    /// variable assigned by multiple events of which at least one has no [Priority].
+    pub(crate) fn apply_conflicting_assignments_check(
+        list_of_events: &XmlList<Event>,
+        issues: &mut Vec<SbmlIssue>,
+    ) {
+        // Variables in the order of their first assignment, with all events assigning them.
+        let mut assignments: Vec<(String, Vec<Event>)> = Vec::new();
+        for event in list_of_events.iter() {
+            for variable in event.assigned_variables() {
+                match assignments.iter_mut().find(|(it, _)| *it == variable) {
+                    Some((_, events)) => events.push(event.clone()),
+                    None => assignments.push((variable, vec![event.clone()])),
+                }
+            }
+        }
+
+        for (variable, events) in assignments {
+            if events.len() < 2 || events.iter().all(|it| it.priority().is_set()) {
+                continue;
+            }
+            let ids = events
+                .iter()
+                .map(|it| format!("'{}'", it.id().get().unwrap_or_default()))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let message = format!(
+                "The variable ('{variable}') is assigned by multiple events ({ids}), but not all \
+                of them define a <priority>. If the events fire simultaneously, the result is \
+                ambiguous. Consider setting priorities."
+            );
+            issues.push(SbmlIssue::new_warning(
+                "lint-event-conflicting-assignments",
+                &events[1],
+                message,
+            ));
+        }
+    }
+
     /// ### Rule 10305
     /// In every [Event] object, the value of the attribute *variable* within each [EventAssignment]
     /// subobject must be unique across the set of all such [EventAssignment] subobjects within
@@ -33,6 +33,55 @@ impl SbmlValidable for FunctionDefinition {
         if let Some(math) = self.math().get() {
             math.validate(issues);
         }
+        self.apply_bvar_checks(issues);
+    }
+}
+
+impl FunctionDefinition {
+    /// ### Lambda arguments (lint)
+    /// Every *bvar* of the *lambda* in a [FunctionDefinition] must declare a distinct argument
+    /// name, otherwise the function cannot be evaluated (reported as an error,
+    /// `lint-duplicate-bvar`). Furthermore, every argument should be used in the function body.
+    /// An unused argument is allowed, but suspicious, hence it is only reported as info
+    /// (`lint-unused-bvar`).
+    pub(crate) fn apply_bvar_checks(&self, issues: &mut Vec<SbmlIssue>) {
+        let id = self.id().get().unwrap_or_default();
+        let arguments = self.argument_names();
+
+        let mut declared = HashSet::new();
+        for argument in &arguments {
+            if !declared.insert(argument.as_str()) {
+                let message = format!(
+                    "The argument ('{argument}') is declared multiple times in the <lambda> \
+                    of <functionDefinition> ('{id}')."
+                );
+                let rule = "lint-duplicate-bvar";
+                issues.push(SbmlIssue::new_error(rule, self.xml_element(), message));
+            }
+        }
+
+        let Some(body) = self.body() else {
+            return;
+        };
+        let mut used = body.recursive_child_elements_filtered(|it| it.tag_name() == "ci");
+        if body.tag_name() == "ci" {
+            used.push(body);
+        }
+        let used = used
+            .into_iter()
+            .map(|ci| ci.text_content_trimmed())
+            .collect::<HashSet<_>>();
+        let mut reported = HashSet::new();
+        for argument in &arguments {
+            if !used.contains(argument) && reported.insert(argument) {
+                let message = format!(
+                    "The argument ('{argument}') of <functionDefinition> ('{id}') \
+                    is not used in the function body."
+                );
+                let rule = "lint-unused-bvar";
+                issues.push(SbmlIssue::new_info(rule, self.xml_element(), message));
+            }
+        }
     }
 }
 
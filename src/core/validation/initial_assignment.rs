@@ -1,10 +1,11 @@
+use crate::constants::namespaces::URL_SBML_CORE;
 use crate::core::validation::type_check::CanTypeCheck;
 use crate::core::validation::{
     apply_rule_10301, apply_rule_10307, apply_rule_10308, apply_rule_10309, apply_rule_10310,
     apply_rule_10312, apply_rule_10401, apply_rule_10402, SbmlValidable,
 };
-use crate::core::{InitialAssignment, SBase};
-use crate::xml::{OptionalXmlChild, OptionalXmlProperty, XmlWrapper};
+use crate::core::{InitialAssignment, Model, SBase};
+use crate::xml::{OptionalXmlChild, OptionalXmlProperty, RequiredXmlProperty, XmlList, XmlWrapper};
 use crate::SbmlIssue;
 use std::collections::HashSet;
 
@@ -37,3 +38,101 @@ impl SbmlValidable for InitialAssignment {
 }
 
 impl CanTypeCheck for InitialAssignment {}
+
+impl InitialAssignment {
+    /// ### Rule 20801
+    /// The value of a *symbol* attribute in an [InitialAssignment] object must be the
+    /// identifier of a [Compartment](crate::core::Compartment), [Species](crate::core::Species),
+    /// [SpeciesReference](crate::core::SpeciesReference) or [Parameter](crate::core::Parameter)
+    /// object defined in the model.
+    ///
+    /// Identifiers of elements defined by SBML packages are not reported, since a package
+    /// can extend the set of elements that can be assigned.
+    pub(crate) fn apply_rule_20801(
+        list_of_initial_assignments: &XmlList<InitialAssignment>,
+        issues: &mut Vec<SbmlIssue>,
+    ) {
+        let model = Model::for_child_element(list_of_initial_assignments.xml_element()).unwrap();
+        let assignable: HashSet<String> = model
+            .species_identifiers()
+            .into_iter()
+            .chain(model.compartment_identifiers())
+            .chain(model.parameter_identifiers())
+            .chain(model.species_reference_identifiers())
+            .collect();
+
+        for initial_assignment in list_of_initial_assignments.iter() {
+            let symbol = initial_assignment.symbol().get();
+            if assignable.contains(&symbol) {
+                continue;
+            }
+
+            let target = model
+                .recursive_child_elements_filtered(|it| {
+                    it.get_attribute("id").as_deref() == Some(symbol.as_str())
+                })
+                .into_iter()
+                .next();
+            let message = match target {
+                Some(target) if target.namespace_url() != URL_SBML_CORE => continue,
+                Some(target) => format!(
+                    "The symbol ('{symbol}') of <initialAssignment> refers to a <{}>. Only \
+                    a <compartment>, <species>, <speciesReference> or <parameter> can be assigned.",
+                    target.tag_name()
+                ),
+                None => format!(
+                    "The symbol ('{symbol}') of <initialAssignment> does not refer to any \
+                    <compartment>, <species>, <speciesReference> or <parameter>."
+                ),
+            };
+            issues.push(SbmlIssue::new_error("20801", &initial_assignment, message));
+        }
+    }
+
+    /// ### Rule 20802
+    /// The value of a *symbol* attribute in any [InitialAssignment] object cannot also appear
+    /// as the value of a *symbol* attribute in another [InitialAssignment] object.
+    pub(crate) fn apply_rule_20802(
+        list_of_initial_assignments: &XmlList<InitialAssignment>,
+        issues: &mut Vec<SbmlIssue>,
+    ) {
+        let mut symbols: HashSet<String> = HashSet::new();
+
+        for initial_assignment in list_of_initial_assignments.iter() {
+            let symbol = initial_assignment.symbol().get();
+            if symbols.contains(&symbol) {
+                let message = format!(
+                    "The symbol ('{symbol}') of <initialAssignment> is already assigned by \
+                    another <initialAssignment>."
+                );
+                issues.push(SbmlIssue::new_error("20802", &initial_assignment, message));
+            } else {
+                symbols.insert(symbol);
+            }
+        }
+    }
+
+    /// ### Rule 20803
+    /// The value of a *symbol* attribute in any [InitialAssignment] object cannot also appear
+    /// as the value of a *variable* attribute in an
+    /// [AssignmentRule](crate::core::rule::AssignmentRule) object. In other words, a model
+    /// component cannot be the subject of both an initial assignment and an assignment rule.
+    pub(crate) fn apply_rule_20803(
+        list_of_initial_assignments: &XmlList<InitialAssignment>,
+        issues: &mut Vec<SbmlIssue>,
+    ) {
+        let model = Model::for_child_element(list_of_initial_assignments.xml_element()).unwrap();
+        let assignment_rule_variables = model.assignment_rule_variables();
+
+        for initial_assignment in list_of_initial_assignments.iter() {
+            let symbol = initial_assignment.symbol().get();
+            if assignment_rule_variables.contains(&symbol) {
+                let message = format!(
+                    "The symbol ('{symbol}') of <initialAssignment> is also a variable \
+                    of <assignmentRule>."
+                );
+                issues.push(SbmlIssue::new_error("20803", &initial_assignment, message));
+            }
+        }
+    }
+}
@@ -2,7 +2,8 @@ use std::str::FromStr;
 
 use crate::constants::element::{
     MATHML_ALLOWED_CHILDREN_BY_ATTR, MATHML_ALLOWED_DEFINITION_URLS, MATHML_ALLOWED_TYPES,
-    MATHML_BINARY_OPERATORS, MATHML_UNARY_OPERATORS,
+    MATHML_BINARY_OPERATORS, MATHML_NARY_OPERATORS, MATHML_TIME_DEFINITION_URL,
+    MATHML_UNARY_OPERATORS,
 };
 use crate::core::validation::{apply_rule_10313, get_allowed_children, matches_unit_sid_pattern};
 use crate::core::{BaseUnit, FunctionDefinition, KineticLaw, Math, Model};
@@ -19,6 +20,7 @@ impl Math {
     ///  - **[10207](Math::apply_rule_10207)** - Ensures *type* attribute correct value.
     ///  - **[10208](Math::apply_rule_10208)** - Validates *lambda* element usage.
     ///  - **[10214](Math::apply_rule_10214)** - Validates first *ci* element usage outside [FunctionDefinition].
+    ///  - **[10214](Math::apply_function_call_target_rule)** - Validates first *ci* element usage inside [FunctionDefinition].
     ///  - **[10215](Math::apply_rule_10215)** - Validates non-first *ci* element usage outside [FunctionDefinition].
     ///  - **[10216](Math::apply_rule_10216)** - Validates [LocalParameter](crate::core::LocalParameter) *id* occurrence.
     ///  - **[10218](Math::apply_rule_10218)** - Validates number of arguments for operators.
@@ -28,9 +30,19 @@ impl Math {
     ///  - **[10223](Math::apply_rule_10223)** - Validates *rateOf* *csymbol* element has single argument.
     ///  - **[10224](Math::apply_rule_10224)** - Validates the argument of *rateOf* *csymbol* element.
     ///  - **[10225](Math::apply_rule_10225)** - Validates the value of argument of *rateOf* *csymbol* element.
+    ///  - **[10226](Math::apply_rateof_target_rule)** - Validates the argument of *rateOf* *csymbol* refers to a quantity.
     ///
     /// Rule **10201** is applied as part of the type check, because without it,
-    /// we cannot create a valid [Math] element.
+    /// we cannot create a valid [Math] element. However, the requirement that the content is a
+    /// single MathML expression is checked [here](Math::apply_single_expression_rule).
+    /// Uses of the *time* **csymbol** in time-independent math are reported
+    /// [here](Math::apply_time_independent_math_check).
+    ///
+    /// Content nested deeper than the limit given to [Sbml::validate_with_max_math_depth] is
+    /// reported before the validation starts (`lint-math-depth`), and is never validated
+    /// by these rules.
+    ///
+    /// [Sbml::validate_with_max_math_depth]: crate::Sbml::validate_with_max_math_depth
     ///
     /// ### Ignored rules as of SBML Level 3 Version 1 Core:
     /// - **10209** - "The arguments of the MathML logical operators and, not, or, and xor must evaluate to Boolean values."
@@ -50,6 +62,7 @@ impl Math {
     ///   AssignmentRule, math in RateRule, math in AlgebraicRule, math in Event Delay, and math in EventAssignment."
     ///
     pub(crate) fn validate(&self, issues: &mut Vec<SbmlIssue>) {
+        self.apply_single_expression_rule(issues);
         self.apply_rule_10202(issues);
         self.apply_rule_10203(issues);
         self.apply_rule_10204(issues);
@@ -58,8 +71,10 @@ impl Math {
         self.apply_rule_10207(issues);
         self.apply_rule_10208(issues);
         self.apply_rule_10214(issues);
+        self.apply_function_call_target_rule(issues);
         self.apply_rule_10215(issues);
         self.apply_rule_10216(issues);
+        self.apply_time_independent_math_check(issues);
         self.apply_rule_10218(issues);
         self.apply_rule_10219(issues);
         self.apply_rule_10220(issues);
@@ -67,10 +82,37 @@ impl Math {
         self.apply_rule_10223(issues);
         self.apply_rule_10224(issues);
         self.apply_rule_10225(issues);
+        self.apply_rateof_target_rule(issues);
         self.apply_rule_10311(issues);
         self.apply_rule_10313(issues);
     }
 
+    /// ### Rule 10201 (single expression)
+    /// The content of a [Math] element must be exactly one MathML expression. If the content
+    /// is wrapped in a **semantics** element, then the **semantics** element must contain
+    /// exactly one expression (besides its **annotation** and **annotation-xml** elements).
+    pub(crate) fn apply_single_expression_rule(&self, issues: &mut Vec<SbmlIssue>) {
+        let mut parent = self.xml_element().clone();
+        let mut children = parent.child_elements();
+        if children.len() == 1 && children[0].tag_name() == "semantics" {
+            parent = children.remove(0);
+            children = parent
+                .child_elements()
+                .into_iter()
+                .filter(|it| it.tag_name() != "annotation" && it.tag_name() != "annotation-xml")
+                .collect();
+        }
+
+        if children.len() != 1 {
+            let message = format!(
+                "The <{}> element must contain exactly one MathML expression, but {} were found.",
+                parent.tag_name(),
+                children.len()
+            );
+            issues.push(SbmlIssue::new_error("10201", &parent, message));
+        }
+    }
+
     // TODO: Complete implementation when adding extensions/packages is solved
     /// ### Rule 10202
     /// Validates that only the allowed subset of **MathML** child elements are present within
@@ -147,13 +189,7 @@ impl Math {
     /// definitionURL attribute of a csymbol, and if so, the package must define **required="true"**
     /// on the SBML container element [**sbml**](crate::Sbml).
     pub(crate) fn apply_rule_10205(&self, issues: &mut Vec<SbmlIssue>) {
-        let children_of_interest = self.recursive_child_elements_filtered(|child| {
-            child.tag_name() == "csymbol" && child.has_attribute("definitionURL")
-        });
-
-        for child in children_of_interest {
-            // Unwrap is safe, because we only consider children where the attribute is set.
-            let value = child.get_attribute("definitionURL").unwrap();
+        for (child, value) in self.csymbol_definition_urls() {
             if !MATHML_ALLOWED_DEFINITION_URLS.contains(&value.as_str()) {
                 let message = format!(
                     "Invalid definitionURL value found '{}'. Permitted values are: {:?}",
@@ -305,7 +341,7 @@ impl Math {
 
             for child in children_of_interest {
                 // This unwrap must succeed because we enforced that ci is the first child.
-                let value = child.get_child_at(0).unwrap().text_content();
+                let value = child.get_child_at(0).unwrap().text_content_trimmed();
 
                 if !identifiers.contains(&value) {
                     let message = format!(
@@ -319,6 +355,47 @@ impl Math {
         }
     }
 
+    /// ### Rule 10214 (inside function definitions)
+    /// Rule 10214 only covers math outside of a [FunctionDefinition]. However, the body of
+    /// a [FunctionDefinition] can only call other [FunctionDefinition] objects as well (the order
+    /// in which they are declared does not matter), hence a **ci** element which is the first
+    /// element within a MathML **apply** must also refer to an existing [FunctionDefinition].
+    pub(crate) fn apply_function_call_target_rule(&self, issues: &mut Vec<SbmlIssue>) {
+        let parent_name = self.parent().unwrap().tag_name();
+        if parent_name != "functionDefinition" {
+            return;
+        }
+
+        let children_of_interest = self.recursive_child_elements_filtered(|child| {
+            child.tag_name() == "apply"
+                && child
+                    .get_child_at(0)
+                    .map(|it| it.tag_name() == "ci")
+                    .unwrap_or(false)
+        });
+        if children_of_interest.is_empty() {
+            return;
+        }
+
+        let identifiers = Model::for_child_element(self.xml_element())
+            .unwrap()
+            .function_definition_identifiers();
+
+        for child in children_of_interest {
+            // This unwrap must succeed because we enforced that ci is the first child.
+            let value = child.get_child_at(0).unwrap().text_content_trimmed();
+
+            if !identifiers.contains(&value) {
+                let message = format!(
+                    "Function '{value}' called within a <functionDefinition> is not defined. \
+                    Function referred by <ci> must be defined in <functionDefinition> object \
+                    with relevant identifier (id)."
+                );
+                issues.push(SbmlIssue::new_error("10214", &child, message));
+            }
+        }
+    }
+
     /// ### Rule 10215
     /// Outside a [FunctionDefinition] object, if a MathML **ci** element is not the first element within
     /// a MathML **apply**, then the **ci** element's value may only be chosen from the following set of
@@ -359,7 +436,7 @@ impl Math {
                 .collect::<Vec<_>>();
 
             for ci in ci_elements {
-                let value = ci.text_content();
+                let value = ci.text_content_trimmed();
 
                 if !identifiers.contains(&value) {
                     let message = format!(
@@ -391,13 +468,13 @@ impl Math {
             .recursive_child_elements()
             .into_iter()
             .filter(|child| child.tag_name() == "bvar")
-            .filter_map(|bvar| bvar.get_child_at(0).map(|it| it.text_content()))
+            .filter_map(|bvar| bvar.get_child_at(0).map(|it| it.text_content_trimmed()))
             .collect::<Vec<String>>();
 
         let ci_elements = self.recursive_child_elements_filtered(|child| child.tag_name() == "ci");
 
         for ci in ci_elements {
-            let value = ci.text_content();
+            let value = ci.text_content_trimmed();
             if !b_variables.contains(&value)
                 && all_local_param_ids.contains(&value)
                 && !scoped_local_param_ids.contains(&value)
@@ -408,8 +485,57 @@ impl Math {
         }
     }
 
+    /// All **csymbol** elements in this [Math] which declare a **definitionURL**, together with
+    /// the value of the attribute.
+    fn csymbol_definition_urls(&self) -> Vec<(XmlElement, String)> {
+        self.recursive_child_elements_filtered(|child| child.tag_name() == "csymbol")
+            .into_iter()
+            .filter_map(|child| {
+                let url = child.get_attribute("definitionURL")?;
+                Some((child, url))
+            })
+            .collect()
+    }
+
+    /// ### Time in time-independent math (lint)
+    /// The math of a [FunctionDefinition] should not depend on the simulation time (it can
+    /// only use its arguments), and the math of an
+    /// [InitialAssignment](crate::core::InitialAssignment) is only evaluated at the start of
+    /// the simulation, where the **time** **csymbol** is always zero. Such uses of **time** are
+    /// likely a mistake, hence we report a warning (`lint-time-in-static-math`).
+    pub(crate) fn apply_time_independent_math_check(&self, issues: &mut Vec<SbmlIssue>) {
+        let Some(parent) = self.parent() else {
+            return;
+        };
+        let parent_name = parent.tag_name();
+        if parent_name != "functionDefinition" && parent_name != "initialAssignment" {
+            return;
+        }
+
+        let time_symbols = self
+            .csymbol_definition_urls()
+            .into_iter()
+            .filter(|(_, url)| url == MATHML_TIME_DEFINITION_URL);
+
+        for (csymbol, _) in time_symbols {
+            let message = format!(
+                "The <csymbol> 'time' is used in the <math> of <{parent_name}>, \
+                which is not expected to depend on time."
+            );
+            issues.push(SbmlIssue::new_warning(
+                "lint-time-in-static-math",
+                &csymbol,
+                message,
+            ));
+        }
+    }
+
     /// ### Rule 10218
     /// A MathML operator must be supplied the number of arguments appropriate for that operator.
+    ///
+    /// Additionally, the first child of every **apply** must be an operator, a function call
+    /// (**ci**) or a **csymbol**. Anything else (e.g. `<apply><cn>2</cn></apply>`) cannot be
+    /// applied to the remaining arguments.
     pub(crate) fn apply_rule_10218(&self, issues: &mut Vec<SbmlIssue>) {
         let apply_elements =
             self.recursive_child_elements_filtered(|child| child.tag_name() == "apply");
@@ -450,6 +576,15 @@ impl Math {
                     let message = format!("Invalid number ({arg_count}) of arguments for binary operator <{operator}>.");
                     issues.push(SbmlIssue::new_error("10218", &apply, message));
                 }
+            } else if !MATHML_NARY_OPERATORS.contains(&operator.as_str())
+                && operator != "ci"
+                && operator != "csymbol"
+            {
+                let message = format!(
+                    "The element <{operator}> is not an operator and cannot be the first child \
+                    of <apply>. Expected an operator, a function call (<ci>) or a <csymbol>."
+                );
+                issues.push(SbmlIssue::new_error("10218", &apply, message));
             }
         }
 
@@ -493,7 +628,7 @@ impl Math {
 
             let arg_count = children.len() - 1;
             let func_identifiers = model.function_definition_identifiers();
-            let id = function_call.text_content();
+            let id = function_call.text_content_trimmed();
 
             if func_identifiers.contains(&id) {
                 // Only check argument count if the function is actually declared.
@@ -625,7 +760,7 @@ impl Math {
 
         for apply in apply_elements {
             let ci = apply.child_elements()[1].clone(); // This is safe due to the filter expression.
-            let value = ci.text_content();
+            let value = ci.text_content_trimmed();
             let is_target_constant = model.is_rateof_target_constant(value.as_str());
 
             if assignment_rule_variables.contains(&value) {
@@ -672,7 +807,7 @@ impl Math {
 
         for apply in apply_elements {
             let ci = apply.child_elements()[1].clone(); // This is safe due to the filter expression.
-            let value = ci.text_content();
+            let value = ci.text_content_trimmed();
 
             let Some(species) = model.find_species(value.as_str()) else {
                 continue;
@@ -700,6 +835,78 @@ impl Math {
         }
     }
 
+    /// ### Rule 10226 area
+    /// The target of a *rateOf* **csymbol** function must be the identifier of a
+    /// [Species](crate::core::Species), [Compartment](crate::core::Compartment),
+    /// [Parameter](crate::core::Parameter), [SpeciesReference](crate::core::SpeciesReference)
+    /// or [Reaction](crate::core::Reaction) of the enclosing [Model]. In particular, it cannot
+    /// be the identifier of a [LocalParameter](crate::core::LocalParameter).
+    ///
+    /// Undeclared targets outside of a [FunctionDefinition] are not reported here, since they
+    /// are already reported by [rule 10215](Math::apply_rule_10215). Inside a [FunctionDefinition],
+    /// the target can be also an argument of the function.
+    pub(crate) fn apply_rateof_target_rule(&self, issues: &mut Vec<SbmlIssue>) {
+        let apply_elements = self.recursive_child_elements_filtered(|child| {
+            child.tag_name() == "apply" && {
+                let children = child.child_elements();
+                children.len() == 2
+                    && children[1].tag_name() == "ci"
+                    && children[0]
+                        .get_attribute("definitionURL")
+                        .is_some_and(|url| url == "http://www.sbml.org/sbml/symbols/rateOf")
+            }
+        });
+        if apply_elements.is_empty() {
+            return;
+        }
+
+        let model = Model::for_child_element(self.xml_element()).unwrap();
+        let function_definition = FunctionDefinition::for_child_element(self.xml_element());
+        let quantities = [
+            model.species_identifiers(),
+            model.compartment_identifiers(),
+            model.parameter_identifiers(),
+            model.species_reference_identifiers(),
+            model.reaction_identifiers(),
+        ]
+        .concat();
+        let local_parameters = model.local_parameter_identifiers();
+
+        for apply in apply_elements {
+            let ci = apply.child_elements()[1].clone(); // This is safe due to the filter expression.
+            let value = ci.text_content_trimmed();
+            if quantities.contains(&value) {
+                continue;
+            }
+
+            let message = if local_parameters.contains(&value) {
+                format!(
+                    "The target ('{value}') of rateOf <csymbol> is a <localParameter>. \
+                    Only a <species>, <compartment>, <parameter>, <speciesReference> \
+                    or <reaction> can be the target of rateOf."
+                )
+            } else if let Some(function_definition) = &function_definition {
+                let arguments = function_definition
+                    .recursive_child_elements_filtered(|it| it.tag_name() == "bvar")
+                    .iter()
+                    .flat_map(|it| it.child_elements())
+                    .map(|it| it.text_content_trimmed())
+                    .collect::<Vec<_>>();
+                if arguments.contains(&value) {
+                    continue;
+                }
+                format!(
+                    "The target ('{value}') of rateOf <csymbol> is neither an argument \
+                    of the <functionDefinition> nor a declared quantity."
+                )
+            } else {
+                // Reported by rule 10215.
+                continue;
+            };
+            issues.push(SbmlIssue::new_error("10226", &ci, message));
+        }
+    }
+
     /// ### Rule 10311
     /// The SBML *units* attribute on MathML **cn** elements must always conform to the syntax of the
     /// SBML data type **UnitSId**. Full description of the rule [here](crate::core::validation::apply_rule_10311).
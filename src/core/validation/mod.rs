@@ -3,13 +3,18 @@ use std::collections::HashSet;
 use const_format::formatcp;
 use regex::Regex;
 
-use crate::constants::element::{ALLOWED_CHILDREN, MATHML_ALLOWED_CHILDREN};
-use crate::core::{BaseUnit, Model, SBase};
+use crate::constants::element::{
+    ALLOWED_CHILDREN, MATHML_ALLOWED_CHILDREN, NON_FINITE_VALUE_ATTRIBUTES,
+};
+use crate::constants::namespaces::{URL_MATHML, URL_RDF, URL_SBML_CORE};
+use crate::core::{BaseUnit, Math, Model, SBase};
+use crate::xml::OptionalProperty;
 use crate::xml::OptionalXmlProperty;
 use crate::xml::XmlElement;
 use crate::xml::XmlList;
 use crate::xml::XmlWrapper;
 use crate::SbmlIssue;
+use type_check::CanTypeCheck;
 
 mod compartment;
 mod constraint;
@@ -53,6 +58,71 @@ pub(crate) trait SbmlValidable: XmlWrapper {
     );
 }
 
+/// Validation of a single SBML element (and all its descendants) outside of the full
+/// [crate::Sbml::validate] procedure. This is useful for incremental editing, where
+/// re-validating the whole document after every change is wasteful.
+pub trait ValidateSubtree {
+    /// Type-check and validate this element and its descendants, returning the discovered
+    /// issues.
+    ///
+    /// Identifier uniqueness (rules 10301 and 10307) is checked against the identifiers
+    /// declared elsewhere in the enclosing document, but the check is approximate: a duplicate
+    /// is reported on the element inside the subtree (even if the other occurrence comes first
+    /// in the document), and duplicates outside the subtree are not reported at all.
+    /// Document-level checks (e.g. on package elements) are not performed.
+    fn validate_subtree(&self) -> Vec<SbmlIssue>;
+}
+
+impl<T: SbmlValidable + CanTypeCheck> ValidateSubtree for T {
+    fn validate_subtree(&self) -> Vec<SbmlIssue> {
+        let mut issues = vec![];
+        self.type_check(&mut issues);
+        if !issues.is_empty() {
+            return issues;
+        }
+        apply_math_depth_check(self.xml_element(), Math::DEFAULT_MAX_DEPTH, &mut issues);
+        if !issues.is_empty() {
+            return issues;
+        }
+
+        let subtree = self
+            .recursive_child_elements()
+            .into_iter()
+            .chain(std::iter::once(self.xml_element().clone()))
+            .map(|it| it.raw_element())
+            .collect::<HashSet<_>>();
+        let root = self.read_doc().root_element();
+        let mut identifiers = HashSet::new();
+        let mut meta_ids = HashSet::new();
+        if let Some(root) = root {
+            let root = XmlElement::new_raw(self.document(), root);
+            let mut elements = root.recursive_child_elements();
+            elements.push(root);
+            for element in elements {
+                if subtree.contains(&element.raw_element())
+                    || element.namespace_url() != URL_SBML_CORE
+                {
+                    continue;
+                }
+                if let Some(meta_id) = element.get_attribute("metaid") {
+                    meta_ids.insert(meta_id);
+                }
+                // Local parameters, unit definitions and the `sbml` element are not part of
+                // the model's identifier scope.
+                let tag_name = element.tag_name();
+                if !["localParameter", "unitDefinition", "sbml"].contains(&tag_name.as_str()) {
+                    if let Some(id) = element.get_attribute("id") {
+                        identifiers.insert(id);
+                    }
+                }
+            }
+        }
+
+        self.validate(&mut issues, &mut identifiers, &mut meta_ids);
+        issues
+    }
+}
+
 /// Executes a validation of xml list object itself and all its children.
 pub(crate) fn validate_list_of_objects<T: SbmlValidable>(
     list: &XmlList<T>,
@@ -114,6 +184,16 @@ fn check_identifier_uniqueness(
     }
 }
 
+/// Resolves the rule ID which requires the *variable* of the given assignment element
+/// (`assignmentRule`, `rateRule` or `eventAssignment`) to refer to a non-constant object.
+pub(crate) fn constant_target_rule_id(tag_name: &str) -> &'static str {
+    match tag_name {
+        "assignmentRule" => "20903",
+        "rateRule" => "20904",
+        _ => "21212",
+    }
+}
+
 /// Checks that a given value conforms to the syntax described in pattern.
 fn matches_pattern(value: &Option<String>, pattern: &Regex) -> bool {
     if let Some(value) = value {
@@ -123,19 +203,19 @@ fn matches_pattern(value: &Option<String>, pattern: &Regex) -> bool {
 }
 
 /// Check that a given value conforms to the **SId** syntax.
-fn matches_sid_pattern(value: &Option<String>) -> bool {
+pub(crate) fn matches_sid_pattern(value: &Option<String>) -> bool {
     let pattern = Regex::new(r"^[a-zA-Z_][a-zA-Z0-9_]*$").unwrap();
     matches_pattern(value, &pattern)
 }
 
 /// Checks that a given value conforms to the **SBOTerm** syntax.
-fn matches_sboterm_pattern(value: &Option<String>) -> bool {
+pub(crate) fn matches_sboterm_pattern(value: &Option<String>) -> bool {
     let pattern = Regex::new(r"^SBO:\d{7}$").unwrap();
     matches_pattern(value, &pattern)
 }
 
 /// Checks that a given value conforms to the **XML 1.0 ID** syntax.
-fn matches_xml_id_pattern(value: &Option<String>) -> bool {
+pub(crate) fn matches_xml_id_pattern(value: &Option<String>) -> bool {
     let pattern = formatcp!(
         "^[{0}_:][{0}{1}.\\-_:{2}{3}]*$",
         xml_definitions::build_letter_group(),
@@ -226,6 +306,208 @@ pub(crate) fn apply_rule_10308(
     }
 }
 
+/// Applies [rule 10308](apply_rule_10308) to every SBML package element (e.g. `layout`, `fbc`
+/// or `qual` elements) below `xml_element`. Core elements are not covered here, because
+/// they are checked by their own [SbmlValidable::validate] implementations.
+pub(crate) fn apply_rule_10308_to_package_elements(
+    xml_element: &XmlElement,
+    issues: &mut Vec<SbmlIssue>,
+) {
+    let package_elements = xml_element.recursive_child_elements_filtered(|child| {
+        let namespace = child.namespace_url();
+        namespace.starts_with("http://www.sbml.org/sbml/") && namespace != URL_SBML_CORE
+    });
+    for element in package_elements {
+        let sbo_term = OptionalProperty::<String>::new(&element, "sboTerm");
+        apply_rule_10308(sbo_term.get(), &element, issues);
+    }
+}
+
+/// ### RDF annotations (lint)
+/// Following the MIRIAM conventions, the `rdf:Description` elements in the annotation of an
+/// element must refer to that element using `rdf:about="#<metaid>"`. For every SBML core
+/// element below (and including) `xml_element`, we report a warning if its annotation
+/// contains RDF but the element has no *metaid*, or if the `rdf:about` of a description
+/// does not reference the *metaid* of the element (`lint-rdf-about`).
+pub(crate) fn apply_rdf_about_check(xml_element: &XmlElement, issues: &mut Vec<SbmlIssue>) {
+    let mut elements = xml_element.recursive_child_elements();
+    elements.insert(0, xml_element.clone());
+    for element in elements {
+        if element.namespace_url() != URL_SBML_CORE || element.tag_name() == "annotation" {
+            continue;
+        }
+        let descriptions = element
+            .child_elements_filtered(|it| {
+                it.tag_name() == "annotation" && it.namespace_url() == URL_SBML_CORE
+            })
+            .iter()
+            .flat_map(|it| it.child_elements())
+            .filter(|it| it.tag_name() == "RDF" && it.namespace_url() == URL_RDF)
+            .flat_map(|it| it.child_elements())
+            .filter(|it| it.tag_name() == "Description" && it.namespace_url() == URL_RDF)
+            .collect::<Vec<_>>();
+        if descriptions.is_empty() {
+            continue;
+        }
+
+        let tag_name = element.tag_name();
+        let Some(meta_id) = element.get_attribute("metaid") else {
+            let message =
+                format!("The <{tag_name}> has an RDF annotation, but no [metaid] to refer to.");
+            issues.push(SbmlIssue::new_warning("lint-rdf-about", &element, message));
+            continue;
+        };
+        let expected = format!("#{meta_id}");
+        for description in descriptions {
+            let about = description.get_namespaced_attribute("about", URL_RDF);
+            if about.as_deref() != Some(expected.as_str()) {
+                let about = about.unwrap_or_default();
+                let message = format!(
+                    "The [rdf:about] ('{about}') of <rdf:Description> does not reference \
+                    the [metaid] ('{meta_id}') of the annotated <{tag_name}>."
+                );
+                issues.push(SbmlIssue::new_warning(
+                    "lint-rdf-about",
+                    &description,
+                    message,
+                ));
+            }
+        }
+    }
+}
+
+/// ### Order of notes and annotation (lint)
+/// In the content model of SBase, the *notes* element comes before the *annotation* element,
+/// and both come before any other child element. Most parsers tolerate a different order,
+/// hence for every SBML core element below (and including) `xml_element`, we only report
+/// an info message when its *notes* or *annotation* is out of order
+/// (`lint-sbase-children-order`).
+pub(crate) fn apply_sbase_children_order_check(
+    xml_element: &XmlElement,
+    issues: &mut Vec<SbmlIssue>,
+) {
+    let mut elements = xml_element.recursive_child_elements();
+    elements.insert(0, xml_element.clone());
+    for element in elements {
+        let tag_name = element.tag_name();
+        if element.namespace_url() != URL_SBML_CORE
+            || tag_name == "notes"
+            || tag_name == "annotation"
+        {
+            continue;
+        }
+        let children = element.child_elements();
+        let position = |name: &str| {
+            children
+                .iter()
+                .position(|it| it.tag_name() == name && it.namespace_url() == URL_SBML_CORE)
+        };
+        let notes = position("notes");
+        let annotation = position("annotation");
+        let content = children.iter().position(|it| {
+            let name = it.tag_name();
+            it.namespace_url() != URL_SBML_CORE || (name != "notes" && name != "annotation")
+        });
+
+        if let (Some(notes), Some(annotation)) = (notes, annotation) {
+            if annotation < notes {
+                let message =
+                    format!("The <annotation> of <{tag_name}> should come after its <notes>.");
+                issues.push(SbmlIssue::new_info(
+                    "lint-sbase-children-order",
+                    &element,
+                    message,
+                ));
+            }
+        }
+        for (name, position) in [("notes", notes), ("annotation", annotation)] {
+            if let (Some(position), Some(content)) = (position, content) {
+                if content < position {
+                    let message = format!(
+                        "The <{name}> of <{tag_name}> should come before its other child elements."
+                    );
+                    issues.push(SbmlIssue::new_info(
+                        "lint-sbase-children-order",
+                        &element,
+                        message,
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// ### MathML depth limit
+/// Report every *math* element below (and including) `xml_element` whose content is nested
+/// deeper than `max_depth` (see [Math::depth]). This is not a rule of the specification, but
+/// a safeguard against adversarial input, since some rules traverse the expressions
+/// recursively. Hence, the issue is an error (`lint-math-depth`) and the document should not
+/// be validated any further.
+pub(crate) fn apply_math_depth_check(
+    xml_element: &XmlElement,
+    max_depth: usize,
+    issues: &mut Vec<SbmlIssue>,
+) {
+    let mut elements = xml_element.recursive_child_elements_filtered(|it| {
+        it.tag_name() == "math" && it.namespace_url() == URL_MATHML
+    });
+    if xml_element.tag_name() == "math" && xml_element.namespace_url() == URL_MATHML {
+        elements.insert(0, xml_element.clone());
+    }
+    for element in elements {
+        let depth = element.max_depth();
+        if depth > max_depth {
+            let message = format!(
+                "The <math> element is nested too deeply ({depth} levels, the limit is \
+                {max_depth}). The document is not validated any further."
+            );
+            issues.push(SbmlIssue::new_error("lint-math-depth", &element, message));
+        }
+    }
+}
+
+/// ### Non-finite values (lint)
+/// Report `NaN` and infinite values of the numeric attributes listed in
+/// [NON_FINITE_VALUE_ATTRIBUTES], for every SBML core element below (and including)
+/// `xml_element`.
+///
+/// Such values are valid XML Schema doubles, hence the issue uses the non-specification rule
+/// ID `lint-non-finite-value`. Its severity is given by the table, i.e. an error for quantities
+/// like `stoichiometry` or compartment `size`, and a warning for parameter values.
+pub(crate) fn apply_non_finite_value_check(xml_element: &XmlElement, issues: &mut Vec<SbmlIssue>) {
+    let mut elements = xml_element.recursive_child_elements();
+    elements.insert(0, xml_element.clone());
+    for element in elements {
+        if element.namespace_url() != URL_SBML_CORE {
+            continue;
+        }
+        let tag_name = element.tag_name();
+        let Some(attributes) = NON_FINITE_VALUE_ATTRIBUTES.get(tag_name.as_str()) else {
+            continue;
+        };
+        for (attr_name, severity) in attributes.iter() {
+            let Some(value) = element.get_attribute(attr_name) else {
+                continue;
+            };
+            let Ok(value) = value.trim().parse::<f64>() else {
+                // Malformed values are reported by the type check.
+                continue;
+            };
+            if value.is_finite() {
+                continue;
+            }
+            let message =
+                format!("The [{attr_name}] of <{tag_name}> is not a finite number ('{value}').");
+            issues.push(SbmlIssue {
+                element: element.raw_element(),
+                severity: *severity,
+                rule: "lint-non-finite-value".to_string(),
+                message,
+            });
+        }
+    }
+}
+
 // TODO: might be placed inside SBASE validation method
 /// ### Rule 10309
 /// The value of a *metaid* attribute must always conform to the syntax of the *XML* data type **ID**.
@@ -1,13 +1,20 @@
+use crate::constants::namespaces::{
+    URL_PACKAGE_COMP, URL_PACKAGE_FBC, URL_PACKAGE_LAYOUT, URL_PACKAGE_QUAL,
+};
+use crate::core::dimensions::{format_dimensions, same_dimensions, Dimensions};
 use crate::core::validation::type_check::{internal_type_check, type_check_of_list, CanTypeCheck};
 use crate::core::validation::{
     apply_rule_10301, apply_rule_10307, apply_rule_10308, apply_rule_10309, apply_rule_10310,
     apply_rule_10311, apply_rule_10312, apply_rule_10313, apply_rule_10401, apply_rule_10402,
-    validate_list_of_objects, SbmlValidable,
+    constant_target_rule_id, validate_list_of_objects, SbmlValidable,
+};
+use crate::core::{AbstractRule, Event, InitialAssignment, Model, SBase, Species, UnitDefinition};
+use crate::xml::{
+    OptionalXmlChild, OptionalXmlProperty, RequiredXmlProperty, XmlElement, XmlProperty, XmlWrapper,
 };
-use crate::core::{AbstractRule, Model, SBase, UnitDefinition};
-use crate::xml::{OptionalXmlChild, OptionalXmlProperty, XmlElement, XmlProperty, XmlWrapper};
 use crate::SbmlIssue;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::ops::ControlFlow;
 
 impl SbmlValidable for Model {
     fn validate(
@@ -16,6 +23,24 @@ impl SbmlValidable for Model {
         identifiers: &mut HashSet<String>,
         meta_ids: &mut HashSet<String>,
     ) {
+        let _ = self.validate_in_stages(issues, identifiers, meta_ids, &mut |_| {
+            ControlFlow::Continue(())
+        });
+    }
+}
+
+impl Model {
+    /// Same as [SbmlValidable::validate], but `flush` is called with the collected `issues`
+    /// once each stage of the validation (the model attributes, each list of objects, and
+    /// the cross-references at the end) is finished. If `flush` returns [ControlFlow::Break],
+    /// the validation is stopped.
+    pub(crate) fn validate_in_stages(
+        &self,
+        issues: &mut Vec<SbmlIssue>,
+        identifiers: &mut HashSet<String>,
+        meta_ids: &mut HashSet<String>,
+        flush: &mut dyn FnMut(&mut Vec<SbmlIssue>) -> ControlFlow<()>,
+    ) -> ControlFlow<()> {
         let xml_element = self.xml_element();
         let id = self.id();
         let meta_id = self.meta_id();
@@ -28,43 +53,79 @@ impl SbmlValidable for Model {
         self.apply_rule_10311(xml_element, issues);
         apply_rule_10312(self.name().get(), xml_element, issues);
         self.apply_rule_10313(xml_element, issues);
+        self.apply_substance_dimension_check(issues);
 
         if let Some(annotation) = self.annotation().get() {
             apply_rule_10401(&annotation, issues);
             apply_rule_10402(&annotation, issues);
         }
+        flush(issues)?;
+
         if let Some(list_of_function_definition) = self.function_definitions().get() {
             validate_list_of_objects(&list_of_function_definition, issues, identifiers, meta_ids);
         }
+        flush(issues)?;
+
         if let Some(list_of_unit_definitions) = self.unit_definitions().get() {
             validate_list_of_objects(&list_of_unit_definitions, issues, identifiers, meta_ids);
             UnitDefinition::apply_rule_10302(&list_of_unit_definitions, issues);
         }
+        flush(issues)?;
+
         if let Some(list_of_compartments) = self.compartments().get() {
             validate_list_of_objects(&list_of_compartments, issues, identifiers, meta_ids);
         }
+        flush(issues)?;
+
         if let Some(list_of_species) = self.species().get() {
             validate_list_of_objects(&list_of_species, issues, identifiers, meta_ids);
+            Species::apply_constant_and_boundary_rules(self, &list_of_species, issues);
         }
+        flush(issues)?;
+
         if let Some(list_of_parameters) = self.parameters().get() {
             validate_list_of_objects(&list_of_parameters, issues, identifiers, meta_ids);
         }
+        flush(issues)?;
+
         if let Some(list_of_initial_assignment) = self.initial_assignments().get() {
             validate_list_of_objects(&list_of_initial_assignment, issues, identifiers, meta_ids);
+            InitialAssignment::apply_rule_20801(&list_of_initial_assignment, issues);
+            InitialAssignment::apply_rule_20802(&list_of_initial_assignment, issues);
+            InitialAssignment::apply_rule_20803(&list_of_initial_assignment, issues);
         }
+        flush(issues)?;
+
         if let Some(list_of_rules) = self.rules().get() {
             validate_list_of_objects(&list_of_rules, issues, identifiers, meta_ids);
             AbstractRule::apply_rule_10304(&list_of_rules, issues);
         }
+        flush(issues)?;
+
         if let Some(list_of_constraint) = self.constraints().get() {
             validate_list_of_objects(&list_of_constraint, issues, identifiers, meta_ids);
         }
+        flush(issues)?;
+
         if let Some(list_of_reactions) = self.reactions().get() {
             validate_list_of_objects(&list_of_reactions, issues, identifiers, meta_ids);
         }
+        flush(issues)?;
+
         if let Some(list_of_events) = self.events().get() {
             validate_list_of_objects(&list_of_events, issues, identifiers, meta_ids);
+            Event::apply_conflicting_assignments_check(&list_of_events, issues);
         }
+        flush(issues)?;
+
+        self.apply_species_reference_assignment_rules(issues);
+        self.apply_fbc_objective_rules(issues);
+        self.apply_fbc_gene_product_rules(issues);
+        self.apply_qual_constant_output_rule(issues);
+        self.apply_qual_level_bounds_rule(issues);
+        self.apply_layout_species_reference_rule(issues);
+        self.apply_comp_replacement_rules(issues);
+        flush(issues)
     }
 }
 
@@ -106,6 +167,532 @@ impl CanTypeCheck for Model {
 }
 
 impl Model {
+    /// ### Rules 20903, 20904 and 21212 (for species references)
+    /// A [SpeciesReference] whose *id* is the *variable* of an [AssignmentRule] (20903),
+    /// a [RateRule] (20904) or an [EventAssignment] (21212) must have *constant* set to `false`,
+    /// since its stoichiometry changes over time.
+    ///
+    /// [SpeciesReference]: crate::core::SpeciesReference
+    /// [AssignmentRule]: crate::core::AssignmentRule
+    /// [RateRule]: crate::core::RateRule
+    /// [EventAssignment]: crate::core::EventAssignment
+    pub(crate) fn apply_species_reference_assignment_rules(&self, issues: &mut Vec<SbmlIssue>) {
+        for (element, variable) in self.variable_assignments() {
+            let Some(reference) = self.find_species_reference(variable.as_str()) else {
+                continue;
+            };
+            if reference.constant().get() {
+                let tag_name = element.tag_name();
+                let message = format!(
+                    "The <speciesReference> ('{variable}') assigned by <{tag_name}> must have \
+                    [constant] set to 'false'."
+                );
+                let rule_id = constant_target_rule_id(tag_name.as_str());
+                issues.push(SbmlIssue::new_error(rule_id, &element, message));
+            }
+        }
+    }
+
+    /// ### Rules fbc-20505, fbc-20603 and fbc-20604
+    /// The `fbc:activeObjective` attribute of `fbc:listOfObjectives` must reference an existing
+    /// `fbc:objective` (20505). Every `fbc:fluxObjective` must reference an existing [Reaction]
+    /// through its `fbc:reaction` attribute (20603) and its `fbc:coefficient` must be a finite
+    /// number (20604).
+    ///
+    /// [Reaction]: crate::core::Reaction
+    pub(crate) fn apply_fbc_objective_rules(&self, issues: &mut Vec<SbmlIssue>) {
+        let Some(list_of_objectives) = self.fbc_objectives() else {
+            return;
+        };
+        let objectives = fbc_children(&list_of_objectives, "objective");
+        let reactions = self.reaction_identifiers();
+
+        if let Some(active) = self.fbc_active_objective() {
+            let exists = objectives.iter().any(|objective| {
+                objective.get_namespaced_attribute("id", URL_PACKAGE_FBC) == Some(active.clone())
+            });
+            if !exists {
+                let message = format!(
+                    "The [fbc:activeObjective] ('{active}') does not reference an existing \
+                    <fbc:objective>."
+                );
+                issues.push(SbmlIssue::new_error(
+                    "fbc-20505",
+                    &list_of_objectives,
+                    message,
+                ));
+            }
+        }
+
+        for objective in objectives {
+            let flux_objectives = fbc_children(&objective, "listOfFluxObjectives")
+                .iter()
+                .flat_map(|list| fbc_children(list, "fluxObjective"))
+                .collect::<Vec<_>>();
+            for flux_objective in flux_objectives {
+                let reaction = flux_objective
+                    .get_namespaced_attribute("reaction", URL_PACKAGE_FBC)
+                    .unwrap_or_default();
+                if !reactions.contains(&reaction) {
+                    let message = format!(
+                        "The [fbc:reaction] ('{reaction}') of <fbc:fluxObjective> does not \
+                        reference an existing <reaction>."
+                    );
+                    issues.push(SbmlIssue::new_error("fbc-20603", &flux_objective, message));
+                }
+
+                let coefficient = flux_objective
+                    .get_namespaced_attribute("coefficient", URL_PACKAGE_FBC)
+                    .unwrap_or_default();
+                if !coefficient.parse::<f64>().is_ok_and(|it| it.is_finite()) {
+                    let message = format!(
+                        "The [fbc:coefficient] ('{coefficient}') of <fbc:fluxObjective> is not \
+                        a finite number."
+                    );
+                    issues.push(SbmlIssue::new_error("fbc-20604", &flux_objective, message));
+                }
+            }
+        }
+    }
+
+    /// ### Rule fbc-20908
+    /// The `fbc:geneProduct` attribute of every `fbc:geneProductRef` (within the gene product
+    /// association of a [Reaction]) must reference the `fbc:id` of an existing
+    /// `fbc:geneProduct`.
+    ///
+    /// Additionally, the `fbc:label` of every `fbc:geneProduct` should be unique within the
+    /// model, since tools typically identify genes by their label. A duplicate label is
+    /// reported as a warning (`lint-fbc-duplicate-gene-product-label`).
+    ///
+    /// [Reaction]: crate::core::Reaction
+    pub(crate) fn apply_fbc_gene_product_rules(&self, issues: &mut Vec<SbmlIssue>) {
+        let gene_products = fbc_children(self.xml_element(), "listOfGeneProducts")
+            .iter()
+            .flat_map(|list| fbc_children(list, "geneProduct"))
+            .collect::<Vec<_>>();
+
+        let mut ids = HashSet::new();
+        let mut labels = HashSet::new();
+        for gene_product in &gene_products {
+            if let Some(id) = gene_product.get_namespaced_attribute("id", URL_PACKAGE_FBC) {
+                ids.insert(id);
+            }
+            let Some(label) = gene_product.get_namespaced_attribute("label", URL_PACKAGE_FBC)
+            else {
+                continue;
+            };
+            if !labels.insert(label.clone()) {
+                let id = gene_product
+                    .get_namespaced_attribute("id", URL_PACKAGE_FBC)
+                    .unwrap_or_default();
+                let message = format!(
+                    "The [fbc:label] ('{label}') of <fbc:geneProduct> ('{id}') is already used \
+                    by another <fbc:geneProduct>."
+                );
+                let rule = "lint-fbc-duplicate-gene-product-label";
+                issues.push(SbmlIssue::new_warning(rule, gene_product, message));
+            }
+        }
+
+        let Some(reactions) = self.reactions().get() else {
+            return;
+        };
+        for reaction in reactions.iter() {
+            let references = reaction.recursive_child_elements_filtered(|it| {
+                it.tag_name() == "geneProductRef" && it.namespace_url() == URL_PACKAGE_FBC
+            });
+            for reference in references {
+                let gene_product = reference
+                    .get_namespaced_attribute("geneProduct", URL_PACKAGE_FBC)
+                    .unwrap_or_default();
+                if !ids.contains(&gene_product) {
+                    let message = format!(
+                        "The [fbc:geneProduct] ('{gene_product}') of <fbc:geneProductRef> in \
+                        reaction ('{}') does not reference an existing <fbc:geneProduct>.",
+                        reaction.id().get()
+                    );
+                    issues.push(SbmlIssue::new_error("fbc-20908", &reference, message));
+                }
+            }
+        }
+    }
+
+    /// ### Rule qual-20601 area
+    /// A `qual:qualitativeSpecies` with `qual:constant="true"` must not be the
+    /// `qual:qualitativeSpecies` of any `qual:output` of a `qual:transition`, since constant
+    /// species never change their level.
+    pub(crate) fn apply_qual_constant_output_rule(&self, issues: &mut Vec<SbmlIssue>) {
+        let constant_species = package_children(
+            self.xml_element(),
+            "listOfQualitativeSpecies",
+            URL_PACKAGE_QUAL,
+        )
+        .iter()
+        .flat_map(|list| package_children(list, "qualitativeSpecies", URL_PACKAGE_QUAL))
+        .filter(|species| {
+            species.get_namespaced_attribute("constant", URL_PACKAGE_QUAL)
+                == Some("true".to_string())
+        })
+        .filter_map(|species| species.get_namespaced_attribute("id", URL_PACKAGE_QUAL))
+        .collect::<HashSet<_>>();
+        if constant_species.is_empty() {
+            return;
+        }
+
+        let outputs = package_children(self.xml_element(), "listOfTransitions", URL_PACKAGE_QUAL)
+            .iter()
+            .flat_map(|list| package_children(list, "transition", URL_PACKAGE_QUAL))
+            .flat_map(|transition| package_children(&transition, "listOfOutputs", URL_PACKAGE_QUAL))
+            .flat_map(|list| package_children(&list, "output", URL_PACKAGE_QUAL))
+            .collect::<Vec<_>>();
+        for output in outputs {
+            let species = output
+                .get_namespaced_attribute("qualitativeSpecies", URL_PACKAGE_QUAL)
+                .unwrap_or_default();
+            if constant_species.contains(&species) {
+                let message = format!(
+                    "The constant <qual:qualitativeSpecies> ('{species}') is used as \
+                    an <qual:output> of a <qual:transition>."
+                );
+                issues.push(SbmlIssue::new_error("qual-20601", &output, message));
+            }
+        }
+    }
+
+    /// ### Rule qual-20308 area
+    /// If a `qual:qualitativeSpecies` declares a `qual:maxLevel`, its `qual:initialLevel`
+    /// (if present) must be within `[0, maxLevel]`. Similarly, a `qual:transition` must not
+    /// produce a level above the `qual:maxLevel` of any of its outputs, i.e. the
+    /// `qual:outputLevel` of the output and the `qual:resultLevel` of every function term
+    /// (including the default term) must not exceed it.
+    pub(crate) fn apply_qual_level_bounds_rule(&self, issues: &mut Vec<SbmlIssue>) {
+        let species = package_children(
+            self.xml_element(),
+            "listOfQualitativeSpecies",
+            URL_PACKAGE_QUAL,
+        )
+        .iter()
+        .flat_map(|list| package_children(list, "qualitativeSpecies", URL_PACKAGE_QUAL))
+        .collect::<Vec<_>>();
+
+        let mut max_levels = HashMap::new();
+        for species in &species {
+            let Some(max_level) = qual_level(species, "maxLevel") else {
+                continue;
+            };
+            let id = species
+                .get_namespaced_attribute("id", URL_PACKAGE_QUAL)
+                .unwrap_or_default();
+            if let Some(initial_level) = qual_level(species, "initialLevel") {
+                if initial_level < 0 || initial_level > max_level {
+                    let message = format!(
+                        "The [qual:initialLevel] ('{initial_level}') of <qual:qualitativeSpecies> \
+                        ('{id}') is not within [0, {max_level}] given by its [qual:maxLevel]."
+                    );
+                    issues.push(SbmlIssue::new_error("qual-20308", species, message));
+                }
+            }
+            max_levels.insert(id, max_level);
+        }
+        if max_levels.is_empty() {
+            return;
+        }
+
+        let transitions =
+            package_children(self.xml_element(), "listOfTransitions", URL_PACKAGE_QUAL)
+                .iter()
+                .flat_map(|list| package_children(list, "transition", URL_PACKAGE_QUAL))
+                .collect::<Vec<_>>();
+        for transition in transitions {
+            let result_levels =
+                package_children(&transition, "listOfFunctionTerms", URL_PACKAGE_QUAL)
+                    .iter()
+                    .flat_map(|list| list.child_elements())
+                    .filter(|term| term.namespace_url() == URL_PACKAGE_QUAL)
+                    .filter_map(|term| qual_level(&term, "resultLevel").map(|it| (term, it)))
+                    .collect::<Vec<_>>();
+            let outputs = package_children(&transition, "listOfOutputs", URL_PACKAGE_QUAL)
+                .iter()
+                .flat_map(|list| package_children(list, "output", URL_PACKAGE_QUAL))
+                .collect::<Vec<_>>();
+            for output in outputs {
+                let id = output
+                    .get_namespaced_attribute("qualitativeSpecies", URL_PACKAGE_QUAL)
+                    .unwrap_or_default();
+                let Some(max_level) = max_levels.get(&id).copied() else {
+                    continue;
+                };
+                if let Some(level) = qual_level(&output, "outputLevel") {
+                    if level > max_level {
+                        let message = format!(
+                            "The [qual:outputLevel] ('{level}') of <qual:output> exceeds \
+                            the [qual:maxLevel] ('{max_level}') of <qual:qualitativeSpecies> \
+                            ('{id}')."
+                        );
+                        issues.push(SbmlIssue::new_error("qual-20308", &output, message));
+                    }
+                }
+                for (term, level) in &result_levels {
+                    if *level > max_level {
+                        let message = format!(
+                            "The [qual:resultLevel] ('{level}') of <qual:{}> exceeds \
+                            the [qual:maxLevel] ('{max_level}') of <qual:qualitativeSpecies> \
+                            ('{id}'), which is an output of the transition.",
+                            term.tag_name()
+                        );
+                        issues.push(SbmlIssue::new_error("qual-20308", term, message));
+                    }
+                }
+            }
+        }
+    }
+
+    /// ### Layout species references (lint)
+    /// The `layout:speciesReference` of a `layout:speciesReferenceGlyph` should reference
+    /// a species reference (reactant, product or modifier) of the [Reaction] which is referenced
+    /// by the `layout:reaction` of the enclosing `layout:reactionGlyph`. If the reaction glyph
+    /// does not reference any reaction, the species reference must at least exist in the model.
+    /// Reaction glyphs which reference a missing reaction are skipped.
+    ///
+    /// The layout specification only requires the species reference to exist, hence the issue
+    /// uses the non-specification rule ID `lint-layout-species-reference`.
+    pub(crate) fn apply_layout_species_reference_rule(&self, issues: &mut Vec<SbmlIssue>) {
+        let reaction_glyphs =
+            package_children(self.xml_element(), "listOfLayouts", URL_PACKAGE_LAYOUT)
+                .iter()
+                .flat_map(|list| package_children(list, "layout", URL_PACKAGE_LAYOUT))
+                .flat_map(|layout| {
+                    package_children(&layout, "listOfReactionGlyphs", URL_PACKAGE_LAYOUT)
+                })
+                .flat_map(|list| package_children(&list, "reactionGlyph", URL_PACKAGE_LAYOUT))
+                .collect::<Vec<_>>();
+        if reaction_glyphs.is_empty() {
+            return;
+        }
+
+        // Species reference identifiers of every reaction.
+        let mut references: HashMap<String, HashSet<String>> = HashMap::new();
+        if let Some(reactions) = self.reactions().get() {
+            for reaction in reactions.iter() {
+                let mut ids = HashSet::new();
+                for list in [reaction.reactants(), reaction.products()] {
+                    if let Some(list) = list.get() {
+                        ids.extend(list.iter().filter_map(|it| it.id().get()));
+                    }
+                }
+                if let Some(list) = reaction.modifiers().get() {
+                    ids.extend(list.iter().filter_map(|it| it.id().get()));
+                }
+                references.insert(reaction.id().get(), ids);
+            }
+        }
+
+        for reaction_glyph in reaction_glyphs {
+            let reaction = reaction_glyph.get_namespaced_attribute("reaction", URL_PACKAGE_LAYOUT);
+            let glyphs = package_children(
+                &reaction_glyph,
+                "listOfSpeciesReferenceGlyphs",
+                URL_PACKAGE_LAYOUT,
+            )
+            .iter()
+            .flat_map(|list| package_children(list, "speciesReferenceGlyph", URL_PACKAGE_LAYOUT))
+            .collect::<Vec<_>>();
+            for glyph in glyphs {
+                let Some(reference) =
+                    glyph.get_namespaced_attribute("speciesReference", URL_PACKAGE_LAYOUT)
+                else {
+                    continue;
+                };
+                let message = match &reaction {
+                    Some(reaction) => {
+                        let Some(ids) = references.get(reaction) else {
+                            continue;
+                        };
+                        if ids.contains(&reference) {
+                            continue;
+                        }
+                        format!(
+                            "The [layout:speciesReference] ('{reference}') of \
+                            <layout:speciesReferenceGlyph> is not a species reference of the \
+                            <reaction> ('{reaction}') referenced by the enclosing \
+                            <layout:reactionGlyph>."
+                        )
+                    }
+                    None => {
+                        if references.values().any(|ids| ids.contains(&reference)) {
+                            continue;
+                        }
+                        format!(
+                            "The [layout:speciesReference] ('{reference}') of \
+                            <layout:speciesReferenceGlyph> does not reference an existing \
+                            species reference."
+                        )
+                    }
+                };
+                let rule = "lint-layout-species-reference";
+                issues.push(SbmlIssue::new_error(rule, &glyph, message));
+            }
+        }
+    }
+
+    /// ### Rules comp-20701 and comp-20801
+    /// The *submodelRef* of every `comp:replacedElement` (comp-20701) and `comp:replacedBy`
+    /// (comp-20801) must be the identifier of a `comp:submodel` of this model.
+    ///
+    /// ### Rules comp-20302 and comp-20303
+    /// If the submodel is an instance of a `comp:modelDefinition` of this document, the
+    /// *portRef* of a replacement must reference a `comp:port` (comp-20302) and its *idRef*
+    /// an existing element (comp-20303) of the instantiated model. References into external
+    /// model definitions are not resolved.
+    ///
+    /// ### Replaced replacements (lint)
+    /// An element should not be both replaced by a submodel element (through `comp:replacedBy`)
+    /// and replace submodel elements (through `comp:replacedElement`), since such an element
+    /// cannot be flattened consistently. This is reported as `lint-comp-replaced-and-replacing`.
+    pub(crate) fn apply_comp_replacement_rules(&self, issues: &mut Vec<SbmlIssue>) {
+        let replacements = self.recursive_child_elements_filtered(|it| {
+            (it.tag_name() == "replacedElement" || it.tag_name() == "replacedBy")
+                && it.namespace_url() == URL_PACKAGE_COMP
+        });
+        if replacements.is_empty() {
+            return;
+        }
+
+        let definitions: HashMap<String, XmlElement> = self
+            .parent()
+            .map(|root| package_children(&root, "listOfModelDefinitions", URL_PACKAGE_COMP))
+            .unwrap_or_default()
+            .iter()
+            .flat_map(|list| package_children(list, "modelDefinition", URL_PACKAGE_COMP))
+            .filter_map(|it| Some((it.get_attribute("id")?, it)))
+            .collect();
+        // Maps every submodel to its model definition (`None` if external or unknown).
+        let submodels: HashMap<String, Option<XmlElement>> =
+            package_children(self.xml_element(), "listOfSubmodels", URL_PACKAGE_COMP)
+                .iter()
+                .flat_map(|list| package_children(list, "submodel", URL_PACKAGE_COMP))
+                .filter_map(|it| {
+                    let id = it.get_namespaced_attribute("id", URL_PACKAGE_COMP)?;
+                    let definition = it
+                        .get_namespaced_attribute("modelRef", URL_PACKAGE_COMP)
+                        .and_then(|model_ref| definitions.get(&model_ref).cloned());
+                    Some((id, definition))
+                })
+                .collect();
+
+        let mut replaced_by_parents = Vec::new();
+        let mut replacing_parents = HashSet::new();
+        for replacement in &replacements {
+            let tag_name = replacement.tag_name();
+            let rule = if tag_name == "replacedElement" {
+                // The parent is `comp:listOfReplacedElements`, which belongs to the element.
+                if let Some(element) = replacement.parent().and_then(|it| it.parent()) {
+                    replacing_parents.insert(element.raw_element());
+                }
+                "comp-20701"
+            } else {
+                if let Some(element) = replacement.parent() {
+                    replaced_by_parents.push(element);
+                }
+                "comp-20801"
+            };
+
+            let submodel_ref = replacement
+                .get_namespaced_attribute("submodelRef", URL_PACKAGE_COMP)
+                .unwrap_or_default();
+            let Some(definition) = submodels.get(&submodel_ref) else {
+                let message = format!(
+                    "The [comp:submodelRef] ('{submodel_ref}') of <comp:{tag_name}> does not \
+                    reference any <comp:submodel> of the model."
+                );
+                issues.push(SbmlIssue::new_error(rule, replacement, message));
+                continue;
+            };
+            let Some(definition) = definition else {
+                continue;
+            };
+
+            if let Some(port_ref) =
+                replacement.get_namespaced_attribute("portRef", URL_PACKAGE_COMP)
+            {
+                let exists = package_children(definition, "listOfPorts", URL_PACKAGE_COMP)
+                    .iter()
+                    .flat_map(|list| package_children(list, "port", URL_PACKAGE_COMP))
+                    .any(|port| {
+                        port.get_namespaced_attribute("id", URL_PACKAGE_COMP)
+                            == Some(port_ref.clone())
+                    });
+                if !exists {
+                    let message = format!(
+                        "The [comp:portRef] ('{port_ref}') of <comp:{tag_name}> does not \
+                        reference any <comp:port> of the <comp:submodel> ('{submodel_ref}')."
+                    );
+                    issues.push(SbmlIssue::new_error("comp-20302", replacement, message));
+                }
+            }
+            if let Some(id_ref) = replacement.get_namespaced_attribute("idRef", URL_PACKAGE_COMP) {
+                let targets = definition.recursive_child_elements_filtered(|it| {
+                    it.get_attribute("id").as_deref() == Some(id_ref.as_str())
+                });
+                if targets.is_empty() {
+                    let message = format!(
+                        "The [comp:idRef] ('{id_ref}') of <comp:{tag_name}> does not \
+                        reference any element of the <comp:submodel> ('{submodel_ref}')."
+                    );
+                    issues.push(SbmlIssue::new_error("comp-20303", replacement, message));
+                }
+            }
+        }
+
+        for element in replaced_by_parents
+            .iter()
+            .filter(|it| replacing_parents.contains(&it.raw_element()))
+        {
+            let message = format!(
+                "The <{}> is both replaced by a submodel element (<comp:replacedBy>) and \
+                replaces submodel elements (<comp:replacedElement>).",
+                element.tag_name()
+            );
+            let rule = "lint-comp-replaced-and-replacing";
+            issues.push(SbmlIssue::new_error(rule, element, message));
+        }
+    }
+
+    /// ### Substance units (lint)
+    /// The *substanceUnits* and *extentUnits* of a [Model] should be units of substance,
+    /// i.e. their dimensions should be either mole, mass (e.g. `gram`), or dimensionless
+    /// (e.g. `item` or `avogadro`). We report a warning when one of these units resolves
+    /// to different dimensions (e.g. a length) as `lint-substance-units`. Units that cannot be
+    /// resolved are covered by rule 10313.
+    pub(crate) fn apply_substance_dimension_check(&self, issues: &mut Vec<SbmlIssue>) {
+        const SUBSTANCE: [Dimensions; 3] = [
+            [0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0],
+            [1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+            [0.0; 7],
+        ];
+        for property in [self.substance_units(), self.extent_units()] {
+            let Some(units) = property.get() else {
+                continue;
+            };
+            let Some(dimensions) = self.unit_dimensions(units.as_str()) else {
+                continue;
+            };
+            if !SUBSTANCE.iter().any(|it| same_dimensions(it, &dimensions)) {
+                let message = format!(
+                    "The attribute [{}] of the <model> refers to `{units}` with dimensions \
+                    `{}`, which are not units of substance.",
+                    property.name(),
+                    format_dimensions(&dimensions)
+                );
+                issues.push(SbmlIssue::new_warning(
+                    "lint-substance-units",
+                    self,
+                    message,
+                ));
+            }
+        }
+    }
+
     pub(crate) fn apply_rule_10311(&self, xml_element: &XmlElement, issues: &mut Vec<SbmlIssue>) {
         let sbstnc_units = self.substance_units();
         let volume_units = self.volume_units();
@@ -137,3 +724,27 @@ impl Model {
         apply_rule_10313(extent_units.name(), extent_units.get(), xml_element, issues);
     }
 }
+
+/// Collect child elements of `parent` with the given `tag_name` in the fbc namespace.
+fn fbc_children(parent: &XmlElement, tag_name: &str) -> Vec<XmlElement> {
+    package_children(parent, tag_name, URL_PACKAGE_FBC)
+}
+
+/// Read an integer level attribute (e.g. `maxLevel`) of a qual element. Missing or invalid
+/// values are ignored, since they are not subject to the level bounds.
+fn qual_level(element: &XmlElement, name: &str) -> Option<i64> {
+    element
+        .get_namespaced_attribute(name, URL_PACKAGE_QUAL)?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Collect child elements of `parent` with the given `tag_name` in the given package namespace.
+fn package_children(parent: &XmlElement, tag_name: &str, namespace_url: &str) -> Vec<XmlElement> {
+    parent
+        .child_elements()
+        .into_iter()
+        .filter(|child| child.tag_name() == tag_name && child.namespace_url() == namespace_url)
+        .collect()
+}
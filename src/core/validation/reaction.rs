@@ -1,3 +1,4 @@
+use crate::core::dimensions::{combine, format_dimensions, same_dimensions};
 use crate::core::validation::type_check::{internal_type_check, type_check_of_list, CanTypeCheck};
 use crate::core::validation::{
     apply_rule_10301, apply_rule_10307, apply_rule_10308, apply_rule_10309, apply_rule_10310,
@@ -5,7 +6,8 @@ use crate::core::validation::{
     validate_list_of_objects, SbmlValidable,
 };
 use crate::core::{
-    KineticLaw, LocalParameter, ModifierSpeciesReference, Reaction, SBase, SpeciesReference,
+    KineticLaw, LocalParameter, Model, ModifierSpeciesReference, Reaction, SBase,
+    SimpleSpeciesReference, SpeciesReference,
 };
 use crate::xml::{
     OptionalXmlChild, OptionalXmlProperty, RequiredXmlProperty, XmlList, XmlProperty, XmlWrapper,
@@ -47,6 +49,177 @@ impl SbmlValidable for Reaction {
         if let Some(kinetic_law) = self.kinetic_law().get() {
             kinetic_law.validate(issues, identifiers, meta_ids);
         }
+        self.apply_empty_reaction_check(issues);
+        self.apply_fbc_flux_bound_rules(issues);
+        self.apply_fbc_mass_balance_check(issues);
+        self.apply_compartment_consistency_check(issues);
+        self.apply_irreversible_rate_law_check(issues);
+    }
+}
+
+impl Reaction {
+    /// ### Empty reaction (lint)
+    /// In SBML Level 3 Version 1, a [Reaction] must have at least one reactant or product
+    /// (rule 21101). Version 2 permits such reactions, but a reaction without any reactants,
+    /// products and modifiers has no effect and is typically a truncated definition, hence
+    /// we report a warning (`lint-empty-reaction`).
+    pub(crate) fn apply_empty_reaction_check(&self, issues: &mut Vec<SbmlIssue>) {
+        let reactants = self.reactants().get().map(|it| it.len()).unwrap_or(0);
+        let products = self.products().get().map(|it| it.len()).unwrap_or(0);
+        let modifiers = self.modifiers().get().map(|it| it.len()).unwrap_or(0);
+
+        if reactants + products + modifiers == 0 {
+            let message = format!(
+                "The <reaction> ('{}') has no reactants, products, or modifiers.",
+                self.id().get()
+            );
+            let rule = "lint-empty-reaction";
+            issues.push(SbmlIssue::new_warning(rule, self.xml_element(), message));
+        }
+    }
+
+    /// ### Irreversible rate law (lint)
+    /// The rate of a [Reaction] with `reversible="false"` should not become negative. Deciding
+    /// this in general is not feasible, but a kinetic law which is (at the top level) a
+    /// difference of two terms (e.g. mass action `kf * S - kr * P`) typically describes
+    /// a reversible reaction. Since this is only a heuristic, we report it as info
+    /// (`lint-irreversible-rate-law`).
+    pub(crate) fn apply_irreversible_rate_law_check(&self, issues: &mut Vec<SbmlIssue>) {
+        if !matches!(self.reversible().get_checked(), Ok(Some(false))) {
+            return;
+        }
+        let Some(math) = self.kinetic_law().get().and_then(|law| law.math().get()) else {
+            return;
+        };
+        let Some(expression) = math.xml_element().child_elements().into_iter().next() else {
+            return;
+        };
+        if expression.tag_name() != "apply" {
+            return;
+        }
+        let arguments = expression.child_elements();
+        if arguments.len() == 3 && arguments[0].tag_name() == "minus" {
+            let message = format!(
+                "(Heuristic) The <reaction> ('{}') is not reversible, but its kinetic law is \
+                a difference of two terms and can become negative.",
+                self.id().get()
+            );
+            issues.push(SbmlIssue::new_info(
+                "lint-irreversible-rate-law",
+                self.xml_element(),
+                message,
+            ));
+        }
+    }
+
+    /// ### Species compartments (lint)
+    /// If a [Reaction] declares a *compartment*, its reactants and products are expected to
+    /// reside in that compartment. Reactions spanning multiple compartments are allowed
+    /// (e.g. transport), but a mismatch is frequently a copy-paste error, hence we only
+    /// report a warning (`lint-reaction-compartment`).
+    pub(crate) fn apply_compartment_consistency_check(&self, issues: &mut Vec<SbmlIssue>) {
+        let Some(compartment) = self.compartment().get() else {
+            return;
+        };
+        let Some(model) = Model::for_child_element(self.xml_element()) else {
+            return;
+        };
+        let id = self.id().get();
+
+        for list in [self.reactants(), self.products()] {
+            let Some(list) = list.get() else {
+                continue;
+            };
+            for species_ref in list.iter() {
+                let species_id = species_ref.species().get();
+                let Some(species) = model.find_species(species_id.as_str()) else {
+                    continue;
+                };
+                let species_compartment = species.compartment().get();
+                if species_compartment != compartment {
+                    let message = format!(
+                        "The <species> ('{species_id}') of <reaction> ('{id}') is located in \
+                        <compartment> ('{species_compartment}'), but the reaction declares \
+                        <compartment> ('{compartment}')."
+                    );
+                    let rule = "lint-reaction-compartment";
+                    issues.push(SbmlIssue::new_warning(rule, &species_ref, message));
+                }
+            }
+        }
+    }
+
+    /// ### Mass balance (lint)
+    /// If all reactants and products of a [Reaction] declare an `fbc:chemicalFormula`, the
+    /// reaction should conserve the atoms of every chemical element (see
+    /// [Reaction::is_mass_balanced]). Unbalanced reactions are allowed (e.g. exchange
+    /// reactions), hence we only report a warning (`lint-fbc-mass-balance`).
+    pub(crate) fn apply_fbc_mass_balance_check(&self, issues: &mut Vec<SbmlIssue>) {
+        let Some(model) = Model::for_child_element(self.xml_element()) else {
+            return;
+        };
+        if self.is_mass_balanced(&model) == Some(false) {
+            let message = format!(
+                "The <reaction> ('{}') is not mass balanced according to the \
+                [fbc:chemicalFormula] of its reactants and products.",
+                self.id().get()
+            );
+            let rule = "lint-fbc-mass-balance";
+            issues.push(SbmlIssue::new_warning(rule, self.xml_element(), message));
+        }
+    }
+
+    /// ### Rules fbc-20701 to fbc-20705
+    /// The `fbc:lowerFluxBound` and `fbc:upperFluxBound` attributes of a [Reaction] must
+    /// reference an existing [Parameter](crate::core::Parameter) (20701, 20702). If the
+    /// enclosing model declares `fbc:strict="true"`, then both bounds must be present (20703),
+    /// and the referenced parameters must be constant (20704) and have a defined value (20705).
+    pub(crate) fn apply_fbc_flux_bound_rules(&self, issues: &mut Vec<SbmlIssue>) {
+        let Some(model) = Model::for_child_element(self.xml_element()) else {
+            return;
+        };
+        let strict = model.fbc_strict() == Some(true);
+        let id = self.id().get();
+
+        let bounds = [
+            ("lowerFluxBound", self.fbc_lower_flux_bound(), "fbc-20701"),
+            ("upperFluxBound", self.fbc_upper_flux_bound(), "fbc-20702"),
+        ];
+        for (attr_name, bound, rule) in bounds {
+            let Some(bound) = bound else {
+                if strict {
+                    let message = format!(
+                        "The <reaction> ('{id}') is missing the [fbc:{attr_name}] attribute \
+                        required in strict mode."
+                    );
+                    issues.push(SbmlIssue::new_error("fbc-20703", self, message));
+                }
+                continue;
+            };
+            let Some(parameter) = model.find_parameter(bound.as_str()) else {
+                let message = format!(
+                    "The [fbc:{attr_name}] ('{bound}') of <reaction> ('{id}') does not \
+                    reference an existing <parameter>."
+                );
+                issues.push(SbmlIssue::new_error(rule, self, message));
+                continue;
+            };
+            if strict && !parameter.constant().get() {
+                let message = format!(
+                    "The [fbc:{attr_name}] ('{bound}') of <reaction> ('{id}') references \
+                    a non-constant <parameter>."
+                );
+                issues.push(SbmlIssue::new_error("fbc-20704", self, message));
+            }
+            let value = parameter.value();
+            if strict && (!value.is_set() || value.get().is_some_and(|value| value.is_nan())) {
+                let message = format!(
+                    "The [fbc:{attr_name}] ('{bound}') of <reaction> ('{id}') references \
+                    a <parameter> without a defined value."
+                );
+                issues.push(SbmlIssue::new_error("fbc-20705", self, message));
+            }
+        }
     }
 }
 
@@ -152,6 +325,8 @@ impl SbmlValidable for KineticLaw {
         if let Some(math) = self.math().get() {
             math.validate(issues);
         }
+        self.apply_units_check(issues);
+        self.apply_shadowed_parameter_check(issues);
     }
 }
 
@@ -166,6 +341,78 @@ impl CanTypeCheck for KineticLaw {
 }
 
 impl KineticLaw {
+    /// ### Kinetic law units (rule 10541 area)
+    /// The units of the *math* of a [KineticLaw] should be *extentUnits* per *timeUnits*
+    /// of the enclosing [Model]. We compare the dimensions inferred from the *math* with
+    /// the dimensions of these units and report a warning when they differ. The check is
+    /// skipped when the model does not declare both units, or when the dimensions of the
+    /// *math* cannot be inferred (e.g. it contains numbers without `sbml:units`). Only the
+    /// dimensions are compared, i.e. a kinetic law in `mmol/s` matches `mole/second`.
+    pub(crate) fn apply_units_check(&self, issues: &mut Vec<SbmlIssue>) {
+        let Some(model) = Model::for_child_element(self.xml_element()) else {
+            return;
+        };
+        let (Some(extent), Some(time)) = (model.extent_units().get(), model.time_units().get())
+        else {
+            return;
+        };
+        let (Some(extent), Some(time)) = (
+            model.unit_dimensions(extent.as_str()),
+            model.unit_dimensions(time.as_str()),
+        ) else {
+            return;
+        };
+        let Some(inferred) = self.math().get().and_then(|it| it.infer_dimensions(&model)) else {
+            return;
+        };
+
+        let expected = combine(&extent, &time, -1.0);
+        if !same_dimensions(&expected, &inferred) {
+            let reaction = self
+                .parent()
+                .and_then(|it| it.get_attribute("id"))
+                .unwrap_or_default();
+            let message = format!(
+                "The units of the <kineticLaw> of reaction `{reaction}` should be extentUnits \
+                per timeUnits (`{}`), but `{}` were inferred.",
+                format_dimensions(&expected),
+                format_dimensions(&inferred)
+            );
+            issues.push(SbmlIssue::new_warning("10541", self, message));
+        }
+    }
+
+    /// ### Shadowed parameters (lint)
+    /// A [LocalParameter] with the same *id* as a global [Parameter](crate::core::Parameter)
+    /// shadows the global parameter within the *math* of its [KineticLaw]. This is permitted,
+    /// but if the two parameters declare different values, the override is often accidental.
+    /// We report such cases as info (`lint-shadowed-local-parameter`). Parameters without
+    /// a *value* are not considered.
+    pub(crate) fn apply_shadowed_parameter_check(&self, issues: &mut Vec<SbmlIssue>) {
+        let Some(model) = Model::for_child_element(self.xml_element()) else {
+            return;
+        };
+        for (local, global) in self.shadowed_identifiers(&model) {
+            let (Some(local_value), Some(global_value)) =
+                (local.value().get(), global.value().get())
+            else {
+                continue;
+            };
+            if local_value != global_value {
+                let message = format!(
+                    "(Heuristic) The <localParameter> '{}' (value {local_value}) shadows the global \
+                    <parameter> with a different value ({global_value}).",
+                    local.id().get()
+                );
+                issues.push(SbmlIssue::new_info(
+                    "lint-shadowed-local-parameter",
+                    &local,
+                    message,
+                ));
+            }
+        }
+    }
+
     /// ### Rule 10303
     /// The value of the attribute id of every [LocalParameter] object defined within a [KineticLaw]
     /// object must be unique across the set of all such parameter definitions within that
@@ -2,11 +2,11 @@ use crate::core::validation::type_check::CanTypeCheck;
 use crate::core::validation::{
     apply_rule_10301, apply_rule_10307, apply_rule_10308, apply_rule_10309, apply_rule_10310,
     apply_rule_10311, apply_rule_10312, apply_rule_10313, apply_rule_10401, apply_rule_10402,
-    SbmlValidable,
+    constant_target_rule_id, SbmlValidable,
 };
-use crate::core::{SBase, Species};
+use crate::core::{Model, SBase, SimpleSpeciesReference, Species};
 use crate::xml::{
-    OptionalXmlChild, OptionalXmlProperty, RequiredXmlProperty, XmlProperty, XmlWrapper,
+    OptionalXmlChild, OptionalXmlProperty, RequiredXmlProperty, XmlList, XmlProperty, XmlWrapper,
 };
 use crate::SbmlIssue;
 use std::collections::HashSet;
@@ -40,3 +40,75 @@ impl SbmlValidable for Species {
 }
 
 impl CanTypeCheck for Species {}
+
+impl Species {
+    /// ### Rules 20610, 20611, 20903, 20904 and 21212 (for species)
+    /// Implements the constraints of Section 4.6.6 on how a [Species] may change depending on
+    /// its *constant* and *boundaryCondition* attributes:
+    ///  - A species with *constant* set to `true` must not be the *variable* of an
+    ///    [AssignmentRule] (20903), a [RateRule] (20904) or an [EventAssignment] (21212).
+    ///  - A species with *constant* set to `true` and *boundaryCondition* set to `false`
+    ///    cannot be a reactant or product of any [Reaction] (20611).
+    ///  - A species with *boundaryCondition* set to `false` cannot be both a reactant or product
+    ///    of a [Reaction] and the *variable* of an [AssignmentRule] or a [RateRule] (20610),
+    ///    since its value would be determined twice.
+    ///
+    /// [AssignmentRule]: crate::core::AssignmentRule
+    /// [RateRule]: crate::core::RateRule
+    /// [EventAssignment]: crate::core::EventAssignment
+    /// [Reaction]: crate::core::Reaction
+    pub(crate) fn apply_constant_and_boundary_rules(
+        model: &Model,
+        list_of_species: &XmlList<Species>,
+        issues: &mut Vec<SbmlIssue>,
+    ) {
+        let mut participants: HashSet<String> = HashSet::new();
+        if let Some(reactions) = model.reactions().get() {
+            for reaction in reactions.iter() {
+                for list in [reaction.reactants(), reaction.products()] {
+                    if let Some(list) = list.get() {
+                        participants.extend(list.iter().map(|it| it.species().get()));
+                    }
+                }
+            }
+        }
+        let assignments = model.variable_assignments();
+
+        for species in list_of_species.iter() {
+            let id = species.id().get();
+            let constant = species.constant().get();
+            let boundary = species.boundary_condition().get();
+            let is_participant = participants.contains(&id);
+
+            if constant && !boundary && is_participant {
+                let message = format!(
+                    "The <species> ('{id}') with [constant] 'true' and [boundaryCondition] \
+                    'false' cannot be a reactant or product of a <reaction>."
+                );
+                issues.push(SbmlIssue::new_error("20611", &species, message));
+            }
+
+            for (element, variable) in &assignments {
+                if variable != &id {
+                    continue;
+                }
+                let tag_name = element.tag_name();
+                if constant {
+                    let message = format!(
+                        "The <species> ('{id}') assigned by <{tag_name}> must have [constant] \
+                        set to 'false'."
+                    );
+                    let rule_id = constant_target_rule_id(tag_name.as_str());
+                    issues.push(SbmlIssue::new_error(rule_id, element, message));
+                } else if !boundary && is_participant && tag_name != "eventAssignment" {
+                    let message = format!(
+                        "The <species> ('{id}') with [boundaryCondition] 'false' is a reactant \
+                        or product of a <reaction>, hence it cannot be the variable of \
+                        <{tag_name}>."
+                    );
+                    issues.push(SbmlIssue::new_error("20610", element, message));
+                }
+            }
+        }
+    }
+}
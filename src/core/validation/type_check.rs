@@ -1,7 +1,10 @@
 use crate::constants::element::{
-    ALLOWED_ATTRIBUTES, ALLOWED_CHILDREN, ATTRIBUTE_TYPES, REQUIRED_ATTRIBUTES, UNIQUE_CHILDREN,
+    ALLOWED_ATTRIBUTES, ALLOWED_CHILDREN, ATTRIBUTE_TYPES, PACKAGE_ALLOWED_CHILDREN,
+    REQUIRED_ATTRIBUTES, UNIQUE_CHILDREN,
+};
+use crate::constants::namespaces::{
+    URL_MATHML, URL_PACKAGE_FBC, URL_PACKAGE_LAYOUT, URL_PACKAGE_QUAL, URL_SBML_CORE,
 };
-use crate::constants::namespaces::{URL_MATHML, URL_SBML_CORE};
 use crate::xml::{DynamicProperty, XmlElement, XmlList, XmlProperty, XmlPropertyType, XmlWrapper};
 use crate::SbmlIssue;
 use biodivine_xml_doc::Element;
@@ -179,18 +182,33 @@ pub fn validate_allowed_attributes(
             // According to the specification, the SBML core attributes should be placed in the
             // default empty namespace, with any additional attributes (e.g. added by packages)
             // placed in their respective namespaces. Hence, we can skip validating anything with
-            // a non-empty prefix, as this is likely a non-core attribute. however...
+            // a non-empty prefix, as this is likely a non-core attribute. However, the prefix
+            // must be declared, otherwise the attribute is most likely a typo that would be
+            // silently ignored.
             // TODO:
             //      If we find an attribute that has the core namespace but uses a prefix,
             //      we should report this as an error too, because it is technically out of spec.
+            let is_declared = {
+                let doc = xml_element.read_doc();
+                xml_element
+                    .raw_element()
+                    .namespace_for_prefix(doc.deref(), prefix)
+                    .is_some()
+            };
+            if !is_declared {
+                let message = format!(
+                    "The prefix of the attribute [{full_name}] of the element <{element_name}> \
+                    is not a declared namespace."
+                );
+                let rule_id =
+                    tag_to_attribute_rule_id(element_name.as_str(), attr_name).unwrap_or("10102");
+                issues.push(SbmlIssue::new_error(rule_id, xml_element, message));
+            }
             continue;
         }
 
         if !allowed_attributes.contains(&attr_name) {
-            let message = format!(
-                "An unknown attribute [{}] of the element <{}> found.",
-                attr_name, element_name
-            );
+            let message = unknown_attribute_message(element_name.as_str(), attr_name);
 
             let rule_id =
                 tag_to_attribute_rule_id(element_name.as_str(), attr_name).unwrap_or("10102");
@@ -199,8 +217,28 @@ pub fn validate_allowed_attributes(
     }
 }
 
+/// Builds the error message for an unknown attribute. Attributes that were removed in SBML
+/// Level 3 Version 2 but commonly remain after conversions from older levels get a more
+/// descriptive message.
+fn unknown_attribute_message(element_name: &str, attr_name: &str) -> String {
+    match (element_name, attr_name) {
+        ("reaction", "fast") => "The attribute [fast] of the element <reaction> is not allowed \
+            in SBML Level 3 Version 2 (it was removed from the specification). Remove the \
+            attribute; reactions marked as fast should be converted, e.g. into algebraic rules \
+            describing the rapid equilibrium."
+            .to_string(),
+        _ => format!(
+            "An unknown attribute [{}] of the element <{}> found.",
+            attr_name, element_name
+        ),
+    }
+}
+
 /// Validates for a given element that its children (tag names) are only from the predefined set
 /// of children (tag names). If not, an error is logged in the vector of issues.
+///
+/// Children in the SBML Core namespace are checked using [ALLOWED_CHILDREN], children in the
+/// namespaces of supported packages are checked using [PACKAGE_ALLOWED_CHILDREN].
 pub(crate) fn validate_allowed_children(xml_element: &XmlElement, issues: &mut Vec<SbmlIssue>) {
     let element_name = xml_element.tag_name();
     let allowed_children = ALLOWED_CHILDREN.get(element_name.as_str()).unwrap();
@@ -217,9 +255,18 @@ pub(crate) fn validate_allowed_children(xml_element: &XmlElement, issues: &mut V
             // element at this position.
             let message = "Found a <math> element without the proper MathML namespace.".to_string();
             issues.push(SbmlIssue::new_error("10201", xml_element, message));
+        } else if child_name == "math"
+            && !allowed_children.contains(&"math")
+            && (child_namespace == URL_MATHML || child_namespace == URL_SBML_CORE)
+        {
+            // A misplaced `math` element would be otherwise ignored (MathML namespace) or
+            // reported as an unknown child (core namespace). A dedicated message is more helpful,
+            // but the issue still falls under the allowed children rule of the parent element.
+            let message = format!("A <math> element is not permitted in <{element_name}>.");
+            let rule_id = tag_to_allowed_child_rule_id(element_name.as_str()).unwrap_or("10102");
+            issues.push(SbmlIssue::new_error(rule_id, xml_element, message));
         } else if child_namespace == URL_SBML_CORE {
-            // All other children are expected to be in the SBML Core namespace. Anything else
-            // that is not in the core namespace is skipped.
+            // All other core children must be listed in the allowed children of the element.
             if !allowed_children.contains(&child_name.as_str()) {
                 let message = format!(
                     "An unknown child <{}> of the element <{}> found.",
@@ -229,10 +276,40 @@ pub(crate) fn validate_allowed_children(xml_element: &XmlElement, issues: &mut V
                     tag_to_allowed_child_rule_id(element_name.as_str()).unwrap_or("10102");
                 issues.push(SbmlIssue::new_error(rule_id, xml_element, message));
             }
+        } else if let Some(package) = namespace_to_package(child_namespace.as_str()) {
+            // Children from the namespaces of supported packages are checked against the
+            // package tables. Other namespaces (including unsupported packages) are skipped.
+            let allowed_children = PACKAGE_ALLOWED_CHILDREN
+                .get(package)
+                .and_then(|it| it.get(element_name.as_str()))
+                .copied()
+                .unwrap_or_default();
+            if !allowed_children.contains(&child_name.as_str()) {
+                let message = format!(
+                    "An unknown child <{package}:{child_name}> of the element <{element_name}> found."
+                );
+                // Rule `<package>-20101` restricts the package elements of a <model>.
+                let rule_id = if element_name == "model" {
+                    format!("{package}-20101")
+                } else {
+                    "10102".to_string()
+                };
+                issues.push(SbmlIssue::new_error(rule_id.as_str(), xml_element, message));
+            }
         }
     }
 }
 
+/// The default prefix of a supported package with an entry in [PACKAGE_ALLOWED_CHILDREN].
+fn namespace_to_package(namespace_url: &str) -> Option<&'static str> {
+    match namespace_url {
+        URL_PACKAGE_FBC => Some("fbc"),
+        URL_PACKAGE_LAYOUT => Some("layout"),
+        URL_PACKAGE_QUAL => Some("qual"),
+        _ => None,
+    }
+}
+
 /// Validates for a given element that its children that are required to appear at most once
 /// indeed do. Logs error if this is violated.
 pub(crate) fn validate_unique_children(xml_element: &XmlElement, issues: &mut Vec<SbmlIssue>) {
@@ -321,7 +398,7 @@ fn tag_to_attribute_rule_id(tag_name: &str, attr_name: &str) -> Option<&'static
 
 /// Similar to [tag_to_attribute_rule_id], resolves a tag name into a rule ID which specifies
 /// what child elements are allowed for that particular element.
-fn tag_to_allowed_child_rule_id(tag_name: &str) -> Option<&'static str> {
+pub(crate) fn tag_to_allowed_child_rule_id(tag_name: &str) -> Option<&'static str> {
     match tag_name {
         "listOfFunctionDefinitions" => Some("20206"),
         "listOfUnitDefinitions" => Some("20207"),
@@ -3,8 +3,8 @@ use crate::core::validation::{
     apply_rule_10301, apply_rule_10307, apply_rule_10308, apply_rule_10309, apply_rule_10310,
     apply_rule_10312, apply_rule_10401, apply_rule_10402, SbmlValidable,
 };
-use crate::core::{SBase, Unit};
-use crate::xml::{OptionalXmlChild, OptionalXmlProperty, XmlWrapper};
+use crate::core::{BaseUnit, SBase, Unit};
+use crate::xml::{OptionalXmlChild, OptionalXmlProperty, XmlProperty, XmlWrapper};
 use crate::SbmlIssue;
 use std::collections::HashSet;
 
@@ -30,7 +30,38 @@ impl SbmlValidable for Unit {
             apply_rule_10401(&annotation, issues);
             apply_rule_10402(&annotation, issues);
         }
+        self.apply_base_unit_check(issues);
     }
 }
 
 impl CanTypeCheck for Unit {}
+
+impl Unit {
+    /// ### Suspicious base units (lint)
+    /// A `dimensionless` unit is not affected by its *exponent*, so a [Unit] of this kind
+    /// with an *exponent* other than `1` is most likely a mistake (a non-zero *scale* is fine,
+    /// e.g. percent uses `scale="-2"`). Similarly, `item` and `avogadro` are counts which are
+    /// normally only used with an *exponent* of `1` or `-1`. These are not errors in the
+    /// specification, hence we only report a warning (`lint-base-unit`).
+    pub(crate) fn apply_base_unit_check(&self, issues: &mut Vec<SbmlIssue>) {
+        let Ok(Some(kind)) = self.kind().get_checked() else {
+            return;
+        };
+        let Ok(Some(exponent)) = self.exponent().get_checked() else {
+            return;
+        };
+
+        let suspicious = match kind {
+            BaseUnit::Dimensionless => exponent != 1.0,
+            BaseUnit::Item | BaseUnit::Avogadro => exponent.abs() != 1.0,
+            _ => false,
+        };
+        if suspicious {
+            let message = format!(
+                "(Heuristic) The <unit> of kind `{kind}` uses exponent `{exponent}`, \
+                which is likely a mistake."
+            );
+            issues.push(SbmlIssue::new_warning("lint-base-unit", self, message));
+        }
+    }
+}
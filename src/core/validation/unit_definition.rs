@@ -33,6 +33,7 @@ impl SbmlValidable for UnitDefinition {
         if let Some(list_of_units) = self.units().get() {
             validate_list_of_objects(&list_of_units, issues, identifiers, meta_ids);
         }
+        self.apply_empty_unit_definition_check(issues);
     }
 }
 
@@ -47,6 +48,21 @@ impl CanTypeCheck for UnitDefinition {
 }
 
 impl UnitDefinition {
+    /// ### Empty unit definition (lint)
+    /// Since Level 3 Version 2, the `listOfUnits` of a [UnitDefinition] is optional and can be
+    /// empty. However, such a definition does not describe any unit and is almost always
+    /// a mistake (some tools also reject it), hence we report a warning
+    /// (`lint-empty-unit-definition`).
+    pub(crate) fn apply_empty_unit_definition_check(&self, issues: &mut Vec<SbmlIssue>) {
+        let units = self.units().get().map(|it| it.len()).unwrap_or(0);
+        if units == 0 {
+            let id = self.id().get().unwrap_or_default();
+            let message = format!("The <unitDefinition> ('{id}') does not contain any <unit>.");
+            let rule = "lint-empty-unit-definition";
+            issues.push(SbmlIssue::new_warning(rule, self.xml_element(), message));
+        }
+    }
+
     pub(crate) fn apply_rule_10302(
         list_of_unit_definitions: &XmlList<UnitDefinition>,
         issues: &mut Vec<SbmlIssue>,
@@ -0,0 +1,97 @@
+use crate::core::{
+    AbstractRule, Compartment, Constraint, Event, EventAssignment, FunctionDefinition,
+    InitialAssignment, LocalParameter, Model, ModifierSpeciesReference, Parameter, Reaction,
+    Species, SpeciesReference, Unit, UnitDefinition,
+};
+use crate::xml::{OptionalXmlChild, XmlList, XmlWrapper};
+
+/// A visitor for the typed traversal of a [Model] (see [Model::accept]).
+///
+/// Every method has an empty default implementation, hence an implementation only needs to
+/// override the methods for the elements it is interested in. Parent elements are always
+/// visited before their children (e.g. a [Reaction] is visited before its reactants).
+#[allow(unused_variables)]
+pub trait SbmlVisitor {
+    fn visit_model(&mut self, model: &Model) {}
+    fn visit_function_definition(&mut self, function_definition: &FunctionDefinition) {}
+    fn visit_unit_definition(&mut self, unit_definition: &UnitDefinition) {}
+    fn visit_unit(&mut self, unit: &Unit) {}
+    fn visit_compartment(&mut self, compartment: &Compartment) {}
+    fn visit_species(&mut self, species: &Species) {}
+    fn visit_parameter(&mut self, parameter: &Parameter) {}
+    fn visit_initial_assignment(&mut self, initial_assignment: &InitialAssignment) {}
+    fn visit_rule(&mut self, rule: &AbstractRule) {}
+    fn visit_constraint(&mut self, constraint: &Constraint) {}
+    fn visit_reaction(&mut self, reaction: &Reaction) {}
+    /// Called for both reactants and products of a [Reaction].
+    fn visit_species_reference(&mut self, species_reference: &SpeciesReference) {}
+    fn visit_modifier(&mut self, modifier: &ModifierSpeciesReference) {}
+    fn visit_local_parameter(&mut self, local_parameter: &LocalParameter) {}
+    fn visit_event(&mut self, event: &Event) {}
+    fn visit_event_assignment(&mut self, event_assignment: &EventAssignment) {}
+}
+
+impl Model {
+    /// Walk all core elements of this model in document order and call the corresponding
+    /// method of the `visitor` for each of them.
+    ///
+    /// Elements of SBML packages are not visited.
+    pub fn accept(&self, visitor: &mut impl SbmlVisitor) {
+        visitor.visit_model(self);
+        for function_definition in list_items(self.function_definitions().get()) {
+            visitor.visit_function_definition(&function_definition);
+        }
+        for unit_definition in list_items(self.unit_definitions().get()) {
+            visitor.visit_unit_definition(&unit_definition);
+            for unit in list_items(unit_definition.units().get()) {
+                visitor.visit_unit(&unit);
+            }
+        }
+        for compartment in list_items(self.compartments().get()) {
+            visitor.visit_compartment(&compartment);
+        }
+        for species in list_items(self.species().get()) {
+            visitor.visit_species(&species);
+        }
+        for parameter in list_items(self.parameters().get()) {
+            visitor.visit_parameter(&parameter);
+        }
+        for initial_assignment in list_items(self.initial_assignments().get()) {
+            visitor.visit_initial_assignment(&initial_assignment);
+        }
+        for rule in list_items(self.rules().get()) {
+            visitor.visit_rule(&rule);
+        }
+        for constraint in list_items(self.constraints().get()) {
+            visitor.visit_constraint(&constraint);
+        }
+        for reaction in list_items(self.reactions().get()) {
+            visitor.visit_reaction(&reaction);
+            for reactant in list_items(reaction.reactants().get()) {
+                visitor.visit_species_reference(&reactant);
+            }
+            for product in list_items(reaction.products().get()) {
+                visitor.visit_species_reference(&product);
+            }
+            for modifier in list_items(reaction.modifiers().get()) {
+                visitor.visit_modifier(&modifier);
+            }
+            if let Some(kinetic_law) = reaction.kinetic_law().get() {
+                for local_parameter in list_items(kinetic_law.local_parameters().get()) {
+                    visitor.visit_local_parameter(&local_parameter);
+                }
+            }
+        }
+        for event in list_items(self.events().get()) {
+            visitor.visit_event(&event);
+            for event_assignment in list_items(event.event_assignments().get()) {
+                visitor.visit_event_assignment(&event_assignment);
+            }
+        }
+    }
+}
+
+/// Items of an optional list, or an empty vector if the list is missing.
+fn list_items<T: XmlWrapper>(list: Option<XmlList<T>>) -> Vec<T> {
+    list.map(|it| it.as_vec()).unwrap_or_default()
+}
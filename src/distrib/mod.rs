@@ -0,0 +1,3 @@
+mod uncertainty;
+
+pub use uncertainty::{UncertParameter, Uncertainty};
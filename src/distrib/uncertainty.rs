@@ -0,0 +1,58 @@
+use crate::constants::namespaces::URL_PACKAGE_DISTRIB;
+use crate::core::sbase::SbmlUtils;
+use crate::core::Math;
+use crate::xml::{OptionalChild, XmlElement, XmlList, XmlPropertyType, XmlWrapper};
+use sbml_macros::{SBase, XmlWrapper};
+
+/// The `distrib:uncertainty` element, which describes the uncertainty of the value
+/// of its parent element (e.g. a [Parameter](crate::core::Parameter)).
+#[derive(Clone, Debug, XmlWrapper, SBase)]
+pub struct Uncertainty(XmlElement);
+
+impl Uncertainty {
+    pub fn uncert_parameters(&self) -> OptionalChild<XmlList<UncertParameter>> {
+        self.optional_package_child("listOfUncertParams", URL_PACKAGE_DISTRIB)
+    }
+}
+
+/// The `distrib:uncertParameter` element, which describes one statistical property
+/// of an [Uncertainty] (e.g. its mean, standard deviation, or the full distribution).
+#[derive(Clone, Debug, XmlWrapper, SBase)]
+pub struct UncertParameter(XmlElement);
+
+/// Note that package attributes are namespaced (e.g. `distrib:type`), which is not supported
+/// by [XmlProperty](crate::xml::XmlProperty) yet, hence the attributes are only readable.
+impl UncertParameter {
+    /// The kind of the parameter, e.g. `mean`, `standardDeviation` or `distribution`.
+    pub fn uncert_type(&self) -> Option<String> {
+        self.get_namespaced_attribute("type", URL_PACKAGE_DISTRIB)
+    }
+
+    pub fn value(&self) -> Option<f64> {
+        let value = self.get_namespaced_attribute("value", URL_PACKAGE_DISTRIB)?;
+        f64::try_get(Some(value.as_str())).ok().flatten()
+    }
+
+    /// Reference to an SBML object which holds the value of this parameter.
+    pub fn var(&self) -> Option<String> {
+        self.get_namespaced_attribute("var", URL_PACKAGE_DISTRIB)
+    }
+
+    pub fn units(&self) -> Option<String> {
+        self.get_namespaced_attribute("units", URL_PACKAGE_DISTRIB)
+    }
+
+    /// Identifies the distribution, required when [Self::uncert_type] is `distribution`.
+    pub fn definition_url(&self) -> Option<String> {
+        self.get_namespaced_attribute("definitionURL", URL_PACKAGE_DISTRIB)
+    }
+
+    pub fn math(&self) -> OptionalChild<Math> {
+        self.optional_math_child("math")
+    }
+
+    /// Nested parameters of a distribution (e.g. the mean of a normal distribution).
+    pub fn uncert_parameters(&self) -> OptionalChild<XmlList<UncertParameter>> {
+        self.optional_package_child("listOfUncertParams", URL_PACKAGE_DISTRIB)
+    }
+}
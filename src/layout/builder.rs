@@ -0,0 +1,259 @@
+use crate::constants::namespaces::URL_PACKAGE_LAYOUT;
+use crate::core::{Model, SBase, SimpleSpeciesReference};
+use crate::xml::{
+    FloatFormat, OptionalXmlChild, OptionalXmlProperty, RequiredXmlProperty, XmlDocument,
+    XmlElement, XmlWrapper,
+};
+use std::ops::{Deref, DerefMut};
+
+/// Generates a "skeleton" `layout:layout` of a [Model]: one `layout:speciesGlyph` for every
+/// species and one `layout:reactionGlyph` for every reaction, placed on a regular grid.
+///
+/// Every reaction glyph contains a `layout:speciesReferenceGlyph` for each reactant (role
+/// `substrate`), product (role `product`) and modifier (role `modifier`) of the reaction,
+/// pointing to the glyph of the referenced species. No curves are generated, hence the result
+/// is mainly a starting point for diagram editors which then arrange the glyphs.
+///
+/// The glyph identifiers are derived from the model identifiers: `sg_<species>`,
+/// `rg_<reaction>` and `srg_<reaction>_<index>`.
+#[derive(Clone, Debug)]
+pub struct LayoutBuilder {
+    id: String,
+    columns: usize,
+    glyph_width: f64,
+    glyph_height: f64,
+    spacing: f64,
+}
+
+impl LayoutBuilder {
+    /// Create a new builder of a layout with the given `id`, using a grid with five columns,
+    /// `100x40` glyphs and a spacing of `20` between them.
+    pub fn new(id: &str) -> LayoutBuilder {
+        LayoutBuilder {
+            id: id.to_string(),
+            columns: 5,
+            glyph_width: 100.0,
+            glyph_height: 40.0,
+            spacing: 20.0,
+        }
+    }
+
+    /// Set the number of glyphs in one row of the grid (at least one).
+    pub fn columns(mut self, columns: usize) -> LayoutBuilder {
+        self.columns = columns.max(1);
+        self
+    }
+
+    /// Set the size of the bounding box of every glyph.
+    pub fn glyph_size(mut self, width: f64, height: f64) -> LayoutBuilder {
+        self.glyph_width = width;
+        self.glyph_height = height;
+        self
+    }
+
+    /// Set the space between two neighbouring glyphs (and around the grid).
+    pub fn spacing(mut self, spacing: f64) -> LayoutBuilder {
+        self.spacing = spacing;
+        self
+    }
+
+    /// Generate the layout and append it to the `layout:listOfLayouts` of the given `model`
+    /// (the list is created if necessary). The `layout` package is declared on the root
+    /// `sbml` element (with `layout:required="false"`) unless it is declared already.
+    ///
+    /// Returns the new `layout:layout` element, or an error if the `model` already contains
+    /// a layout with the same *id*, or if the package cannot be declared because its default
+    /// prefix (`layout`) is used by another namespace.
+    pub fn build(&self, model: &Model) -> Result<XmlElement, String> {
+        let prefix = declare_layout_package(model)?;
+        let builder = ElementBuilder {
+            document: model.document(),
+            prefix: prefix.as_str(),
+        };
+
+        let list_of_layouts = builder.child(model.xml_element(), "listOfLayouts")?;
+        let exists = list_of_layouts.child_elements().iter().any(|it| {
+            it.namespace_url() == URL_PACKAGE_LAYOUT
+                && it.get_namespaced_attribute("id", URL_PACKAGE_LAYOUT) == Some(self.id.clone())
+        });
+        if exists {
+            return Err(format!("Layout `{}` already exists.", self.id));
+        }
+
+        let layout = builder.append(&list_of_layouts, "layout")?;
+        builder.set(&layout, "id", self.id.clone());
+
+        let species = model
+            .species()
+            .get()
+            .map(|list| list.iter().map(|it| it.id().get()).collect::<Vec<_>>())
+            .unwrap_or_default();
+        let reactions = model
+            .reactions()
+            .get()
+            .map(|list| list.iter().collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        let cells = species.len() + reactions.len();
+        let rows = cells.div_ceil(self.columns).max(1);
+        let columns = cells.clamp(1, self.columns);
+        let dimensions = builder.append(&layout, "dimensions")?;
+        builder.set_number(&dimensions, "width", self.offset(columns, self.glyph_width));
+        builder.set_number(&dimensions, "height", self.offset(rows, self.glyph_height));
+
+        if !species.is_empty() {
+            let list = builder.append(&layout, "listOfSpeciesGlyphs")?;
+            for (cell, id) in species.iter().enumerate() {
+                let glyph = builder.append(&list, "speciesGlyph")?;
+                builder.set(&glyph, "id", format!("sg_{id}"));
+                builder.set(&glyph, "species", id.clone());
+                self.bounding_box(&builder, &glyph, cell)?;
+            }
+        }
+
+        if !reactions.is_empty() {
+            let list = builder.append(&layout, "listOfReactionGlyphs")?;
+            for (index, reaction) in reactions.iter().enumerate() {
+                let id = reaction.id().get();
+                let glyph = builder.append(&list, "reactionGlyph")?;
+                builder.set(&glyph, "id", format!("rg_{id}"));
+                builder.set(&glyph, "reaction", id.clone());
+                self.bounding_box(&builder, &glyph, species.len() + index)?;
+
+                let mut references = Vec::new();
+                for (list, role) in [
+                    (reaction.reactants(), "substrate"),
+                    (reaction.products(), "product"),
+                ] {
+                    if let Some(list) = list.get() {
+                        references.extend(
+                            list.iter()
+                                .map(|it| (it.species().get(), it.id().get(), role)),
+                        );
+                    }
+                }
+                if let Some(list) = reaction.modifiers().get() {
+                    references.extend(
+                        list.iter()
+                            .map(|it| (it.species().get(), it.id().get(), "modifier")),
+                    );
+                }
+                if references.is_empty() {
+                    continue;
+                }
+
+                let list = builder.append(&glyph, "listOfSpeciesReferenceGlyphs")?;
+                for (index, (species, reference, role)) in references.into_iter().enumerate() {
+                    let reference_glyph = builder.append(&list, "speciesReferenceGlyph")?;
+                    builder.set(&reference_glyph, "id", format!("srg_{id}_{index}"));
+                    builder.set(&reference_glyph, "speciesGlyph", format!("sg_{species}"));
+                    if let Some(reference) = reference {
+                        builder.set(&reference_glyph, "speciesReference", reference);
+                    }
+                    builder.set(&reference_glyph, "role", role.to_string());
+                }
+            }
+        }
+
+        Ok(layout)
+    }
+
+    /// Append a `layout:boundingBox` of the grid cell with the given index to `glyph`.
+    fn bounding_box(
+        &self,
+        builder: &ElementBuilder,
+        glyph: &XmlElement,
+        cell: usize,
+    ) -> Result<(), String> {
+        let bounding_box = builder.append(glyph, "boundingBox")?;
+        let position = builder.append(&bounding_box, "position")?;
+        let x = self.offset(cell % self.columns, self.glyph_width);
+        let y = self.offset(cell / self.columns, self.glyph_height);
+        builder.set_number(&position, "x", x);
+        builder.set_number(&position, "y", y);
+        let dimensions = builder.append(&bounding_box, "dimensions")?;
+        builder.set_number(&dimensions, "width", self.glyph_width);
+        builder.set_number(&dimensions, "height", self.glyph_height);
+        Ok(())
+    }
+
+    /// The total size of `count` glyphs of the given `size` (including spacing).
+    fn offset(&self, count: usize, size: f64) -> f64 {
+        self.spacing + (count as f64) * (size + self.spacing)
+    }
+}
+
+/// Ensure the `layout` package is declared on the root `sbml` element of `model`
+/// and return its prefix.
+fn declare_layout_package(model: &Model) -> Result<String, String> {
+    let root = model
+        .parent()
+        .filter(|it| it.tag_name() == "sbml")
+        .ok_or_else(|| "The model is not the model of an SBML document.".to_string())?;
+
+    let mut doc = root.write_doc();
+    let element = root.raw_element();
+    if let Some(prefix) = element.closest_prefix(doc.deref(), URL_PACKAGE_LAYOUT) {
+        return Ok(prefix.to_string());
+    }
+    if element
+        .namespace_for_prefix(doc.deref(), "layout")
+        .is_some()
+    {
+        return Err("Prefix `layout` is already used by another namespace.".to_string());
+    }
+    element.set_namespace_decl(doc.deref_mut(), "layout", URL_PACKAGE_LAYOUT);
+    if !element
+        .attributes(doc.deref())
+        .contains_key("layout:required")
+    {
+        element.set_attribute(doc.deref_mut(), "layout:required", "false");
+    }
+    Ok("layout".to_string())
+}
+
+/// A helper for creating `layout` elements and attributes with a fixed prefix.
+struct ElementBuilder<'a> {
+    document: XmlDocument,
+    prefix: &'a str,
+}
+
+impl ElementBuilder<'_> {
+    /// Find the first child of `parent` with the given layout `name`, or append a new one.
+    fn child(&self, parent: &XmlElement, name: &str) -> Result<XmlElement, String> {
+        let existing = parent
+            .child_elements()
+            .into_iter()
+            .find(|it| it.tag_name() == name && it.namespace_url() == URL_PACKAGE_LAYOUT);
+        match existing {
+            Some(element) => Ok(element),
+            None => self.append(parent, name),
+        }
+    }
+
+    /// Append a new layout element with the given `name` to `parent`.
+    fn append(&self, parent: &XmlElement, name: &str) -> Result<XmlElement, String> {
+        let element = XmlElement::new_quantified(
+            self.document.clone(),
+            name,
+            (self.prefix, URL_PACKAGE_LAYOUT),
+        );
+        element.try_attach_at(parent, None)?;
+        Ok(element)
+    }
+
+    /// Set the layout attribute `name` of `element`.
+    fn set(&self, element: &XmlElement, name: &str, value: String) {
+        let mut doc = element.write_doc();
+        element.raw_element().set_attribute(
+            doc.deref_mut(),
+            format!("{}:{name}", self.prefix),
+            value,
+        );
+    }
+
+    /// Set a numeric layout attribute `name` of `element`.
+    fn set_number(&self, element: &XmlElement, name: &str, value: f64) {
+        self.set(element, name, FloatFormat::ShortestRoundTrip.format(value));
+    }
+}
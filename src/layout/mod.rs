@@ -0,0 +1,3 @@
+mod builder;
+
+pub use builder::LayoutBuilder;
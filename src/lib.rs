@@ -91,34 +91,59 @@
 //!
 
 use std::collections::HashSet;
-use std::ops::Deref;
+use std::fmt::{Display, Formatter};
+use std::ops::{ControlFlow, Deref, DerefMut};
 use std::str::FromStr;
 use std::sync::{Arc, RwLock};
 
-use biodivine_xml_doc::{Document, Element, ReadOptions};
+use biodivine_xml_doc::{Document, Element, Node, ReadOptions};
 use embed_doc_image::embed_doc_image;
 
-use xml::{OptionalChild, RequiredProperty};
+use xml::{OptionalChild, OptionalProperty, RequiredProperty};
 
-use crate::constants::namespaces::URL_SBML_CORE;
+use crate::constants::namespaces::{
+    URL_MATHML, URL_PACKAGE_COMP, URL_PACKAGE_DISTRIB, URL_RDF, URL_SBML_CORE,
+};
+use crate::core::sbase::{deterministic_meta_id, document_meta_ids, element_ancestors};
 use crate::core::validation::type_check::{internal_type_check, CanTypeCheck};
 use crate::core::validation::{
-    apply_rule_10301, apply_rule_10307, apply_rule_10308, apply_rule_10309, apply_rule_10310,
-    apply_rule_10312, SbmlValidable,
+    apply_math_depth_check, apply_non_finite_value_check, apply_rdf_about_check, apply_rule_10307,
+    apply_rule_10308, apply_rule_10308_to_package_elements, apply_rule_10309, apply_rule_10310,
+    apply_rule_10312, apply_sbase_children_order_check,
+};
+use crate::core::{Math, Model, RuleTypes, SBase};
+use crate::xml::{
+    EditGuard, OptionalXmlChild, OptionalXmlProperty, RequiredXmlProperty, XmlDocument, XmlElement,
+    XmlList, XmlProperty, XmlPropertyType, XmlWrapper,
 };
-use crate::core::{Model, SBase};
-use crate::xml::{OptionalXmlChild, OptionalXmlProperty, XmlDocument, XmlElement, XmlWrapper};
 
 /// Defines [`Model`], [`Species`][core::Species], [`Compartment`][core::Compartment],
 /// [`FunctionDefinition`][core::FunctionDefinition] and other data objects prescribed
 /// by the SBML core specification.
 pub mod core;
 
+/// Defines [`CombineArchive`][combine::CombineArchive], which reads SBML models distributed
+/// as COMBINE archives (requires the `zip` feature).
+#[cfg(feature = "zip")]
+pub mod combine;
+
+/// Defines [`Uncertainty`][distrib::Uncertainty] and [`UncertParameter`][distrib::UncertParameter],
+/// which provide read access to the data of the SBML `distrib` package.
+pub mod distrib;
+
+/// Defines [`LayoutBuilder`][layout::LayoutBuilder], which generates a skeleton diagram
+/// of a model using the SBML `layout` package.
+pub mod layout;
+
 /// Defines [`XmlDocument`], [`XmlElement`], [`XmlWrapper`], [`XmlProperty`][xml::XmlProperty],
 /// [`XmlChild`][xml::XmlChild] and other utility types or traits that can be used to safely
 /// manipulate the underlying XML document.
 pub mod xml;
 
+/// **(internal)** Implements flattening of hierarchical models which use the SBML `comp`
+/// package (see [`Sbml::flatten_comp`]).
+pub(crate) mod comp;
+
 /// **(internal)** An internal module which defines constant values relevant for SBML, such as
 /// namespace URLs or mappings assigning elements their allowed attributes.
 pub(crate) mod constants;
@@ -203,18 +228,175 @@ impl Sbml {
     pub fn version(&self) -> RequiredProperty<u32> {
         RequiredProperty::new(&self.sbml_root, "version")
     }
+
+    /// Returns the SBML *level* and *version* of this document as a pair.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either attribute is missing or invalid, same as [Sbml::level] and
+    /// [Sbml::version].
+    pub fn level_and_version(&self) -> (u32, u32) {
+        (self.level().get(), self.version().get())
+    }
+
+    /// Returns the `comp:modelDefinition` elements of this document (declared in
+    /// `comp:listOfModelDefinitions`), in document order.
+    ///
+    /// Model definitions share the structure of the main [Model], hence they are accessible
+    /// through the same wrapper. Note that `comp:externalModelDefinition` elements are
+    /// not included, since they only reference models in other documents.
+    pub fn model_definitions(&self) -> Vec<Model> {
+        let list: OptionalChild<XmlList<Model>> =
+            OptionalChild::new(&self.sbml_root, "listOfModelDefinitions", URL_PACKAGE_COMP);
+        let Some(list) = list.get() else {
+            return Vec::new();
+        };
+        list.iter()
+            .filter(|it| {
+                it.tag_name() == "modelDefinition" && it.namespace_url() == URL_PACKAGE_COMP
+            })
+            .collect()
+    }
+
+    /// Find a `comp:modelDefinition` of this document by its `id` (see
+    /// [Sbml::model_definitions]).
+    pub fn model_definition_by_id(&self, id: &str) -> Option<Model> {
+        self.model_definitions()
+            .into_iter()
+            .find(|it| it.id().get().as_deref() == Some(id))
+    }
+
+    /// Create a copy of this document where all `comp:submodel` instances of the main [Model]
+    /// are (recursively) inlined, resulting in a single flat model without any `comp` content.
+    ///
+    /// Every submodel is instantiated from its `comp:modelDefinition`: its deletions are
+    /// removed, the identifiers and meta identifiers of its elements are prefixed by the
+    /// submodel identifier (e.g. `A__x` for element `x` of submodel `A`), and the resulting
+    /// core components are merged into the main model. Elements referenced by
+    /// `comp:replacedElement` are removed from the instance and references to them are
+    /// redirected to the replacing element. Elements with `comp:replacedBy` are removed from
+    /// the main model and references to them are redirected to the (renamed) replacement.
+    ///
+    /// Only core components of the submodels are merged; other package content of
+    /// the submodels is discarded. References in the `fbc` and `qual` attributes of merged
+    /// components (e.g. `fbc:lowerFluxBound`) are renamed together with the core references.
+    ///
+    /// Returns an error for constructs which cannot be flattened, e.g. references which
+    /// do not resolve, recursive model definitions, and currently unsupported features
+    /// (external model definitions, conversion factors and nested `comp:sBaseRef`).
+    pub fn flatten_comp(&self) -> Result<Sbml, String> {
+        comp::flatten_document(self)
+    }
+
+    /// Run `action` while holding the write lock of the underlying document, such that
+    /// the properties modified through the provided [EditGuard] do not need to acquire
+    /// the lock repeatedly. This is useful when setting a large number of properties.
+    ///
+    /// # Deadlocks
+    ///
+    /// The lock is not reentrant: within `action`, the document must be accessed only through
+    /// the [EditGuard]. Calling methods that lock the document themselves (reading or writing
+    /// properties and children directly, iterating an [XmlList], creating new elements,
+    /// or [Sbml::validate]) blocks forever. New elements should be created and inserted using
+    /// [EditGuard::new_element] and [EditGuard::attach]. Property objects themselves can be
+    /// safely obtained, because they do not access the document until they are read or written.
+    pub fn edit<R>(&self, action: impl FnOnce(&EditGuard) -> R) -> R {
+        let guard = EditGuard::new(&self.xml);
+        action(&guard)
+    }
+
+    /// Returns the value of the `distrib:required` attribute of the root `sbml` element,
+    /// or `None` if the attribute is not present (i.e. the `distrib` package is not used).
+    pub fn distrib_required(&self) -> Option<bool> {
+        self.package_required(URL_PACKAGE_DISTRIB)
+    }
+
+    /// Returns the value of the `required` attribute which the package with the given
+    /// namespace `package_url` declares on the root `sbml` element.
+    ///
+    /// The result is `None` if the package namespace is not declared, or if the attribute
+    /// is missing or invalid.
+    pub fn package_required(&self, package_url: &str) -> Option<bool> {
+        let value = self
+            .sbml_root
+            .get_namespaced_attribute("required", package_url)?;
+        bool::try_get(Some(value.as_str())).ok().flatten()
+    }
+
+    /// Returns the namespace URL that the given `prefix` is bound to on the root `sbml`
+    /// element, or `None` if the prefix is not declared there. The default namespace
+    /// uses an empty prefix.
+    pub fn url_for_prefix(&self, prefix: &str) -> Option<String> {
+        let doc = self.xml.read().unwrap();
+        self.sbml_root
+            .raw_element()
+            .namespace_decls(doc.deref())
+            .get(prefix)
+            .cloned()
+    }
+
+    /// Returns a prefix that is bound to the namespace `url` on the root `sbml` element,
+    /// or `None` if the namespace is not declared there.
+    ///
+    /// If the namespace is declared under several prefixes, the lexicographically smallest
+    /// one is returned (i.e. an empty prefix if the namespace is also the default namespace).
+    pub fn prefix_for_url(&self, url: &str) -> Option<String> {
+        let doc = self.xml.read().unwrap();
+        self.sbml_root
+            .raw_element()
+            .namespace_decls(doc.deref())
+            .iter()
+            .filter(|(_, value)| value.as_str() == url)
+            .map(|(prefix, _)| prefix)
+            .min()
+            .cloned()
+    }
 }
 
 /// Other methods for creating and manipulating [`Sbml`] container.
 impl Sbml {
     pub fn read_str(file_contents: &str) -> Result<Sbml, String> {
-        // Only accept documents that are using UTF-8.
-        let opts = ReadOptions {
+        Self::read_str_with(file_contents, Whitespace::default())
+    }
+
+    /// Same as [Sbml::read_str], but allows to specify how [Whitespace] in text content
+    /// (e.g. in `notes` or `message`) is handled.
+    pub fn read_str_with(file_contents: &str, whitespace: Whitespace) -> Result<Sbml, String> {
+        let mut options = Self::read_options();
+        whitespace.apply(&mut options);
+        let doc = Document::parse_str_with_opts(file_contents, options).map(|mut doc| {
+            whitespace.normalize(&mut doc);
+            doc
+        });
+        Self::from_parsed_document(doc)
+    }
+
+    /// Read an SBML document from raw bytes (e.g. received over the network).
+    ///
+    /// The encoding is checked directly on the bytes, hence there is no need to convert
+    /// them to `&str` first. Same as [Sbml::read_str], only UTF-8 documents are accepted.
+    pub fn read_bytes(bytes: &[u8]) -> Result<Sbml, String> {
+        let whitespace = Whitespace::default();
+        let mut options = Self::read_options();
+        whitespace.apply(&mut options);
+        let doc = Document::parse_reader_with_opts(bytes, options).map(|mut doc| {
+            whitespace.normalize(&mut doc);
+            doc
+        });
+        Self::from_parsed_document(doc)
+    }
+
+    /// Options used when reading documents: we only accept documents that are using UTF-8.
+    fn read_options() -> ReadOptions {
+        ReadOptions {
             enforce_encoding: true,
             encoding: Some("UTF-8".to_string()),
             ..Default::default()
-        };
-        let doc = match Document::parse_str_with_opts(file_contents, opts) {
+        }
+    }
+
+    fn from_parsed_document(doc: biodivine_xml_doc::Result<Document>) -> Result<Sbml, String> {
+        let doc = match doc {
             Ok(doc) => doc,
             Err(why) => {
                 return if matches!(why, biodivine_xml_doc::Error::CannotDecode) {
@@ -262,6 +444,43 @@ impl Sbml {
         }
     }
 
+    /// A deterministic text representation of this document, which can be used to compare
+    /// documents that differ only in the order of elements or attributes, in namespace
+    /// prefixes, or in whitespace.
+    ///
+    /// The items of `listOf*` elements are sorted by their `id` (or by their canonical form
+    /// if they have no `id`), except for lists where the order is significant (rules and
+    /// layout curve segments). Attributes are sorted by name, text is trimmed and consecutive
+    /// whitespace is collapsed, and comments are removed. Names from namespaces other than
+    /// SBML core are qualified by the namespace URL instead of a prefix. The result is not
+    /// a valid SBML document and is only meant for comparison.
+    pub fn canonical_form(&self) -> Result<String, String> {
+        let doc = match self.xml.read() {
+            Ok(doc) => doc,
+            Err(why) => return Err(why.to_string()),
+        };
+        Ok(core::canonical::canonical_form(
+            doc.deref(),
+            self.sbml_root.raw_element(),
+        ))
+    }
+
+    /// Serialize this document into raw bytes, the counterpart of [Sbml::read_bytes].
+    ///
+    /// The output is always encoded as UTF-8 (and declares `encoding="UTF-8"`), hence it can
+    /// be read back using [Sbml::read_bytes], or converted to a string without checks.
+    pub fn to_xml_bytes(&self) -> Result<Vec<u8>, String> {
+        let doc = match self.xml.read() {
+            Ok(doc) => doc,
+            Err(why) => return Err(why.to_string()),
+        };
+        let mut bytes = Vec::new();
+        match doc.write(&mut bytes) {
+            Ok(()) => Ok(bytes),
+            Err(why) => Err(why.to_string()),
+        }
+    }
+
     /// Perform a basic type checking procedure. If this procedure passes without issues,
     /// the document is safe to work with. If some issues are found, working with the document
     /// can cause the program to panic.
@@ -299,11 +518,338 @@ impl Sbml {
             ));
         }
 
+        if let Some(namespace) = element.namespace_decls(doc.deref()).get("") {
+            if let (Ok(Some(level)), Ok(Some(version))) =
+                (self.level().get_checked(), self.version().get_checked())
+            {
+                Self::apply_rule_20101(level, version, namespace, &self.sbml_root, issues);
+            }
+        }
+
         if let Some(model) = self.model().get() {
             model.type_check(issues);
         }
     }
 
+    /// ### Rule 20101
+    /// The [Sbml] container element must declare the XML Namespace for SBML, and this
+    /// declaration must be consistent with the values of the *level* and *version*
+    /// attributes on the [Sbml] element.
+    fn apply_rule_20101(
+        level: u32,
+        version: u32,
+        namespace: &str,
+        root: &XmlElement,
+        issues: &mut Vec<SbmlIssue>,
+    ) {
+        let expected = match (level, version) {
+            (1, _) => "http://www.sbml.org/sbml/level1".to_string(),
+            (2, 1) => "http://www.sbml.org/sbml/level2".to_string(),
+            (2, _) => format!("http://www.sbml.org/sbml/level2/version{version}"),
+            _ => format!("http://www.sbml.org/sbml/level{level}/version{version}/core"),
+        };
+        if namespace != expected {
+            let message = format!(
+                "The SBML namespace '{namespace}' does not match level {level} \
+                and version {version} (expected '{expected}')."
+            );
+            issues.push(SbmlIssue::new_error("20101", root, message));
+        }
+    }
+
+    /// Repairs `math` elements which are missing the MathML namespace (rule 10201) by moving
+    /// them (and their unprefixed content) into the MathML namespace.
+    ///
+    /// Only `math` elements that are direct children of SBML core elements are considered.
+    /// Returns the number of repaired elements.
+    pub fn auto_fix_math_namespace(&self) -> usize {
+        let broken = self.sbml_root.recursive_child_elements_filtered(|child| {
+            child.tag_name() == "math"
+                && child.namespace_url() != URL_MATHML
+                && child
+                    .parent()
+                    .is_some_and(|parent| parent.namespace_url() == URL_SBML_CORE)
+        });
+        for math in &broken {
+            math.set_namespace(URL_MATHML);
+        }
+        broken.len()
+    }
+
+    /// Assigns a fresh `metaid` to every SBML core element which has an `annotation` but no
+    /// `metaid` (annotations that use RDF refer to the annotated element through its `metaid`).
+    ///
+    /// The generated values are the same as those of [SBase::ensure_meta_id_deterministic]
+    /// and do not collide with any existing `metaid` in the document. Returns the number of
+    /// modified elements.
+    pub fn auto_fix_meta_ids(&self) -> usize {
+        let mut used = document_meta_ids(&self.sbml_root);
+        let mut elements = vec![self.sbml_root.clone()];
+        elements.extend(self.sbml_root.recursive_child_elements());
+        let missing = elements
+            .into_iter()
+            .filter(|it| {
+                it.namespace_url() == URL_SBML_CORE
+                    && it.get_attribute("metaid").is_none()
+                    && it
+                        .child_elements()
+                        .iter()
+                        .any(|c| c.tag_name() == "annotation" && c.namespace_url() == URL_SBML_CORE)
+            })
+            .collect::<Vec<_>>();
+
+        for element in &missing {
+            let meta_id = deterministic_meta_id(element, &used);
+            OptionalProperty::new(element, "metaid").set_some(&meta_id);
+            used.insert(meta_id);
+        }
+        missing.len()
+    }
+
+    /// Removes [FunctionDefinition](core::FunctionDefinition) and
+    /// [UnitDefinition](core::UnitDefinition) objects of SBML core which are not referenced
+    /// anywhere in the document (neither by an attribute, e.g. `units`, nor by a `ci`
+    /// element in MathML).
+    ///
+    /// Definitions which are only used by other removed definitions are removed as well.
+    /// Returns the number of removed elements.
+    pub fn auto_fix_unused_definitions(&self) -> usize {
+        let mut removed = 0;
+        loop {
+            let elements = self.sbml_root.recursive_child_elements();
+            let mut used = HashSet::new();
+            for element in &elements {
+                if element.tag_name() == "ci" && element.namespace_url() == URL_MATHML {
+                    used.insert(element.text_content_trimmed());
+                }
+                // The `id` attributes are declarations, not references.
+                used.extend(
+                    element
+                        .attributes()
+                        .into_iter()
+                        .filter(|(name, _)| name != "id")
+                        .map(|(_, value)| value),
+                );
+            }
+            let unused = elements
+                .into_iter()
+                .filter(|it| {
+                    let tag_name = it.tag_name();
+                    (tag_name == "functionDefinition" || tag_name == "unitDefinition")
+                        && it.namespace_url() == URL_SBML_CORE
+                        && it.get_attribute("id").is_some_and(|id| !used.contains(&id))
+                })
+                .collect::<Vec<_>>();
+            if unused.is_empty() {
+                return removed;
+            }
+            for element in unused {
+                if element.try_detach().is_ok() {
+                    removed += 1;
+                }
+            }
+        }
+    }
+
+    /// Cleans up the annotations of SBML core elements: empty `annotation` elements are
+    /// removed, and the `rdf:about` of every RDF description is set to reference the `metaid`
+    /// of the annotated element. Elements without a `metaid` are skipped (see
+    /// [Sbml::auto_fix_meta_ids]). Returns the number of modified elements.
+    pub fn auto_fix_annotations(&self) -> usize {
+        let mut elements = vec![self.sbml_root.clone()];
+        elements.extend(self.sbml_root.recursive_child_elements());
+        let mut changed = 0;
+        for element in elements {
+            if element.namespace_url() != URL_SBML_CORE || element.tag_name() == "annotation" {
+                continue;
+            }
+            let annotations = element.child_elements_filtered(|it| {
+                it.tag_name() == "annotation" && it.namespace_url() == URL_SBML_CORE
+            });
+            let mut modified = false;
+            for annotation in &annotations {
+                if annotation.child_elements().is_empty()
+                    && annotation.text_content().trim().is_empty()
+                {
+                    modified |= annotation.try_detach().is_ok();
+                }
+            }
+
+            let Some(meta_id) = element.get_attribute("metaid") else {
+                changed += usize::from(modified);
+                continue;
+            };
+            let expected = format!("#{meta_id}");
+            let descriptions = annotations
+                .iter()
+                .filter(|it| !it.is_detached())
+                .flat_map(|it| it.child_elements())
+                .filter(|it| it.tag_name() == "RDF" && it.namespace_url() == URL_RDF)
+                .flat_map(|it| it.child_elements())
+                .filter(|it| it.tag_name() == "Description" && it.namespace_url() == URL_RDF);
+            for description in descriptions {
+                let about = description.get_namespaced_attribute("about", URL_RDF);
+                let prefix = description.prefix();
+                // Without a prefix, the `about` attribute cannot be placed in the RDF namespace.
+                if about.as_deref() == Some(expected.as_str()) || prefix.is_empty() {
+                    continue;
+                }
+                let name = format!("{prefix}:about");
+                let mut doc = description.write_doc();
+                description
+                    .raw_element()
+                    .set_attribute(doc.deref_mut(), name, expected.as_str());
+                modified = true;
+            }
+            changed += usize::from(modified);
+        }
+        changed
+    }
+
+    /// Assigns a fresh `id` to every SBML core element whose `id` duplicates the `id` of
+    /// an earlier element in the same scope (rules 10301, 10302 and 10303): the identifiers
+    /// of a model, the unit definitions of a model, and the local parameters of a kinetic law.
+    ///
+    /// The first element keeps its `id`, hence existing references keep pointing to it. The
+    /// new identifiers have the form `{id}_N` and do not collide with any `id` in the document.
+    /// Returns the number of modified elements.
+    pub fn auto_fix_duplicate_ids(&self) -> usize {
+        let elements = self
+            .sbml_root
+            .recursive_child_elements_filtered(|it| it.namespace_url() == URL_SBML_CORE);
+        let mut used = elements
+            .iter()
+            .filter_map(|it| it.get_attribute("id"))
+            .collect::<HashSet<_>>();
+
+        let mut declared = HashSet::new();
+        let mut changed = 0;
+        for element in elements {
+            let Some(id) = element.get_attribute("id") else {
+                continue;
+            };
+            let tag_name = element.tag_name();
+            // The scope is identified by the kind of the identifier and the enclosing element:
+            // the list of local parameters, or the model (definition) of the element.
+            let scope = if tag_name == "localParameter" {
+                element.parent()
+            } else {
+                element_ancestors(&element).find(|it| {
+                    let name = it.tag_name();
+                    name == "model" || name == "modelDefinition"
+                })
+            };
+            let Some(scope) = scope else {
+                continue;
+            };
+            let kind = tag_name == "unitDefinition";
+            if declared.insert((kind, scope.raw_element(), id.clone())) {
+                continue;
+            }
+            let mut counter = 1;
+            while used.contains(&format!("{id}_{counter}")) {
+                counter += 1;
+            }
+            let fresh = format!("{id}_{counter}");
+            OptionalProperty::new(&element, "id").set_some(&fresh);
+            declared.insert((kind, scope.raw_element(), fresh.clone()));
+            used.insert(fresh);
+            changed += 1;
+        }
+        changed
+    }
+
+    /// Count the components of this document's [Model] and collect the SBML Level 3
+    /// packages declared by the document. If the document has no model, all counts are zero.
+    pub fn statistics(&self) -> ModelStats {
+        let mut stats = ModelStats {
+            packages: self.declared_packages(),
+            ..Default::default()
+        };
+        let Some(model) = self.model().get() else {
+            return stats;
+        };
+
+        fn count<T: XmlWrapper>(list: OptionalChild<XmlList<T>>) -> usize {
+            list.get().map(|it| it.len()).unwrap_or(0)
+        }
+
+        stats.compartments = count(model.compartments());
+        stats.species = count(model.species());
+        stats.parameters = count(model.parameters());
+        stats.local_parameters = model.local_parameter_identifiers().len();
+        stats.reactions = count(model.reactions());
+        stats.initial_assignments = count(model.initial_assignments());
+        stats.constraints = count(model.constraints());
+        stats.events = count(model.events());
+        stats.function_definitions = count(model.function_definitions());
+        stats.unit_definitions = count(model.unit_definitions());
+        if let Some(rules) = model.rules().get() {
+            for rule in rules.iter() {
+                match rule.cast() {
+                    RuleTypes::Assignment(_) => stats.assignment_rules += 1,
+                    RuleTypes::Rate(_) => stats.rate_rules += 1,
+                    RuleTypes::Algebraic(_) => stats.algebraic_rules += 1,
+                    _ => (),
+                }
+            }
+        }
+        stats
+    }
+
+    /// Short names of the SBML Level 3 packages whose namespaces are declared on the
+    /// `sbml` element (e.g. `http://www.sbml.org/sbml/level3/version1/fbc/version2` is `fbc`).
+    fn declared_packages(&self) -> Vec<String> {
+        let doc = self.xml.read().unwrap();
+        let mut packages = self
+            .sbml_root
+            .raw_element()
+            .namespace_decls(doc.deref())
+            .values()
+            .filter(|url| url.starts_with("http://www.sbml.org/sbml/level3/"))
+            .filter(|url| url.as_str() != URL_SBML_CORE)
+            .filter_map(|url| url.rsplit('/').nth(1).map(|it| it.to_string()))
+            .collect::<Vec<_>>();
+        packages.sort();
+        packages.dedup();
+        packages
+    }
+
+    /// Apply the requested automatic repairs to this document, in the given order.
+    ///
+    /// Every [AutoFix] is idempotent, i.e. applying it to an already repaired document does
+    /// not change the document. The result contains the number of elements modified by
+    /// each fix.
+    pub fn apply_fixes(&self, fixes: &[AutoFix]) -> Vec<(AutoFix, usize)> {
+        fixes
+            .iter()
+            .map(|fix| {
+                let changed = match fix {
+                    AutoFix::MathNamespace => self.auto_fix_math_namespace(),
+                    AutoFix::MetaIds => self.auto_fix_meta_ids(),
+                    AutoFix::UnusedDefinitions => self.auto_fix_unused_definitions(),
+                    AutoFix::Annotations => self.auto_fix_annotations(),
+                    AutoFix::DuplicateIds => self.auto_fix_duplicate_ids(),
+                };
+                (*fix, changed)
+            })
+            .collect()
+    }
+
+    /// Check the structure of the document, without running any of the semantic validation
+    /// rules. This is the type check which [Sbml::validate] performs before the full validation.
+    ///
+    /// The check covers the required and allowed attributes and their types, the allowed and
+    /// unique child elements, and the namespaces of the elements supported by this library.
+    /// It is considerably faster than [Sbml::validate], but it is not a replacement for
+    /// the validation against the SBML XSD schema, and a document without issues can still be
+    /// invalid (see [Sbml::validate]).
+    pub fn schema_report(&self) -> Vec<SbmlIssue> {
+        let mut issues: Vec<SbmlIssue> = vec![];
+        self.type_check(&mut issues);
+        issues
+    }
+
     /// Validates the document against validation rules specified in the
     /// [specification](https://sbml.org/specifications/sbml-level-3/version-2/core/release-2/sbml-level-3-version-2-release-2-core.pdf).
     /// Eventual issues are returned in the vector. Empty vector represents a valid document.
@@ -318,32 +864,137 @@ impl Sbml {
     /// a document without an error ensures that the document conforms to the basic
     /// structural and syntactic constraints.
     pub fn validate(&self) -> Vec<SbmlIssue> {
+        let mut issues: Vec<SbmlIssue> = vec![];
+        self.validate_each(|issue| {
+            issues.push(issue);
+            ControlFlow::Continue(())
+        });
+        issues
+    }
+
+    /// Same as [Sbml::validate], but instead of collecting the issues into a vector, each
+    /// issue is passed to the `sink` as soon as the validation stage that found it finishes
+    /// (e.g. once a single list of the [Model] is validated).
+    ///
+    /// This allows tools to display problems in large documents progressively. The order
+    /// of the reported issues is the same as in [Sbml::validate]. Once the `sink` returns
+    /// [ControlFlow::Break], no further issues are reported and the validation stops
+    /// (e.g. when the first error is sufficient).
+    pub fn validate_each(&self, sink: impl FnMut(SbmlIssue) -> ControlFlow<()>) {
+        self.validate_each_with_max_math_depth(Math::DEFAULT_MAX_DEPTH, sink);
+    }
+
+    /// Same as [Sbml::validate], but MathML content is accepted only up to the given nesting
+    /// depth (instead of [Math::DEFAULT_MAX_DEPTH]).
+    ///
+    /// Deeper content is reported as an error (`lint-math-depth`) and the document is not
+    /// validated any further. This protects the validation from adversarial input
+    /// (e.g. files uploaded by users).
+    pub fn validate_with_max_math_depth(&self, max_math_depth: usize) -> Vec<SbmlIssue> {
+        let mut issues: Vec<SbmlIssue> = vec![];
+        self.validate_each_with_max_math_depth(max_math_depth, |issue| {
+            issues.push(issue);
+            ControlFlow::Continue(())
+        });
+        issues
+    }
+
+    /// The implementation of [Sbml::validate_each] with a configurable limit on the MathML
+    /// nesting depth (see [Sbml::validate_with_max_math_depth]).
+    fn validate_each_with_max_math_depth(
+        &self,
+        max_math_depth: usize,
+        mut sink: impl FnMut(SbmlIssue) -> ControlFlow<()>,
+    ) {
+        let mut issues: Vec<SbmlIssue> = vec![];
+        self.type_check(&mut issues);
+        if issues.is_empty() {
+            apply_math_depth_check(self.xml_element(), max_math_depth, &mut issues);
+        }
+
+        if !issues.is_empty() {
+            let _ = Self::flush_issues(&mut issues, &mut sink);
+            return;
+        }
+
+        let _ = self.validate_type_checked(&mut sink);
+    }
+
+    /// Same as [Sbml::validate], but once the built-in validation is finished, the given
+    /// [CustomRule] checks are executed on the document's [Model] (in the given order).
+    ///
+    /// Custom rules are not executed if the document fails the type check (see
+    /// [Sbml::schema_report]), since they could not safely access the model.
+    pub fn validate_with_custom(&self, rules: &[Box<dyn CustomRule>]) -> Vec<SbmlIssue> {
         let mut issues: Vec<SbmlIssue> = vec![];
         self.type_check(&mut issues);
+        if issues.is_empty() {
+            apply_math_depth_check(self.xml_element(), Math::DEFAULT_MAX_DEPTH, &mut issues);
+        }
 
         if !issues.is_empty() {
             return issues;
         }
 
+        let _ = self.validate_type_checked(&mut |issue| {
+            issues.push(issue);
+            ControlFlow::Continue(())
+        });
+        if let Some(model) = self.model().get() {
+            for rule in rules {
+                rule.check(&model, &mut issues);
+            }
+        }
+        issues
+    }
+
+    /// Forward all `issues` to the `sink`, leaving `issues` empty. Stops once the `sink`
+    /// returns [ControlFlow::Break].
+    fn flush_issues(
+        issues: &mut Vec<SbmlIssue>,
+        sink: &mut impl FnMut(SbmlIssue) -> ControlFlow<()>,
+    ) -> ControlFlow<()> {
+        issues.drain(..).try_for_each(sink)
+    }
+
+    /// The part of [Sbml::validate] which is executed once the type check succeeds.
+    ///
+    /// The issues of each validation stage are forwarded to the `sink` once the stage
+    /// is finished.
+    fn validate_type_checked(
+        &self,
+        sink: &mut impl FnMut(SbmlIssue) -> ControlFlow<()>,
+    ) -> ControlFlow<()> {
         let mut identifiers: HashSet<String> = HashSet::new();
         let mut meta_ids: HashSet<String> = HashSet::new();
+        let mut issues: Vec<SbmlIssue> = vec![];
+        let mut flush = |issues: &mut Vec<SbmlIssue>| Self::flush_issues(issues, sink);
 
         let xml_element = self.xml_element();
         let id = self.id();
         let meta_id = self.meta_id();
 
-        apply_rule_10301(id.get(), xml_element, &mut issues, &mut identifiers);
+        // The `id` of the `sbml` element is outside the SId namespace of the model (Section
+        // 4.1.1), hence it is not subject to rule 10301. Its `metaid` is still checked for
+        // uniqueness, since meta identifiers are unique across the whole document.
         apply_rule_10307(meta_id.get(), xml_element, &mut issues, &mut meta_ids);
         apply_rule_10308(self.sbo_term().get(), xml_element, &mut issues);
         apply_rule_10309(meta_id.get(), xml_element, &mut issues);
         apply_rule_10310(id.get(), xml_element, &mut issues);
         apply_rule_10312(self.name().get(), xml_element, &mut issues);
+        flush(&mut issues)?;
 
         if let Some(model) = self.model().get() {
-            model.validate(&mut issues, &mut identifiers, &mut meta_ids);
+            model.validate_in_stages(&mut issues, &mut identifiers, &mut meta_ids, &mut flush)?;
         }
-
-        issues
+        apply_rule_10308_to_package_elements(xml_element, &mut issues);
+        flush(&mut issues)?;
+        apply_rdf_about_check(xml_element, &mut issues);
+        flush(&mut issues)?;
+        apply_non_finite_value_check(xml_element, &mut issues);
+        flush(&mut issues)?;
+        apply_sbase_children_order_check(xml_element, &mut issues);
+        flush(&mut issues)
     }
 }
 
@@ -387,6 +1038,9 @@ pub struct SbmlIssue {
     /// Refers to the "raw" XML element where the issue occurred.
     pub element: Element,
     pub severity: SbmlIssueSeverity,
+    /// The ID of the validation rule. Rules of the SBML specification use their official ID
+    /// (e.g. `10301`, or `fbc-20505` for packages). Heuristic checks which are not part of the
+    /// specification use IDs with the `lint-` prefix (e.g. `lint-non-finite-value`).
     pub rule: String,
     pub message: String,
 }
@@ -402,6 +1056,195 @@ impl SbmlIssue {
             message: message.to_string(),
         }
     }
+
+    /// A helper method to more easily create an [SbmlIssue] with [SbmlIssueSeverity::Warning]
+    /// severity.
+    pub fn new_warning<S: ToString, E: XmlWrapper>(
+        rule: &str,
+        element: &E,
+        message: S,
+    ) -> SbmlIssue {
+        SbmlIssue {
+            element: element.raw_element(),
+            severity: SbmlIssueSeverity::Warning,
+            rule: rule.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    /// A helper method to more easily create an [SbmlIssue] with [SbmlIssueSeverity::Info]
+    /// severity.
+    pub fn new_info<S: ToString, E: XmlWrapper>(rule: &str, element: &E, message: S) -> SbmlIssue {
+        SbmlIssue {
+            element: element.raw_element(),
+            severity: SbmlIssueSeverity::Info,
+            rule: rule.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+/// Controls how whitespace in text content is handled when reading a document
+/// (see [Sbml::read_str_with]).
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq, Default)]
+pub enum Whitespace {
+    /// Text nodes within the `notes` and `annotation` subtrees are kept exactly as they appear
+    /// in the document, while the rest of the document is trimmed as with [Whitespace::Trim].
+    /// This is the behaviour of [Sbml::read_str].
+    ///
+    /// The underlying parser cannot treat subtrees differently, hence the document is read
+    /// with the [ReadOptions] of [Whitespace::Preserve] and the text nodes outside of `notes`
+    /// and `annotation` are trimmed afterwards.
+    #[default]
+    PreserveNotes,
+    /// Leading and trailing whitespace of every text node is removed and whitespace-only
+    /// text nodes are dropped (maps to `trim_text = true` in [ReadOptions]).
+    Trim,
+    /// Text nodes are kept exactly as they appear in the document, including whitespace-only
+    /// nodes (maps to `trim_text = false` and `ignore_whitespace_only = false` in
+    /// [ReadOptions]).
+    Preserve,
+}
+
+impl Whitespace {
+    fn apply(&self, options: &mut ReadOptions) {
+        match self {
+            Whitespace::Trim => {
+                options.trim_text = true;
+            }
+            Whitespace::Preserve | Whitespace::PreserveNotes => {
+                options.trim_text = false;
+                options.ignore_whitespace_only = false;
+            }
+        }
+    }
+
+    /// Trim the text nodes of a parsed `doc` which were not trimmed by the parser, but
+    /// should be according to this option.
+    fn normalize(&self, doc: &mut Document) {
+        if *self != Whitespace::PreserveNotes {
+            return;
+        }
+        let mut stack = doc.root_element().into_iter().collect::<Vec<_>>();
+        while let Some(element) = stack.pop() {
+            let is_preserved = matches!(element.name(doc), "notes" | "annotation")
+                && element.namespace(doc) == Some(URL_SBML_CORE);
+            if is_preserved {
+                continue;
+            }
+            let children = element.clear_children(doc);
+            for child in children {
+                let child = match child {
+                    Node::Text(text) => {
+                        let text = text.trim();
+                        if text.is_empty() {
+                            continue;
+                        }
+                        Node::Text(text.to_string())
+                    }
+                    Node::Element(child) => {
+                        stack.push(child);
+                        Node::Element(child)
+                    }
+                    other => other,
+                };
+                element.push_child(doc, child).unwrap();
+            }
+        }
+    }
+}
+
+/// A user-defined validation rule, executed by [Sbml::validate_with_custom] after the
+/// built-in validation. This can be used to enforce project-specific conventions (e.g.
+/// naming schemes or required annotations).
+///
+/// Issues are reported using the standard [SbmlIssue] type. To avoid collisions with the
+/// official rule identifiers (which are numeric, e.g. `10301`, or prefixed with a package
+/// name, e.g. `fbc-20301`), the `rule` of a custom issue should use a prefix that clearly
+/// identifies its origin, e.g. `myorg:naming-01`.
+pub trait CustomRule {
+    /// Check the given `model` and append any discovered issues to `issues`.
+    fn check(&self, model: &Model, issues: &mut Vec<SbmlIssue>);
+}
+
+/// Automatic repairs which can be applied to a document using [Sbml::apply_fixes].
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+pub enum AutoFix {
+    /// Move `math` elements without the MathML namespace into this namespace
+    /// (see [Sbml::auto_fix_math_namespace]).
+    MathNamespace,
+    /// Assign a `metaid` to annotated elements which do not have one
+    /// (see [Sbml::auto_fix_meta_ids]).
+    MetaIds,
+    /// Remove function and unit definitions which are never used
+    /// (see [Sbml::auto_fix_unused_definitions]).
+    UnusedDefinitions,
+    /// Remove empty annotations and fix the `rdf:about` references of RDF annotations
+    /// (see [Sbml::auto_fix_annotations]). Apply [AutoFix::MetaIds] first to also fix
+    /// annotated elements without a `metaid`.
+    Annotations,
+    /// Re-mint identifiers which duplicate an earlier identifier in the same scope
+    /// (see [Sbml::auto_fix_duplicate_ids]).
+    DuplicateIds,
+}
+
+/// Summary counts of the components of an SBML document (see [Sbml::statistics]).
+#[derive(Debug, Clone, Default, Hash, Eq, PartialEq)]
+pub struct ModelStats {
+    pub compartments: usize,
+    pub species: usize,
+    /// Global parameters (i.e. [crate::core::Parameter] objects).
+    pub parameters: usize,
+    /// Local parameters declared by the kinetic laws of all reactions.
+    pub local_parameters: usize,
+    pub reactions: usize,
+    pub initial_assignments: usize,
+    pub assignment_rules: usize,
+    pub rate_rules: usize,
+    pub algebraic_rules: usize,
+    pub constraints: usize,
+    pub events: usize,
+    pub function_definitions: usize,
+    pub unit_definitions: usize,
+    /// Short names (e.g. `fbc`) of the SBML Level 3 packages declared on the `sbml` element,
+    /// sorted alphabetically.
+    pub packages: Vec<String>,
+}
+
+impl ModelStats {
+    /// Total number of rules of all types.
+    pub fn rules(&self) -> usize {
+        self.assignment_rules + self.rate_rules + self.algebraic_rules
+    }
+}
+
+impl Display for ModelStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} compartments, {} species, {} reactions, {} parameters ({} local), \
+            {} initial assignments, {} rules ({} assignment, {} rate, {} algebraic), \
+            {} constraints, {} events, {} function definitions, {} unit definitions",
+            self.compartments,
+            self.species,
+            self.reactions,
+            self.parameters,
+            self.local_parameters,
+            self.initial_assignments,
+            self.rules(),
+            self.assignment_rules,
+            self.rate_rules,
+            self.algebraic_rules,
+            self.constraints,
+            self.events,
+            self.function_definitions,
+            self.unit_definitions,
+        )?;
+        if !self.packages.is_empty() {
+            write!(f, ", packages: {}", self.packages.join(", "))?;
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
@@ -420,22 +1263,42 @@ pub enum SbmlIssueSeverity {
 
 #[cfg(test)]
 mod tests {
-    use std::ops::{Deref, DerefMut};
-
-    use crate::constants::namespaces::{NS_EMPTY, NS_HTML, NS_SBML_CORE, URL_EMPTY, URL_SBML_CORE};
+    use std::collections::{HashMap, HashSet};
+    use std::fmt::Debug;
+    use std::ops::{ControlFlow, Deref, DerefMut};
+    use strum::IntoEnumIterator;
+
+    use crate::constants::namespaces::{
+        NS_EMPTY, NS_HTML, NS_SBML_CORE, URL_EMPTY, URL_MATHML, URL_PACKAGE_COMP, URL_PACKAGE_FBC,
+        URL_PACKAGE_LAYOUT, URL_PACKAGE_QUAL, URL_SBML_CORE,
+    };
     use crate::core::RuleTypes::Assignment;
     use crate::core::{
         AlgebraicRule, AssignmentRule, BaseUnit, Compartment, Constraint, Delay, Event,
         EventAssignment, FunctionDefinition, InitialAssignment, KineticLaw, LocalParameter, Math,
-        Model, ModifierSpeciesReference, Parameter, Priority, RateRule, Reaction, Rule, RuleTypes,
-        SBase, SimpleSpeciesReference, Species, SpeciesReference, Trigger, Unit, UnitDefinition,
+        MetaId, Model, ModifierSpeciesReference, Parameter, Priority, QuantityKind, RateRule,
+        Reaction, Rule, RuleTypes, SBase, SId, SbmlVisitor, SboTerm, SimpleSpeciesReference,
+        Species, SpeciesReference, Trigger, Unit, UnitDefinition, ValidateSubtree,
     };
+    use crate::layout::LayoutBuilder;
     use crate::xml::{
-        OptionalXmlChild, OptionalXmlProperty, RequiredDynamicChild, RequiredDynamicProperty,
-        RequiredXmlChild, RequiredXmlProperty, XmlChild, XmlChildDefault, XmlDefault, XmlElement,
-        XmlProperty, XmlSubtype, XmlSupertype, XmlWrapper,
+        FloatFormat, OptionalDynamicProperty, OptionalXmlChild, OptionalXmlProperty,
+        RequiredDynamicChild, RequiredDynamicProperty, RequiredXmlChild, RequiredXmlProperty,
+        XmlChild, XmlChildDefault, XmlDefault, XmlElement, XmlList, XmlProperty, XmlPropertyType,
+        XmlSubtype, XmlSupertype, XmlWrapper,
     };
-    use crate::Sbml;
+    use crate::{AutoFix, CustomRule, ModelStats, Sbml, SbmlIssue, SbmlIssueSeverity, Whitespace};
+
+    /// Checks that writing `value` as an XML attribute and reading it back recovers `value`.
+    fn assert_property_roundtrip<T: XmlPropertyType + PartialEq + Debug>(value: T) {
+        let raw = value.set();
+        let restored = T::try_get(raw.as_deref());
+        assert_eq!(
+            restored,
+            Ok(Some(value)),
+            "Property value does not round-trip through {raw:?}."
+        );
+    }
 
     /// Checks `SbmlDocument`'s properties such as `version` and `level`.
     /// Additionally, checks if `Model` retrieval returns correct child.
@@ -575,6 +1438,53 @@ mod tests {
         );
     }
 
+    #[test]
+    pub fn test_property_set_if_changed() {
+        let doc = Sbml::read_path("test-inputs/model.sbml").unwrap();
+        let model = doc.model().get().unwrap();
+
+        let value: OptionalDynamicProperty<'_, f64> = model.optional_property("value");
+        assert!(value.set_if_changed(Some(&1.0)));
+        assert!(!value.set_if_changed(Some(&1.0)));
+        // Equivalent representations are not rewritten.
+        value.set_raw("1.000".to_string());
+        assert!(!value.set_if_changed(Some(&1.0)));
+        assert_eq!(value.get_raw(), Some("1.000".to_string()));
+        assert!(value.set_if_changed(None));
+        assert!(!value.set_if_changed(None));
+
+        let name: RequiredDynamicProperty<'_, String> = model.required_property("required");
+        assert!(name.set_if_changed(&"a".to_string()));
+        assert!(!name.set_if_changed(&"a".to_string()));
+        assert!(name.set_if_changed(&"b".to_string()));
+        assert_eq!(name.get(), "b");
+    }
+
+    #[test]
+    pub fn test_property_defaults() {
+        let doc = Sbml::read_path("test-inputs/model.sbml").unwrap();
+        let model = doc.model().get().unwrap();
+
+        let value: OptionalDynamicProperty<'_, f64> = model.optional_property("value");
+        assert!(!value.is_set());
+        assert_eq!(value.get_or_set_default(&2.0), 2.0);
+        assert_eq!(value.get(), Some(2.0));
+        assert_eq!(value.get_or_set_default(&3.0), 2.0);
+        value.set_raw("invalid".to_string());
+        assert_eq!(value.get_or_set_default(&3.0), 3.0);
+        assert_eq!(value.get(), Some(3.0));
+
+        let constant: RequiredDynamicProperty<'_, bool> = model.required_property("constant");
+        assert!(!constant.is_set());
+        constant.ensure(&true);
+        assert!(constant.get());
+        constant.ensure(&false);
+        assert!(constant.get());
+        constant.set_raw("maybe".to_string());
+        constant.ensure(&false);
+        assert!(!constant.get());
+    }
+
     /// Tests get/set operations on `OptionalChild<>`.
     /// Attempts to remove and create a new custom `OptionalChild<>`.
     #[test]
@@ -685,6 +1595,46 @@ mod tests {
         assert_eq!(content.get(0).raw_element(), compartment2.raw_element());
     }
 
+    #[test]
+    pub fn test_list_swap_and_move() {
+        let doc = Sbml::read_path("test-inputs/model.sbml").unwrap();
+        let model = doc.model().get().unwrap();
+        let content = model.compartments().get().unwrap();
+        let compartment1 = content.get(0);
+        let compartment2: Compartment = Compartment::default(compartment1.document());
+        let compartment3: Compartment = Compartment::default(compartment1.document());
+        content.insert(0, compartment2.clone());
+        content.push(compartment3.clone());
+        assert_eq!(content.get(0).raw_element(), compartment2.raw_element());
+        assert_eq!(content.get(1).raw_element(), compartment1.raw_element());
+
+        content.swap(0, 2);
+        assert_eq!(content.get(0).raw_element(), compartment3.raw_element());
+        assert_eq!(content.get(1).raw_element(), compartment1.raw_element());
+        assert_eq!(content.get(2).raw_element(), compartment2.raw_element());
+        content.move_to(0, 2);
+        assert_eq!(content.get(0).raw_element(), compartment1.raw_element());
+        assert_eq!(content.get(1).raw_element(), compartment2.raw_element());
+        assert_eq!(content.get(2).raw_element(), compartment3.raw_element());
+        content.move_to(1, 0);
+        assert_eq!(content.get(0).raw_element(), compartment2.raw_element());
+        assert_eq!(content.get(1).raw_element(), compartment1.raw_element());
+        assert_eq!(content.len(), 3);
+
+        // An invalid target position is rejected before the list is modified.
+        let before = content
+            .iter()
+            .map(|it| it.raw_element())
+            .collect::<Vec<_>>();
+        let result = std::panic::catch_unwind(|| content.move_to(0, 3));
+        assert!(result.is_err());
+        let after = content
+            .iter()
+            .map(|it| it.raw_element())
+            .collect::<Vec<_>>();
+        assert_eq!(before, after);
+    }
+
     #[test]
     pub fn test_build_doc() {
         let sbml_doc = Sbml::default();
@@ -1124,6 +2074,35 @@ mod tests {
         assert!(annotation.get().is_some());
     }
 
+    #[test]
+    pub fn test_ancestors() {
+        let doc =
+            Sbml::read_path("test-inputs/cholesterol_metabolism_and_atherosclerosis.xml").unwrap();
+        let model = doc.model().get().unwrap();
+        let specie = model.species().get().unwrap().get(0);
+
+        let ancestors = specie
+            .ancestors()
+            .map(|it| it.tag_name())
+            .collect::<Vec<_>>();
+        assert_eq!(ancestors, vec!["listOfSpecies", "model", "sbml"]);
+        assert_eq!(doc.ancestors().count(), 0);
+
+        let (id, element) = specie.nearest_ancestor_with_id().unwrap();
+        assert_eq!(id, "McAuley2012___Whole_body_Cholesterol_Metabolism");
+        assert_eq!(element.raw_element(), model.raw_element());
+
+        assert_eq!(specie.tag_path(), "sbml/model/listOfSpecies/species");
+        assert_eq!(doc.tag_path(), "sbml");
+        assert_eq!(
+            specie.tag_path_with_ids(),
+            format!(
+                "sbml/model[@id={id}]/listOfSpecies/species[@id={}]",
+                specie.id().get()
+            )
+        );
+    }
+
     #[test]
     pub fn test_function_definitions() {
         let doc =
@@ -1140,6 +2119,11 @@ mod tests {
         let f_definition = f_defs.get(0);
         assert!(f_definition.annotation().is_set());
         assert!(f_definition.math().is_set());
+        assert_eq!(
+            f_definition.argument_names(),
+            vec!["k6", "species_2", "species_5"]
+        );
+        assert_eq!(f_definition.body().unwrap().tag_name(), "apply");
     }
 
     #[test]
@@ -1346,10 +2330,10 @@ mod tests {
         let product = products.pop();
         let modifier = modifiers.pop();
         assert_eq!(reactant.species().get(), "species_1");
-        assert!(reactant.constant().get_checked().unwrap().is_none()); // doesn't conform to level3/version1/core
+        assert!(reactant.constant().get_checked().unwrap().is_none()); // missing, reported as 21116 by validation
         assert_eq!(reactant.stoichiometry().get().unwrap(), 1.0);
         assert_eq!(product.species().get(), "species_2");
-        assert!(product.constant().get_checked().unwrap().is_none()); // doesn't conform to level3/version1/core
+        assert!(product.constant().get_checked().unwrap().is_none()); // missing, reported as 21116 by validation
         assert_eq!(product.stoichiometry().get().unwrap(), 1.0);
         assert_eq!(modifier.species().get(), "species_1");
 
@@ -1430,6 +2414,4045 @@ mod tests {
         assert!(evt_assgnmnt.math().is_set());
     }
 
+    #[test]
+    pub fn test_event_priority_with_delay() {
+        let event = |id: &str, delay: bool| {
+            let delay = if delay {
+                r#"<delay><math xmlns="http://www.w3.org/1998/Math/MathML"><cn>1</cn></math></delay>"#
+            } else {
+                ""
+            };
+            format!(
+                r#"<event id="{id}" useValuesFromTriggerTime="true">
+                    <trigger initialValue="true" persistent="true">
+                        <math xmlns="http://www.w3.org/1998/Math/MathML"><true/></math>
+                    </trigger>
+                    <priority><math xmlns="http://www.w3.org/1998/Math/MathML"><cn>1</cn></math></priority>
+                    {delay}
+                </event>"#
+            )
+        };
+        let doc = Sbml::read_str(
+            format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+                <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                    <model><listOfEvents>{}{}</listOfEvents></model>
+                </sbml>"#,
+                event("e1", true),
+                event("e2", false)
+            )
+            .as_str(),
+        )
+        .unwrap();
+
+        let issues = doc.validate();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule, "lint-event-priority-with-delay");
+        assert_eq!(issues[0].severity, SbmlIssueSeverity::Warning);
+        assert!(issues[0].message.contains("('e1')"));
+    }
+
+    #[test]
+    pub fn test_event_child_order() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model>
+                    <listOfParameters>
+                        <parameter id="p" value="1" constant="false"/>
+                    </listOfParameters>
+                    <listOfEvents>
+                        <event id="e" useValuesFromTriggerTime="true">
+                            <delay>
+                                <math xmlns="http://www.w3.org/1998/Math/MathML"><cn> 1 </cn></math>
+                            </delay>
+                            <listOfEventAssignments>
+                                <eventAssignment variable="p">
+                                    <math xmlns="http://www.w3.org/1998/Math/MathML"><cn> 2 </cn></math>
+                                </eventAssignment>
+                            </listOfEventAssignments>
+                            <trigger initialValue="true" persistent="true">
+                                <math xmlns="http://www.w3.org/1998/Math/MathML"><true/></math>
+                            </trigger>
+                        </event>
+                    </listOfEvents>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        let issues = doc.validate();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule, "10102");
+        assert_eq!(issues[0].severity, SbmlIssueSeverity::Error);
+        assert!(issues[0].message.contains(
+            "<trigger> of <event> ('e') must appear before its <listOfEventAssignments>"
+        ));
+    }
+
+    #[test]
+    pub fn test_event_missing_use_values_from_trigger_time() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model id="model">
+                    <listOfEvents>
+                        <event id="event"/>
+                    </listOfEvents>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        let issues = doc.validate();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule, "21225");
+        assert_eq!(issues[0].severity, SbmlIssueSeverity::Error);
+    }
+
+    #[test]
+    pub fn test_fbc_strict_flux_bounds() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core"
+                  xmlns:fbc="http://www.sbml.org/sbml/level3/version1/fbc/version2"
+                  level="3" version="2" fbc:required="false">
+                <model id="model" fbc:strict="true">
+                    <listOfParameters>
+                        <parameter id="lb" value="0" constant="true"/>
+                        <parameter id="ub" constant="false"/>
+                    </listOfParameters>
+                    <listOfReactions>
+                        <reaction id="r1" reversible="false"
+                                  fbc:lowerFluxBound="lb" fbc:upperFluxBound="ub"/>
+                        <reaction id="r2" reversible="false" fbc:lowerFluxBound="missing"/>
+                    </listOfReactions>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        assert_eq!(doc.model().get().unwrap().fbc_strict(), Some(true));
+        let mut rules = doc
+            .validate()
+            .into_iter()
+            .filter(|issue| issue.severity == SbmlIssueSeverity::Error)
+            .map(|issue| issue.rule)
+            .collect::<Vec<_>>();
+        rules.sort();
+        assert_eq!(
+            rules,
+            vec!["fbc-20701", "fbc-20703", "fbc-20704", "fbc-20705"]
+        );
+    }
+
+    #[test]
+    pub fn test_fbc_objectives() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core"
+                  xmlns:fbc="http://www.sbml.org/sbml/level3/version1/fbc/version2"
+                  level="3" version="2" fbc:required="false">
+                <model id="model">
+                    <listOfReactions>
+                        <reaction id="r1" reversible="false"/>
+                    </listOfReactions>
+                    <fbc:listOfObjectives fbc:activeObjective="obj2">
+                        <fbc:objective fbc:id="obj1" fbc:type="maximize">
+                            <fbc:listOfFluxObjectives>
+                                <fbc:fluxObjective fbc:reaction="r1" fbc:coefficient="1"/>
+                                <fbc:fluxObjective fbc:reaction="r2" fbc:coefficient="INF"/>
+                            </fbc:listOfFluxObjectives>
+                        </fbc:objective>
+                    </fbc:listOfObjectives>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        let model = doc.model().get().unwrap();
+        assert_eq!(model.fbc_active_objective(), Some("obj2".to_string()));
+        let mut rules = doc
+            .validate()
+            .into_iter()
+            .filter(|issue| issue.severity == SbmlIssueSeverity::Error)
+            .map(|issue| issue.rule)
+            .collect::<Vec<_>>();
+        rules.sort();
+        assert_eq!(rules, vec!["fbc-20505", "fbc-20603", "fbc-20604"]);
+    }
+
+    #[test]
+    pub fn test_fbc_gene_products() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core"
+                  xmlns:fbc="http://www.sbml.org/sbml/level3/version1/fbc/version2"
+                  level="3" version="2" fbc:required="false">
+                <model id="model">
+                    <listOfReactions>
+                        <reaction id="r1" reversible="false">
+                            <fbc:geneProductAssociation>
+                                <fbc:or>
+                                    <fbc:geneProductRef fbc:geneProduct="g1"/>
+                                    <fbc:geneProductRef fbc:geneProduct="g3"/>
+                                </fbc:or>
+                            </fbc:geneProductAssociation>
+                        </reaction>
+                    </listOfReactions>
+                    <fbc:listOfGeneProducts>
+                        <fbc:geneProduct fbc:id="g1" fbc:label="b0001"/>
+                        <fbc:geneProduct fbc:id="g2" fbc:label="b0001"/>
+                    </fbc:listOfGeneProducts>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        let issues = doc
+            .validate()
+            .into_iter()
+            .filter(|issue| {
+                issue.rule == "fbc-20908" || issue.rule == "lint-fbc-duplicate-gene-product-label"
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].rule, "lint-fbc-duplicate-gene-product-label");
+        assert_eq!(issues[0].severity, SbmlIssueSeverity::Warning);
+        assert_eq!(issues[1].rule, "fbc-20908");
+        assert_eq!(issues[1].severity, SbmlIssueSeverity::Error);
+        assert!(issues[0].message.contains("('b0001')"));
+        assert!(issues[0].message.contains("('g2')"));
+        assert!(issues[1].message.contains("('g3')"));
+        assert!(issues[1].message.contains("('r1')"));
+    }
+
+    #[test]
+    pub fn test_distrib_uncertainty() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core"
+                  xmlns:distrib="http://www.sbml.org/sbml/level3/version1/distrib/version1"
+                  level="3" version="2" distrib:required="true">
+                <model id="model">
+                    <listOfParameters>
+                        <parameter id="k" value="2.5" constant="true">
+                            <distrib:uncertainty>
+                                <distrib:listOfUncertParams>
+                                    <distrib:uncertParameter distrib:type="mean" distrib:value="2.5"/>
+                                    <distrib:uncertParameter distrib:type="standardDeviation" distrib:value="0.5"/>
+                                </distrib:listOfUncertParams>
+                            </distrib:uncertainty>
+                        </parameter>
+                    </listOfParameters>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        assert_eq!(doc.distrib_required(), Some(true));
+        assert!(doc.validate().is_empty());
+        let model = doc.model().get().unwrap();
+        let parameter = model.parameters().get().unwrap().get(0);
+        let uncertainty = parameter.uncertainty().get().unwrap();
+        let params = uncertainty.uncert_parameters().get().unwrap();
+        assert_eq!(params.len(), 2);
+        assert_eq!(params.get(0).uncert_type(), Some("mean".to_string()));
+        assert_eq!(params.get(1).value(), Some(0.5));
+    }
+
+    #[test]
+    pub fn test_layout_builder() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model id="model">
+                    <listOfCompartments>
+                        <compartment id="c" size="1" constant="true"/>
+                    </listOfCompartments>
+                    <listOfSpecies>
+                        <species id="a" compartment="c" hasOnlySubstanceUnits="false"
+                            boundaryCondition="false" constant="false"/>
+                        <species id="b" compartment="c" hasOnlySubstanceUnits="false"
+                            boundaryCondition="false" constant="false"/>
+                    </listOfSpecies>
+                    <listOfReactions>
+                        <reaction id="r" reversible="false">
+                            <listOfReactants>
+                                <speciesReference id="ra" species="a" constant="true"/>
+                            </listOfReactants>
+                            <listOfProducts>
+                                <speciesReference species="b" constant="true"/>
+                            </listOfProducts>
+                        </reaction>
+                    </listOfReactions>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+        let model = doc.model().get().unwrap();
+        let layout_url = "http://www.sbml.org/sbml/level3/version1/layout/version1";
+
+        let layout = LayoutBuilder::new("auto").columns(2).build(&model).unwrap();
+        assert_eq!(doc.package_required(layout_url), Some(false));
+        assert!(LayoutBuilder::new("auto").build(&model).is_err());
+
+        let glyphs = layout.recursive_child_elements_filtered(|it| {
+            it.tag_name() == "speciesGlyph" || it.tag_name() == "reactionGlyph"
+        });
+        let references = glyphs
+            .iter()
+            .map(|it| {
+                let attribute = if it.tag_name() == "speciesGlyph" {
+                    "species"
+                } else {
+                    "reaction"
+                };
+                it.get_namespaced_attribute(attribute, layout_url).unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(references, vec!["a", "b", "r"]);
+
+        // The reaction glyph is in the second row of the grid.
+        let position =
+            glyphs[2].recursive_child_elements_filtered(|it| it.tag_name() == "position");
+        assert_eq!(
+            position[0]
+                .get_namespaced_attribute("x", layout_url)
+                .unwrap(),
+            "20"
+        );
+        assert_eq!(
+            position[0]
+                .get_namespaced_attribute("y", layout_url)
+                .unwrap(),
+            "80"
+        );
+
+        let reference_glyphs =
+            layout.recursive_child_elements_filtered(|it| it.tag_name() == "speciesReferenceGlyph");
+        assert_eq!(reference_glyphs.len(), 2);
+        let attribute =
+            |i: usize, name: &str| reference_glyphs[i].get_namespaced_attribute(name, layout_url);
+        assert_eq!(attribute(0, "speciesGlyph"), Some("sg_a".to_string()));
+        assert_eq!(attribute(0, "speciesReference"), Some("ra".to_string()));
+        assert_eq!(attribute(0, "role"), Some("substrate".to_string()));
+        assert_eq!(attribute(1, "role"), Some("product".to_string()));
+        assert_eq!(attribute(1, "speciesReference"), None);
+
+        // The layout elements are declared only once, on the root element.
+        let xml = doc.to_xml_string().unwrap();
+        assert_eq!(xml.matches("xmlns:layout").count(), 1);
+        assert!(doc.validate().is_empty());
+        let reloaded = Sbml::read_str(&xml).unwrap();
+        assert!(reloaded.validate().is_empty());
+    }
+
+    #[test]
+    pub fn test_layout_species_reference_glyphs() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core"
+                  xmlns:layout="http://www.sbml.org/sbml/level3/version1/layout/version1"
+                  level="3" version="2" layout:required="false">
+                <model id="model">
+                    <listOfCompartments>
+                        <compartment id="c" size="1" constant="true"/>
+                    </listOfCompartments>
+                    <listOfSpecies>
+                        <species id="a" compartment="c" hasOnlySubstanceUnits="false"
+                            boundaryCondition="false" constant="false"/>
+                    </listOfSpecies>
+                    <listOfReactions>
+                        <reaction id="r1" reversible="false">
+                            <listOfReactants>
+                                <speciesReference id="ref1" species="a" constant="true"/>
+                            </listOfReactants>
+                        </reaction>
+                        <reaction id="r2" reversible="false">
+                            <listOfProducts>
+                                <speciesReference id="ref2" species="a" constant="true"/>
+                            </listOfProducts>
+                        </reaction>
+                    </listOfReactions>
+                    <layout:listOfLayouts>
+                        <layout:layout layout:id="l">
+                            <layout:listOfReactionGlyphs>
+                                <layout:reactionGlyph layout:id="g1" layout:reaction="r1">
+                                    <layout:listOfSpeciesReferenceGlyphs>
+                                        <layout:speciesReferenceGlyph layout:id="s1" layout:speciesReference="ref1"/>
+                                        <layout:speciesReferenceGlyph layout:id="s2" layout:speciesReference="ref2"/>
+                                        <layout:speciesReferenceGlyph layout:id="s3" layout:speciesReference="ref3"/>
+                                    </layout:listOfSpeciesReferenceGlyphs>
+                                </layout:reactionGlyph>
+                                <layout:reactionGlyph layout:id="g2">
+                                    <layout:listOfSpeciesReferenceGlyphs>
+                                        <layout:speciesReferenceGlyph layout:id="s4" layout:speciesReference="ref2"/>
+                                        <layout:speciesReferenceGlyph layout:id="s5" layout:speciesReference="ref4"/>
+                                    </layout:listOfSpeciesReferenceGlyphs>
+                                </layout:reactionGlyph>
+                            </layout:listOfReactionGlyphs>
+                        </layout:layout>
+                    </layout:listOfLayouts>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        // Without a reaction, the glyph `g2` only requires existing species references.
+        let issues = doc.validate();
+        assert_eq!(issues.len(), 3);
+        assert!(issues
+            .iter()
+            .all(|it| it.rule == "lint-layout-species-reference"));
+        assert!(issues[0].message.contains("('ref2') of"));
+        assert!(issues[0].message.contains("('r1')"));
+        assert!(issues[1].message.contains("('ref3')"));
+        assert!(issues[2].message.contains("('ref4')"));
+        assert!(issues[2].message.contains("existing species reference"));
+    }
+
+    #[test]
+    pub fn test_package_sbo_term() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core"
+                  xmlns:layout="http://www.sbml.org/sbml/level3/version1/layout/version1"
+                  level="3" version="2" layout:required="false">
+                <model id="model">
+                    <layout:listOfLayouts>
+                        <layout:layout layout:id="l1">
+                            <layout:listOfSpeciesGlyphs>
+                                <layout:speciesGlyph layout:id="g1" sboTerm="SBO:12"/>
+                                <layout:speciesGlyph layout:id="g2" sboTerm="SBO:0000247"/>
+                            </layout:listOfSpeciesGlyphs>
+                        </layout:layout>
+                    </layout:listOfLayouts>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        let issues = doc.validate();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule, "10308");
+        assert!(issues[0].message.contains("SBO:12"));
+    }
+
+    #[test]
+    pub fn test_namespace_prefixes() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core"
+                  xmlns:lay="http://www.sbml.org/sbml/level3/version1/layout/version1"
+                  xmlns:core="http://www.sbml.org/sbml/level3/version2/core"
+                  level="3" version="2" lay:required="false">
+                <model id="model"/>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            doc.url_for_prefix("lay"),
+            Some(URL_PACKAGE_LAYOUT.to_string())
+        );
+        assert_eq!(doc.url_for_prefix(""), Some(URL_SBML_CORE.to_string()));
+        assert_eq!(doc.url_for_prefix("fbc"), None);
+        assert_eq!(
+            doc.prefix_for_url(URL_PACKAGE_LAYOUT),
+            Some("lay".to_string())
+        );
+        assert_eq!(doc.prefix_for_url(URL_SBML_CORE), Some("".to_string()));
+        assert_eq!(doc.prefix_for_url(URL_PACKAGE_FBC), None);
+    }
+
+    #[test]
+    pub fn test_auto_fix_math_namespace() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model id="model">
+                    <listOfParameters>
+                        <parameter id="p" constant="true"/>
+                    </listOfParameters>
+                    <listOfInitialAssignments>
+                        <initialAssignment symbol="p">
+                            <math><apply><plus/><cn> 1 </cn><cn> 2 </cn></apply></math>
+                        </initialAssignment>
+                    </listOfInitialAssignments>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        let issues = doc.validate();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule, "10201");
+
+        assert_eq!(doc.auto_fix_math_namespace(), 1);
+        assert_eq!(doc.auto_fix_math_namespace(), 0);
+        assert!(doc.validate().is_empty());
+        assert!(doc
+            .to_xml_string()
+            .unwrap()
+            .contains(r#"<math xmlns="http://www.w3.org/1998/Math/MathML">"#));
+    }
+
+    #[test]
+    pub fn test_empty_reaction() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model id="model">
+                    <listOfReactions>
+                        <reaction id="r1" reversible="false">
+                            <listOfReactants/>
+                        </reaction>
+                    </listOfReactions>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        let issues = doc.validate();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule, "lint-empty-reaction");
+        assert_eq!(issues[0].severity, SbmlIssueSeverity::Warning);
+        assert!(issues[0].message.contains("'r1'"));
+    }
+
+    #[test]
+    pub fn test_table_csv_export() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model id="model">
+                    <listOfSpecies>
+                        <species id="s1" name="ATP, &quot;free&quot;" compartment="c"
+                                 initialConcentration="1.5" substanceUnits="mole"
+                                 hasOnlySubstanceUnits="false" boundaryCondition="false"
+                                 constant="false"/>
+                    </listOfSpecies>
+                    <listOfParameters>
+                        <parameter id="k" value="0.1" constant="true"/>
+                    </listOfParameters>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        let model = doc.model().get().unwrap();
+        assert_eq!(
+            model.species_table_csv(),
+            "id,name,compartment,initial_value,units,constant\n\
+            s1,\"ATP, \"\"free\"\"\",c,1.5,mole,false\n"
+        );
+        assert_eq!(
+            model.parameter_table_csv(),
+            "id,name,compartment,initial_value,units,constant\nk,,,0.1,,true\n"
+        );
+    }
+
+    #[test]
+    pub fn test_lambda_bvars() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model id="model">
+                    <listOfFunctionDefinitions>
+                        <functionDefinition id="f">
+                            <math xmlns="http://www.w3.org/1998/Math/MathML">
+                                <lambda>
+                                    <bvar><ci> x </ci></bvar>
+                                    <bvar><ci> x </ci></bvar>
+                                    <bvar><ci> y </ci></bvar>
+                                    <apply><times/><ci> x </ci><cn> 2 </cn></apply>
+                                </lambda>
+                            </math>
+                        </functionDefinition>
+                    </listOfFunctionDefinitions>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        let issues = doc.validate();
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].rule, "lint-duplicate-bvar");
+        assert_eq!(issues[0].severity, SbmlIssueSeverity::Error);
+        assert!(issues[0].message.contains("'x'"));
+        assert_eq!(issues[1].rule, "lint-unused-bvar");
+        assert_eq!(issues[1].severity, SbmlIssueSeverity::Info);
+        assert!(issues[1].message.contains("'y'"));
+    }
+
+    #[test]
+    pub fn test_try_set_id() {
+        let doc = Sbml::default();
+        let model = Model::default(doc.xml.clone());
+        assert!(model.try_set_id("model_1").is_ok());
+        assert_eq!(model.id().get(), Some("model_1".to_string()));
+        assert!(model.try_set_id("1model").is_err());
+        assert!(model.try_set_id("model-1").is_err());
+        assert_eq!(model.id().get(), Some("model_1".to_string()));
+
+        assert!(model.try_set_meta_id("meta.1").is_ok());
+        assert!(model.try_set_meta_id("1meta").is_err());
+        assert_eq!(model.meta_id().get(), Some("meta.1".to_string()));
+    }
+
+    #[test]
+    pub fn test_remove_from_parent() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model>
+                    <listOfParameters>
+                        <parameter id="p1" value="1" constant="true"/>
+                        <parameter id="p2" value="2" constant="true"/>
+                    </listOfParameters>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+        let model = doc.model().get().unwrap();
+        let parameters = model.parameters().get().unwrap();
+        let count = parameters.len();
+        let removed = parameters.get(0);
+        let id = removed.id().get();
+
+        assert!(removed.remove_from_parent());
+        assert!(removed.is_detached());
+        assert_eq!(parameters.len(), count - 1);
+        assert!(model.find_parameter(&id).is_none());
+        // The detached element is still readable and cannot be removed again.
+        assert_eq!(removed.id().get(), id);
+        assert!(!removed.remove_from_parent());
+
+        // The removed element can be re-attached as usual.
+        parameters.push(removed.clone());
+        assert_eq!(parameters.len(), count);
+        assert!(model.find_parameter(&id).is_some());
+    }
+
+    #[test]
+    pub fn test_conflicting_event_assignments() {
+        let event = |id: &str, variable: &str, priority: bool| {
+            let priority = if priority {
+                r#"<priority><math xmlns="http://www.w3.org/1998/Math/MathML"><cn>1</cn></math></priority>"#
+            } else {
+                ""
+            };
+            format!(
+                r#"<event id="{id}" useValuesFromTriggerTime="true">
+                    <trigger initialValue="true" persistent="true">
+                        <math xmlns="http://www.w3.org/1998/Math/MathML"><true/></math>
+                    </trigger>
+                    {priority}
+                    <listOfEventAssignments>
+                        <eventAssignment variable="{variable}">
+                            <math xmlns="http://www.w3.org/1998/Math/MathML"><cn>0</cn></math>
+                        </eventAssignment>
+                    </listOfEventAssignments>
+                </event>"#
+            )
+        };
+        let document = |events: &[String]| {
+            format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+                <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                    <model>
+                        <listOfParameters>
+                            <parameter id="x" constant="false"/>
+                            <parameter id="y" constant="false"/>
+                        </listOfParameters>
+                        <listOfEvents>{}</listOfEvents>
+                    </model>
+                </sbml>"#,
+                events.join("")
+            )
+        };
+
+        let doc = Sbml::read_str(&document(&[
+            event("e1", "x", false),
+            event("e2", "x", true),
+            event("e3", "y", false),
+        ]))
+        .unwrap();
+        let issues = doc.validate();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule, "lint-event-conflicting-assignments");
+        assert!(issues[0].message.contains("'e1', 'e2'"));
+
+        let doc =
+            Sbml::read_str(&document(&[event("e1", "x", true), event("e2", "x", true)])).unwrap();
+        assert!(doc.validate().is_empty());
+    }
+
+    #[test]
+    pub fn test_event_variables() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model>
+                    <listOfParameters>
+                        <parameter id="x" constant="false"/>
+                        <parameter id="y" constant="false"/>
+                    </listOfParameters>
+                    <listOfEvents>
+                        <event id="e1" useValuesFromTriggerTime="true">
+                            <trigger initialValue="true" persistent="true">
+                                <math xmlns="http://www.w3.org/1998/Math/MathML">
+                                    <apply><gt/>
+                                        <apply><plus/><ci> x </ci><ci>y</ci></apply>
+                                        <ci>x</ci>
+                                    </apply>
+                                </math>
+                            </trigger>
+                            <listOfEventAssignments>
+                                <eventAssignment variable="y">
+                                    <math xmlns="http://www.w3.org/1998/Math/MathML">
+                                        <cn>0</cn>
+                                    </math>
+                                </eventAssignment>
+                            </listOfEventAssignments>
+                        </event>
+                        <event id="e2" useValuesFromTriggerTime="true"/>
+                    </listOfEvents>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+        let model = doc.model().get().unwrap();
+        let events = model.events().get().unwrap();
+
+        let e1 = events.get(0);
+        assert_eq!(
+            e1.trigger_variables(),
+            vec!["x".to_string(), "y".to_string()]
+        );
+        assert_eq!(e1.assigned_variables(), vec!["y".to_string()]);
+        assert!(events.get(1).trigger_variables().is_empty());
+
+        let affecting = model.events_affecting("y");
+        assert_eq!(affecting.len(), 1);
+        assert_eq!(affecting[0].id().get(), Some("e1".to_string()));
+        assert!(model.events_affecting("x").is_empty());
+    }
+
+    #[test]
+    pub fn test_model_maps() {
+        let doc = Sbml::read_path("test-inputs/Mukandavire2020.xml").unwrap();
+        let model = doc.model().get().unwrap();
+
+        let parameters = model.parameters_map();
+        assert_eq!(parameters.len(), model.parameter_identifiers().len());
+        for id in model.parameter_identifiers() {
+            assert_eq!(parameters.get(id.as_str()).unwrap().id().get(), id);
+        }
+        let species = model.species_map();
+        assert_eq!(species.len(), model.species_identifiers().len());
+        let compartments = model.compartments_map();
+        assert_eq!(compartments.len(), model.compartment_identifiers().len());
+        assert!(!species.is_empty() && !compartments.is_empty());
+        assert!(!species.contains_key("not_a_species"));
+    }
+
+    #[test]
+    pub fn test_model_visitor() {
+        #[derive(Default)]
+        struct Counter {
+            species: Vec<String>,
+            reactions: usize,
+            references: usize,
+            last_reaction: Option<String>,
+            misplaced: bool,
+        }
+
+        impl SbmlVisitor for Counter {
+            fn visit_species(&mut self, species: &Species) {
+                self.species.push(species.id().get());
+            }
+            fn visit_reaction(&mut self, reaction: &Reaction) {
+                self.reactions += 1;
+                self.last_reaction = Some(reaction.id().get());
+            }
+            fn visit_species_reference(&mut self, species_reference: &SpeciesReference) {
+                self.references += 1;
+                // The parent reaction must have been visited right before its references.
+                let reaction = species_reference.parent().and_then(|it| it.parent());
+                let reaction = reaction.and_then(|it| it.get_attribute("id"));
+                self.misplaced |= reaction != self.last_reaction;
+            }
+        }
+
+        let doc = Sbml::read_path("test-inputs/Mukandavire2020.xml").unwrap();
+        let model = doc.model().get().unwrap();
+        let mut counter = Counter::default();
+        model.accept(&mut counter);
+
+        assert_eq!(counter.species, model.species_identifiers());
+        let reactions = model.reactions().get().unwrap();
+        assert_eq!(counter.reactions, reactions.len());
+        let references = reactions
+            .iter()
+            .map(|it| {
+                it.reactants().get().map(|l| l.len()).unwrap_or(0)
+                    + it.products().get().map(|l| l.len()).unwrap_or(0)
+            })
+            .sum::<usize>();
+        assert!(references > 0);
+        assert_eq!(counter.references, references);
+        assert!(!counter.misplaced);
+
+        // A model without any lists only visits the model itself.
+        let mut counter = Counter::default();
+        Sbml::default().model().get_or_create().accept(&mut counter);
+        assert!(counter.species.is_empty());
+        assert_eq!(counter.reactions, 0);
+    }
+
+    #[test]
+    pub fn test_compartment_containment() {
+        let model = |compartments: &str| {
+            let doc = Sbml::read_str(
+                format!(
+                    r#"<?xml version="1.0" encoding="UTF-8"?>
+                    <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                        <model><listOfCompartments>{compartments}</listOfCompartments></model>
+                    </sbml>"#
+                )
+                .as_str(),
+            )
+            .unwrap();
+            doc.model().get().unwrap()
+        };
+        // The parent compartment is stored in the name of the compartment.
+        let resolver = |it: &Compartment| it.name().get().and_then(|it| SId::try_from(it).ok());
+
+        let cell = model(
+            r#"<compartment id="cell" constant="true"/>
+            <compartment id="cytosol" name="cell" constant="true"/>
+            <compartment id="nucleus" name="cytosol" constant="true"/>"#,
+        );
+        let containment = cell.compartment_containment(resolver).unwrap();
+        assert_eq!(containment.len(), 2);
+        assert_eq!(containment["nucleus"].as_str(), "cytosol");
+        assert_eq!(containment["cytosol"].as_str(), "cell");
+        assert!(!containment.contains_key("cell"));
+
+        let cycle = model(
+            r#"<compartment id="a" name="b" constant="true"/>
+            <compartment id="b" name="a" constant="true"/>"#,
+        );
+        let error = cycle.compartment_containment(resolver).unwrap_err();
+        assert!(error.contains("a -> b -> a"));
+
+        let unknown = model(r#"<compartment id="a" name="outside" constant="true"/>"#);
+        assert!(unknown.compartment_containment(resolver).is_err());
+    }
+
+    #[test]
+    pub fn test_identifier_types() {
+        let ids: HashSet<SId> = ["sp1", "sp2"]
+            .into_iter()
+            .map(|it| SId::try_from(it).unwrap())
+            .collect();
+        assert!(ids.contains("sp1"));
+        assert!(!ids.contains("sp3"));
+        assert!(SId::try_from("1sp").is_err());
+
+        let meta_ids: HashMap<MetaId, usize> =
+            HashMap::from([(MetaId::try_from("meta.1".to_string()).unwrap(), 1)]);
+        assert_eq!(meta_ids.get("meta.1"), Some(&1));
+        assert!(MetaId::try_from("1meta").is_err());
+
+        let id = SId::try_from("sp1").unwrap();
+        assert_eq!(id.to_string(), "sp1");
+        assert_eq!(id.as_ref(), "sp1");
+        assert_eq!(String::from(id), "sp1".to_string());
+    }
+
+    #[test]
+    pub fn test_sbo_term() {
+        let term = SboTerm::try_from("SBO:0000014").unwrap();
+        assert_eq!(term.number(), 14);
+        assert!(SboTerm::try_from("SBO:14").is_err());
+        assert!(SboTerm::try_from("sbo:0000014").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "sbo_terms")]
+    pub fn test_sbo_term_labels() {
+        let term = SboTerm::try_from("SBO:0000014").unwrap();
+        assert_eq!(term.label(), Some("enzymatic catalyst"));
+        assert!(term.definition().is_some());
+
+        let unknown = SboTerm::try_from("SBO:9999999").unwrap();
+        assert_eq!(unknown.label(), None);
+        assert_eq!(unknown.definition(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "zip")]
+    pub fn test_combine_archive() {
+        use crate::combine::CombineArchive;
+        use std::io::Write;
+        use zip::write::SimpleFileOptions;
+
+        let archive = |files: &[(&str, &[u8])]| {
+            let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+            for (name, content) in files {
+                writer
+                    .start_file(*name, SimpleFileOptions::default())
+                    .unwrap();
+                writer.write_all(content).unwrap();
+            }
+            let bytes = writer.finish().unwrap().into_inner();
+            CombineArchive::read_bytes(bytes).unwrap()
+        };
+
+        let manifest = br#"<?xml version="1.0" encoding="UTF-8"?>
+            <omexManifest xmlns="http://identifiers.org/combine.specifications/omex-manifest">
+                <content location="." format="http://identifiers.org/combine.specifications/omex"/>
+                <content location="./old.xml" format="http://identifiers.org/combine.specifications/sbml"/>
+                <content location="./model.xml" format="http://identifiers.org/combine.specifications/sbml.level-3.version-2" master="true"/>
+            </omexManifest>"#;
+        let model = std::fs::read("test-inputs/model.sbml").unwrap();
+        let combine = archive(&[
+            ("manifest.xml", manifest),
+            ("old.xml", b"<sbml/>"),
+            ("model.xml", &model),
+        ]);
+
+        assert_eq!(combine.master_sbml_location().unwrap(), "./model.xml");
+        let doc = combine.master_sbml().unwrap();
+        let expected = Sbml::read_path("test-inputs/model.sbml").unwrap();
+        assert_eq!(
+            doc.model().get().unwrap().id().get(),
+            expected.model().get().unwrap().id().get()
+        );
+
+        assert!(archive(&[("model.xml", &model)]).master_sbml().is_err());
+        let missing = archive(&[("manifest.xml", manifest)]);
+        assert!(missing.master_sbml().unwrap_err().contains("./model.xml"));
+    }
+
+    #[test]
+    pub fn test_property_type_roundtrip() {
+        assert_property_roundtrip("text & <markup>".to_string());
+        assert_property_roundtrip(true);
+        assert_property_roundtrip(false);
+        assert_property_roundtrip(-42i32);
+        assert_property_roundtrip(42u32);
+        assert_property_roundtrip(0.1f64);
+        assert_property_roundtrip(-1.5e-300f64);
+        assert_property_roundtrip(SId::try_from("sp_1").unwrap());
+        assert_property_roundtrip(MetaId::try_from("meta.1").unwrap());
+        assert_property_roundtrip(SboTerm::try_from("SBO:0000014").unwrap());
+        for unit in BaseUnit::iter() {
+            assert_property_roundtrip(unit);
+        }
+
+        assert!(SId::try_get(Some("1sp")).is_err());
+        assert_eq!(SboTerm::try_get(None), Ok(None));
+    }
+
+    #[test]
+    pub fn test_kinetic_law_units() {
+        let kinetic_law = |model_units: &str, math: &str| {
+            let doc = Sbml::read_str(
+                format!(
+                    r#"<?xml version="1.0" encoding="UTF-8"?>
+                    <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                        <model {model_units} substanceUnits="mole" volumeUnits="litre">
+                            <listOfUnitDefinitions>
+                                <unitDefinition id="per_second">
+                                    <listOfUnits>
+                                        <unit kind="second" exponent="-1" scale="0" multiplier="1"/>
+                                    </listOfUnits>
+                                </unitDefinition>
+                            </listOfUnitDefinitions>
+                            <listOfCompartments>
+                                <compartment id="c" spatialDimensions="3" size="1" constant="true"/>
+                            </listOfCompartments>
+                            <listOfSpecies>
+                                <species id="s" compartment="c" initialConcentration="1" hasOnlySubstanceUnits="false" boundaryCondition="false" constant="false"/>
+                            </listOfSpecies>
+                            <listOfReactions>
+                                <reaction id="r" reversible="false">
+                                    <listOfReactants>
+                                        <speciesReference species="s" stoichiometry="1" constant="true"/>
+                                    </listOfReactants>
+                                    <kineticLaw>
+                                        <math xmlns="http://www.w3.org/1998/Math/MathML">{math}</math>
+                                        <listOfLocalParameters>
+                                            <localParameter id="k" value="0.1" units="per_second"/>
+                                        </listOfLocalParameters>
+                                    </kineticLaw>
+                                </reaction>
+                            </listOfReactions>
+                        </model>
+                    </sbml>"#
+                )
+                .as_str(),
+            )
+            .unwrap();
+            doc.validate()
+                .into_iter()
+                .filter(|it| it.rule == "10541")
+                .map(|it| it.message)
+                .collect::<Vec<_>>()
+        };
+
+        let units = r#"extentUnits="mole" timeUnits="second""#;
+        let amount = "<apply><times/><ci> k </ci><ci> s </ci><ci> c </ci></apply>";
+        let concentration = "<apply><times/><ci> k </ci><ci> s </ci></apply>";
+        assert!(kinetic_law(units, amount).is_empty());
+
+        let issues = kinetic_law(units, concentration);
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].contains("reaction `r`"));
+        assert!(issues[0].contains("`second^-1 * mole`"));
+        assert!(issues[0].contains("`metre^-3 * second^-1 * mole`"));
+
+        // Undeclared model units or numbers without units skip the check.
+        assert!(kinetic_law("", concentration).is_empty());
+        let number = "<apply><times/><cn> 2 </cn><ci> k </ci><ci> s </ci></apply>";
+        assert!(kinetic_law(units, number).is_empty());
+    }
+
+    #[test]
+    pub fn test_suspicious_base_units() {
+        let units = |units: &str| {
+            let doc = Sbml::read_str(
+                format!(
+                    r#"<?xml version="1.0" encoding="UTF-8"?>
+                    <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                        <model>
+                            <listOfUnitDefinitions>
+                                <unitDefinition id="u">
+                                    <listOfUnits>{units}</listOfUnits>
+                                </unitDefinition>
+                            </listOfUnitDefinitions>
+                        </model>
+                    </sbml>"#
+                )
+                .as_str(),
+            )
+            .unwrap();
+            doc.validate()
+                .into_iter()
+                .map(|it| (it.rule, it.severity))
+                .collect::<Vec<_>>()
+        };
+
+        let warning = vec![("lint-base-unit".to_string(), SbmlIssueSeverity::Warning)];
+        assert_eq!(
+            units(r#"<unit kind="dimensionless" exponent="2" scale="0" multiplier="1"/>"#),
+            warning
+        );
+        assert_eq!(
+            units(r#"<unit kind="item" exponent="2" scale="0" multiplier="1"/>"#),
+            warning
+        );
+        assert!(
+            units(r#"<unit kind="dimensionless" exponent="1" scale="0" multiplier="1"/>"#)
+                .is_empty()
+        );
+        // Percent is a common (and valid) use of a scaled dimensionless unit.
+        assert!(
+            units(r#"<unit kind="dimensionless" exponent="1" scale="-2" multiplier="1"/>"#)
+                .is_empty()
+        );
+        assert!(
+            units(r#"<unit kind="avogadro" exponent="-1" scale="0" multiplier="1"/>"#).is_empty()
+        );
+        assert!(units(r#"<unit kind="mole" exponent="2" scale="-3" multiplier="1"/>"#).is_empty());
+    }
+
+    #[test]
+    pub fn test_local_parameter_scope() {
+        let reaction = |id: &str, parameters: &[&str]| {
+            let parameters = parameters
+                .iter()
+                .map(|it| format!(r#"<localParameter id="{it}" value="1"/>"#))
+                .collect::<String>();
+            format!(
+                r#"<reaction id="{id}" reversible="false">
+                    <kineticLaw>
+                        <math xmlns="http://www.w3.org/1998/Math/MathML"><ci> k1 </ci></math>
+                        <listOfLocalParameters>{parameters}</listOfLocalParameters>
+                    </kineticLaw>
+                </reaction>"#
+            )
+        };
+        let doc = Sbml::read_str(
+            format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+                <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                    <model>
+                        <listOfReactions>{}{}</listOfReactions>
+                    </model>
+                </sbml>"#,
+                reaction("r1", &["k1", "k2", "k1"]),
+                reaction("r2", &["k1", "k2"]),
+            )
+            .as_str(),
+        )
+        .unwrap();
+
+        // Only the duplicate within `r1` is reported, reusing ids in `r2` is fine.
+        let issues = doc
+            .validate()
+            .into_iter()
+            .filter(|it| it.severity == SbmlIssueSeverity::Error)
+            .collect::<Vec<_>>();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule, "10303");
+        assert!(issues[0].message.contains("'k1'"));
+        let parameter = XmlElement::new_raw(doc.xml.clone(), issues[0].element);
+        let kinetic_law = KineticLaw::for_child_element(&parameter).unwrap();
+        assert_eq!(
+            kinetic_law.parent().unwrap().get_attribute("id").unwrap(),
+            "r1"
+        );
+    }
+
+    #[test]
+    pub fn test_validate_each() {
+        let doc = Sbml::read_path("test-inputs/Mukandavire2020.xml").unwrap();
+        let mut issues = Vec::new();
+        doc.validate_each(|issue| {
+            issues.push(issue);
+            ControlFlow::Continue(())
+        });
+        assert_eq!(issues, doc.validate());
+
+        // The duplicate `c` is found long before the non-finite value, so the sink can stop
+        // the validation as soon as the first error arrives.
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model>
+                    <listOfCompartments>
+                        <compartment id="c" constant="true"/>
+                    </listOfCompartments>
+                    <listOfParameters>
+                        <parameter id="c" value="NaN" constant="true"/>
+                    </listOfParameters>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+        let all = doc.validate();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[1].rule, "lint-non-finite-value");
+
+        let mut issues = Vec::new();
+        doc.validate_each(|issue| {
+            let stop = issue.severity == SbmlIssueSeverity::Error;
+            issues.push(issue);
+            if stop {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+        assert_eq!(issues, all[..1]);
+
+        // A document which fails the type check only reports the type check issues.
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model><listOfSpecies><species id="s"/></listOfSpecies></model>
+            </sbml>"#,
+        )
+        .unwrap();
+        let mut count = 0;
+        doc.validate_each(|_| {
+            count += 1;
+            ControlFlow::Continue(())
+        });
+        assert!(count > 0);
+        assert_eq!(count, doc.schema_report().len());
+    }
+
+    #[test]
+    pub fn test_model_substance_dimensions() {
+        let model_units = |substance: &str, extent: &str| {
+            let doc = Sbml::read_str(
+                format!(
+                    r#"<?xml version="1.0" encoding="UTF-8"?>
+                    <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                        <model substanceUnits="{substance}" extentUnits="{extent}">
+                            <listOfUnitDefinitions>
+                                <unitDefinition id="mmol">
+                                    <listOfUnits>
+                                        <unit kind="mole" exponent="1" scale="-3" multiplier="1"/>
+                                    </listOfUnits>
+                                </unitDefinition>
+                                <unitDefinition id="um">
+                                    <listOfUnits>
+                                        <unit kind="metre" exponent="1" scale="-6" multiplier="1"/>
+                                    </listOfUnits>
+                                </unitDefinition>
+                            </listOfUnitDefinitions>
+                        </model>
+                    </sbml>"#
+                )
+                .as_str(),
+            )
+            .unwrap();
+            doc.validate()
+                .into_iter()
+                .filter(|it| it.rule == "lint-substance-units")
+                .collect::<Vec<_>>()
+        };
+
+        assert!(model_units("mmol", "mole").is_empty());
+        assert!(model_units("item", "gram").is_empty());
+
+        let issues = model_units("um", "mmol");
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, SbmlIssueSeverity::Warning);
+        assert!(issues[0].message.contains("[substanceUnits]"));
+        assert!(issues[0].message.contains("`metre`"));
+
+        let issues = model_units("mole", "second");
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].message.contains("[extentUnits]"));
+        assert!(issues[0].message.contains("`second`"));
+    }
+
+    #[test]
+    pub fn test_schema_report() {
+        let doc = |parameters: &str| {
+            Sbml::read_str(
+                format!(
+                    r#"<?xml version="1.0" encoding="UTF-8"?>
+                    <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                        <model><listOfParameters>{parameters}</listOfParameters></model>
+                    </sbml>"#
+                )
+                .as_str(),
+            )
+            .unwrap()
+        };
+
+        // A missing attribute, a value of the wrong type, and an unknown child element.
+        let invalid = doc(r#"<parameter constant="true"/>
+            <parameter id="p" constant="maybe"/>
+            <parameter id="q" constant="true"><value>1</value></parameter>"#);
+        let issues = invalid.schema_report();
+        assert_eq!(issues.len(), 3);
+        assert!(issues
+            .iter()
+            .all(|it| it.severity == SbmlIssueSeverity::Error));
+        assert_eq!(issues, invalid.validate());
+
+        // Duplicate identifiers are only found by the semantic validation.
+        let duplicate = doc(r#"<parameter id="p" constant="true"/>
+            <parameter id="p" constant="true"/>"#);
+        assert!(duplicate.schema_report().is_empty());
+        assert_eq!(duplicate.validate().len(), 1);
+    }
+
+    #[test]
+    pub fn test_kinetic_law_or_create() {
+        let doc = Sbml::default();
+        let model = doc.model().get_or_create();
+        let reaction = Reaction::new(doc.xml.clone(), &"r1".to_string(), false);
+        model.reactions().get_or_create().push(reaction);
+        let reaction = model.reactions().get().unwrap().top();
+
+        assert!(reaction.kinetic_law().get().is_none());
+        let kinetic_law = reaction.kinetic_law_or_create();
+        assert!(reaction.kinetic_law().get().is_some());
+        assert!(kinetic_law.local_parameters().get().is_none());
+
+        let k1 = kinetic_law.add_local_parameter("k1");
+        let k2 = reaction.kinetic_law_or_create().add_local_parameter("k2");
+        assert_eq!(k1.id().get(), "k1");
+        assert_eq!(k2.id().get(), "k2");
+        assert_eq!(kinetic_law.local_parameters_or_create().len(), 2);
+        assert_eq!(kinetic_law.local_parameter_identifiers(), vec!["k1", "k2"]);
+        assert!(doc.schema_report().is_empty());
+    }
+
+    #[test]
+    pub fn test_unknown_package_children() {
+        let doc = |model_content: &str| {
+            Sbml::read_str(
+                format!(
+                    r#"<?xml version="1.0" encoding="UTF-8"?>
+                    <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2"
+                          xmlns:layout="http://www.sbml.org/sbml/level3/version1/layout/version1"
+                          xmlns:other="http://example.org/other" layout:required="false">
+                        <model>
+                            {model_content}
+                            <listOfSpecies>
+                                <species id="s" compartment="c" hasOnlySubstanceUnits="false"
+                                         boundaryCondition="false" constant="false">
+                                    <layout:boundingBox/>
+                                </species>
+                            </listOfSpecies>
+                            <listOfCompartments><compartment id="c" constant="true"/></listOfCompartments>
+                        </model>
+                    </sbml>"#
+                )
+                .as_str(),
+            )
+            .unwrap()
+            .schema_report()
+        };
+
+        // The bounding box is not permitted directly in a species.
+        let issues = doc("<layout:listOfLayouts/>");
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule, "10102");
+        assert!(issues[0].message.contains("<layout:boundingBox>"));
+
+        // A typo in the layout namespace is reported, unknown namespaces are still skipped.
+        let issues = doc("<layout:listOfLayout/><other:listOfLayout/>");
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].rule, "layout-20101");
+        assert!(issues[0].message.contains("<layout:listOfLayout>"));
+        assert_eq!(issues[1].rule, "10102");
+    }
+
+    #[test]
+    pub fn test_ensure_meta_id() {
+        let generate = || {
+            let doc = Sbml::read_str(
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+                <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                    <model>
+                        <listOfSpecies>
+                            <species id="s1"/><species id="s2"/><species/><species/>
+                        </listOfSpecies>
+                    </model>
+                </sbml>"#,
+            )
+            .unwrap();
+            let model = doc.model().get().unwrap();
+            let species = model.species().get().unwrap();
+            let ids = species
+                .iter()
+                .map(|it| it.ensure_meta_id_deterministic())
+                .collect::<Vec<_>>();
+            (doc, ids)
+        };
+
+        // Regenerating the meta ids on the same document gives the same result.
+        let (doc, first) = generate();
+        let (_, second) = generate();
+        assert_eq!(first, second);
+        assert_eq!(first.iter().collect::<HashSet<_>>().len(), first.len());
+        assert!(first.iter().all(|it| MetaId::try_from(it.as_str()).is_ok()));
+
+        // Existing meta ids are kept, new random ones do not collide with them.
+        let model = doc.model().get().unwrap();
+        let species = model.species().get().unwrap().get(0);
+        assert_eq!(species.ensure_meta_id(), first[0]);
+        let meta_id = model.ensure_meta_id();
+        assert!(MetaId::try_from(meta_id.as_str()).is_ok());
+        assert!(!first.contains(&meta_id));
+        assert_eq!(model.meta_id().get(), Some(meta_id));
+    }
+
+    #[test]
+    pub fn test_shadowed_local_parameters() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model>
+                    <listOfParameters>
+                        <parameter id="k1" value="1" constant="true"/>
+                        <parameter id="k2" value="2" constant="true"/>
+                    </listOfParameters>
+                    <listOfReactions>
+                        <reaction id="r1" reversible="false">
+                            <kineticLaw>
+                                <math xmlns="http://www.w3.org/1998/Math/MathML">
+                                    <apply><times/><ci>k1</ci><ci>k2</ci></apply>
+                                </math>
+                                <listOfLocalParameters>
+                                    <localParameter id="k1" value="1"/>
+                                    <localParameter id="k2" value="5"/>
+                                    <localParameter id="k3" value="3"/>
+                                </listOfLocalParameters>
+                            </kineticLaw>
+                        </reaction>
+                    </listOfReactions>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        // Only `k2` shadows a global parameter with a different value.
+        let issues = doc
+            .validate()
+            .into_iter()
+            .filter(|it| it.rule == "lint-shadowed-local-parameter")
+            .collect::<Vec<_>>();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, SbmlIssueSeverity::Info);
+        assert!(issues[0].message.contains("'k2'"));
+        let element = XmlElement::new_raw(doc.xml.clone(), issues[0].element);
+        assert_eq!(element.tag_name(), "localParameter");
+    }
+
+    #[test]
+    pub fn test_canonical_form() {
+        let first = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model id="m">
+                    <!-- compartments -->
+                    <listOfCompartments><compartment id="c" constant="true"/></listOfCompartments>
+                    <listOfSpecies>
+                        <species id="a" compartment="c"/>
+                        <species id="b" compartment="c"/>
+                    </listOfSpecies>
+                    <listOfRules>
+                        <assignmentRule variable="a"><math xmlns="http://www.w3.org/1998/Math/MathML"><ci> b </ci></math></assignmentRule>
+                        <assignmentRule variable="b"><math xmlns="http://www.w3.org/1998/Math/MathML"><cn>1</cn></math></assignmentRule>
+                    </listOfRules>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+        let second = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <core:sbml xmlns:core="http://www.sbml.org/sbml/level3/version2/core" version="2" level="3">
+              <core:model id="m">
+                <core:listOfCompartments><core:compartment constant="true" id="c"/></core:listOfCompartments>
+                <core:listOfSpecies>
+                  <core:species compartment="c" id="b"/>
+                  <core:species compartment="c" id="a"/>
+                </core:listOfSpecies>
+                <core:listOfRules>
+                  <core:assignmentRule variable="a"><math xmlns="http://www.w3.org/1998/Math/MathML"><ci>b</ci></math></core:assignmentRule>
+                  <core:assignmentRule variable="b"><math xmlns="http://www.w3.org/1998/Math/MathML"><cn>1</cn></math></core:assignmentRule>
+                </core:listOfRules>
+              </core:model>
+            </core:sbml>"#,
+        )
+        .unwrap();
+        assert_eq!(
+            first.canonical_form().unwrap(),
+            second.canonical_form().unwrap()
+        );
+    }
+
+    #[test]
+    pub fn test_canonical_form_ordered_lists() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2"
+                  xmlns:layout="http://www.sbml.org/sbml/level3/version1/layout/version1"
+                  layout:required="false">
+                <model id="m">
+                    <listOfParameters>
+                        <parameter id="a" constant="false"/>
+                        <parameter id="b" constant="false"/>
+                    </listOfParameters>
+                    <listOfRules>
+                        <assignmentRule variable="a"><math xmlns="http://www.w3.org/1998/Math/MathML"><ci>b</ci></math></assignmentRule>
+                        <assignmentRule variable="b"><math xmlns="http://www.w3.org/1998/Math/MathML"><cn>1</cn></math></assignmentRule>
+                    </listOfRules>
+                    <layout:listOfLayouts>
+                        <layout:layout layout:id="l">
+                            <layout:listOfReactionGlyphs>
+                                <layout:reactionGlyph layout:id="g">
+                                    <layout:curve>
+                                        <layout:listOfCurveSegments>
+                                            <layout:curveSegment layout:id="s1"/>
+                                            <layout:curveSegment layout:id="s2"/>
+                                        </layout:listOfCurveSegments>
+                                    </layout:curve>
+                                </layout:reactionGlyph>
+                            </layout:listOfReactionGlyphs>
+                        </layout:layout>
+                    </layout:listOfLayouts>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+        let original = doc.canonical_form().unwrap();
+        let model = doc.model().get().unwrap();
+
+        // Swapping two parameters does not change the canonical form.
+        model.parameters().get().unwrap().swap(0, 1);
+        assert_eq!(doc.canonical_form().unwrap(), original);
+
+        // Swapping two rules changes it, since the order of rules is significant.
+        let rules = model.rules().get().unwrap();
+        rules.swap(0, 1);
+        assert_ne!(doc.canonical_form().unwrap(), original);
+        rules.swap(0, 1);
+        assert_eq!(doc.canonical_form().unwrap(), original);
+
+        // The same applies to the segments of a layout curve.
+        let segments = model
+            .recursive_child_elements_filtered(|it| it.tag_name() == "listOfCurveSegments")
+            .remove(0);
+        let first = segments.child_elements()[0].clone();
+        first.try_detach().unwrap();
+        first.try_attach_at(&segments, None).unwrap();
+        assert_ne!(doc.canonical_form().unwrap(), original);
+    }
+
+    #[test]
+    pub fn test_canonical_form_deep_math() {
+        // The canonical form must not recurse over the nesting depth of the document.
+        let depth = 5_000;
+        let doc = Sbml::read_str(
+            format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+                <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                    <model>
+                        <listOfRules>
+                            <algebraicRule>
+                                <math xmlns="http://www.w3.org/1998/Math/MathML">{}<cn>1</cn>{}</math>
+                            </algebraicRule>
+                        </listOfRules>
+                    </model>
+                </sbml>"#,
+                "<apply><minus/>".repeat(depth),
+                "</apply>".repeat(depth)
+            )
+            .as_str(),
+        )
+        .unwrap();
+        let form = doc.canonical_form().unwrap();
+        assert_eq!(
+            form.matches("<{http://www.w3.org/1998/Math/MathML}minus/>")
+                .count(),
+            depth
+        );
+    }
+
+    #[test]
+    pub fn test_max_math_depth() {
+        let nested = |depth: usize| {
+            let math = format!(
+                "{}<cn>1</cn>{}",
+                "<apply><minus/>".repeat(depth),
+                "</apply>".repeat(depth)
+            );
+            Sbml::read_str(
+                format!(
+                    r#"<?xml version="1.0" encoding="UTF-8"?>
+                    <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                        <model>
+                            <listOfParameters>
+                                <parameter id="p" constant="true"/>
+                            </listOfParameters>
+                            <listOfInitialAssignments>
+                                <initialAssignment symbol="p">
+                                    <math xmlns="http://www.w3.org/1998/Math/MathML">{math}</math>
+                                </initialAssignment>
+                            </listOfInitialAssignments>
+                        </model>
+                    </sbml>"#
+                )
+                .as_str(),
+            )
+            .unwrap()
+        };
+
+        // The limit is a parameter of the validation, not a property of the document.
+        let doc = nested(5);
+        assert!(doc.validate().is_empty());
+        assert!(doc.validate_with_max_math_depth(6).is_empty());
+        let issues = doc.validate_with_max_math_depth(5);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule, "lint-math-depth");
+        assert_eq!(issues[0].severity, SbmlIssueSeverity::Error);
+        let element = XmlElement::new_raw(doc.xml.clone(), issues[0].element);
+        assert_eq!(element.tag_name(), "math");
+
+        // Documents exceeding the default limit are rejected without validating the math.
+        let doc = nested(Math::DEFAULT_MAX_DEPTH);
+        let issues = doc.validate();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule, "lint-math-depth");
+    }
+
+    #[test]
+    pub fn test_shape_metrics() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model>
+                    <listOfCompartments><compartment id="c" constant="true"/></listOfCompartments>
+                    <listOfParameters/>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+        let root = doc.xml_element();
+        assert_eq!(root.descendant_count(), 4);
+        assert_eq!(root.max_depth(), 3);
+        let parameters = doc.model().get().unwrap().parameters().get().unwrap();
+        assert_eq!(parameters.descendant_count(), 0);
+        assert_eq!(parameters.max_depth(), 0);
+
+        let doc = Sbml::read_path("test-inputs/Mukandavire2020.xml").unwrap();
+        let root = doc.xml_element();
+        assert_eq!(
+            root.descendant_count(),
+            root.recursive_child_elements().len()
+        );
+    }
+
+    #[test]
+    pub fn test_algebraic_rule_variable() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model>
+                    <listOfParameters><parameter id="x" constant="false"/></listOfParameters>
+                    <listOfRules>
+                        <algebraicRule variable="x">
+                            <math xmlns="http://www.w3.org/1998/Math/MathML"><ci>x</ci></math>
+                        </algebraicRule>
+                    </listOfRules>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+        let rule = doc.model().get().unwrap().rules().get().unwrap().get(0);
+        assert!(matches!(rule.cast(), RuleTypes::Algebraic(_)));
+
+        // The `variable` is not permitted on <algebraicRule>, only on assignment and rate rules.
+        let issues = doc.validate();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule, "20910");
+        assert!(issues[0].message.contains("[variable]"));
+        assert_eq!(issues[0].severity, SbmlIssueSeverity::Error);
+    }
+
+    #[test]
+    pub fn test_species_reference_constant() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model>
+                    <listOfCompartments>
+                        <compartment id="c" constant="true"/>
+                    </listOfCompartments>
+                    <listOfSpecies>
+                        <species id="a" compartment="c" hasOnlySubstanceUnits="false" boundaryCondition="false" constant="false"/>
+                    </listOfSpecies>
+                    <listOfReactions>
+                        <reaction id="r" reversible="false">
+                            <listOfReactants>
+                                <speciesReference species="a" stoichiometry="1"/>
+                            </listOfReactants>
+                        </reaction>
+                    </listOfReactions>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        let issues = doc.validate();
+        let missing = issues
+            .iter()
+            .filter(|it| it.rule == "21116")
+            .collect::<Vec<_>>();
+        assert_eq!(missing.len(), 1);
+        assert!(missing[0].message.contains("constant"));
+        assert_eq!(missing[0].severity, SbmlIssueSeverity::Error);
+
+        let model = doc.model().get().unwrap();
+        let reactant = SpeciesReference::new(model.document(), &"a".to_string(), true);
+        assert_eq!(reactant.constant().get_checked(), Ok(Some(true)));
+    }
+
+    #[test]
+    pub fn test_notes_annotation_order() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model>
+                    <annotation><data xmlns="http://example.org/tool"/></annotation>
+                    <notes><p xmlns="http://www.w3.org/1999/xhtml">Model notes.</p></notes>
+                    <listOfParameters>
+                        <parameter id="p" value="1" constant="true"/>
+                    </listOfParameters>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        let issues = doc.validate();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule, "lint-sbase-children-order");
+        assert_eq!(issues[0].severity, SbmlIssueSeverity::Info);
+        assert!(issues[0].message.contains("should come after its <notes>"));
+
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model>
+                    <listOfParameters>
+                        <parameter id="p" value="1" constant="true"/>
+                    </listOfParameters>
+                    <notes><p xmlns="http://www.w3.org/1999/xhtml">Model notes.</p></notes>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+        let issues = doc.validate();
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0]
+            .message
+            .contains("<notes> of <model> should come before"));
+    }
+
+    #[test]
+    pub fn test_duplicate_model_lists() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model>
+                    <listOfCompartments>
+                        <compartment id="c" constant="true"/>
+                    </listOfCompartments>
+                    <listOfSpecies>
+                        <species id="a" compartment="c" hasOnlySubstanceUnits="false" boundaryCondition="false" constant="false"/>
+                    </listOfSpecies>
+                    <listOfSpecies>
+                        <species id="b" compartment="c" hasOnlySubstanceUnits="false" boundaryCondition="false" constant="false"/>
+                    </listOfSpecies>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        let issues = doc.validate();
+        let duplicates = issues
+            .iter()
+            .filter(|it| it.rule == "20205")
+            .collect::<Vec<_>>();
+        assert_eq!(duplicates.len(), 1);
+        assert!(duplicates[0].message.contains("<listOfSpecies>"));
+        assert_eq!(duplicates[0].severity, SbmlIssueSeverity::Error);
+    }
+
+    #[test]
+    pub fn test_list_iter_indexed() {
+        let doc = Sbml::read_path("test-inputs/Mukandavire2020.xml").unwrap();
+        let model = doc.model().get().unwrap();
+        let parameters = model.parameters().get().unwrap();
+        assert!(parameters.len() > 1);
+
+        let indexed = parameters.iter_indexed().collect::<Vec<_>>();
+        assert_eq!(indexed.len(), parameters.len());
+        for (index, parameter) in indexed {
+            assert_eq!(parameter.id().get(), parameters.get(index).id().get());
+        }
+
+        let empty = Sbml::default()
+            .model()
+            .get_or_create()
+            .parameters()
+            .get_or_create();
+        assert_eq!(empty.iter_indexed().count(), 0);
+    }
+
+    #[test]
+    pub fn test_list_duplicate_ids() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model id="model">
+                    <listOfParameters>
+                        <parameter id="k" constant="true"/>
+                        <parameter id="p" constant="true"/>
+                        <parameter id="k" constant="true"/>
+                        <parameter id="k" constant="true"/>
+                    </listOfParameters>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        let parameters = doc.model().get().unwrap().parameters().get().unwrap();
+        assert_eq!(parameters.duplicate_ids(), vec!["k".to_string()]);
+
+        let expected = parameters
+            .iter()
+            .map(|it| it.id().get())
+            .collect::<Vec<_>>();
+        assert_eq!(parameters.collect_ids(), expected);
+    }
+
+    #[test]
+    pub fn test_xml_bytes_roundtrip() {
+        let doc = Sbml::read_path("test-inputs/model.sbml").unwrap();
+        let model = doc.model().get().unwrap();
+        model.name().set(Some(&"Žluťoučký kůň".to_string()));
+
+        let bytes = doc.to_xml_bytes().unwrap();
+        assert_eq!(bytes, doc.to_xml_string().unwrap().into_bytes());
+        assert!(String::from_utf8(bytes.clone()).is_ok());
+
+        let copy = Sbml::read_bytes(&bytes).unwrap();
+        let name = copy.model().get().unwrap().name().get();
+        assert_eq!(name, Some("Žluťoučký kůň".to_string()));
+        assert_eq!(copy.to_xml_bytes().unwrap(), bytes);
+    }
+
+    #[test]
+    pub fn test_read_bytes() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model id="model" name="Žluťoučký kůň"/>
+            </sbml>"#;
+        let doc = Sbml::read_bytes(xml.as_bytes()).unwrap();
+        let model = doc.model().get().unwrap();
+        assert_eq!(model.name().get(), Some("Žluťoučký kůň".to_string()));
+
+        // A lone 0xFF byte is never valid in UTF-8.
+        let mut invalid = xml.as_bytes().to_vec();
+        let position = xml.find("Ž").unwrap();
+        invalid.insert(position, 0xFF);
+        assert_eq!(
+            Sbml::read_bytes(&invalid).err(),
+            Some("SBML documents must use UTF-8 encoding.".to_string())
+        );
+    }
+
+    #[test]
+    pub fn test_event_delay_references_assigned_variable() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model id="model">
+                    <listOfParameters>
+                        <parameter id="x" value="1" constant="false"/>
+                    </listOfParameters>
+                    <listOfEvents>
+                        <event id="e" useValuesFromTriggerTime="true">
+                            <trigger initialValue="false" persistent="true">
+                                <math xmlns="http://www.w3.org/1998/Math/MathML">
+                                    <apply><gt/><csymbol definitionURL="http://www.sbml.org/sbml/symbols/time"> t </csymbol><cn> 1 </cn></apply>
+                                </math>
+                            </trigger>
+                            <delay>
+                                <math xmlns="http://www.w3.org/1998/Math/MathML">
+                                    <apply><times/><ci> x </ci><ci> x </ci></apply>
+                                </math>
+                            </delay>
+                            <listOfEventAssignments>
+                                <eventAssignment variable="x">
+                                    <math xmlns="http://www.w3.org/1998/Math/MathML"><cn> 0 </cn></math>
+                                </eventAssignment>
+                            </listOfEventAssignments>
+                        </event>
+                    </listOfEvents>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        let issues = doc.validate();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule, "lint-event-reads-assigned-variable");
+        assert_eq!(issues[0].severity, SbmlIssueSeverity::Warning);
+        assert!(issues[0].message.contains("'x'"));
+    }
+
+    #[test]
+    pub fn test_fbc_mass_balance() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core"
+                  xmlns:fbc="http://www.sbml.org/sbml/level3/version1/fbc/version2"
+                  level="3" version="2" fbc:required="false">
+                <model id="model">
+                    <listOfCompartments>
+                        <compartment id="c" constant="true"/>
+                    </listOfCompartments>
+                    <listOfSpecies>
+                        <species id="h2" compartment="c" hasOnlySubstanceUnits="false"
+                                 boundaryCondition="false" constant="false" fbc:chemicalFormula="H2"/>
+                        <species id="o2" compartment="c" hasOnlySubstanceUnits="false"
+                                 boundaryCondition="false" constant="false" fbc:chemicalFormula="O2"/>
+                        <species id="h2o" compartment="c" hasOnlySubstanceUnits="false"
+                                 boundaryCondition="false" constant="false" fbc:chemicalFormula="H2O"/>
+                        <species id="x" compartment="c" hasOnlySubstanceUnits="false"
+                                 boundaryCondition="false" constant="false"/>
+                    </listOfSpecies>
+                    <listOfReactions>
+                        <reaction id="balanced" reversible="false">
+                            <listOfReactants>
+                                <speciesReference species="h2" stoichiometry="2" constant="true"/>
+                                <speciesReference species="o2" stoichiometry="1" constant="true"/>
+                            </listOfReactants>
+                            <listOfProducts>
+                                <speciesReference species="h2o" stoichiometry="2" constant="true"/>
+                            </listOfProducts>
+                        </reaction>
+                        <reaction id="unbalanced" reversible="false">
+                            <listOfReactants>
+                                <speciesReference species="h2" stoichiometry="1" constant="true"/>
+                            </listOfReactants>
+                            <listOfProducts>
+                                <speciesReference species="h2o" stoichiometry="1" constant="true"/>
+                            </listOfProducts>
+                        </reaction>
+                        <reaction id="unknown" reversible="false">
+                            <listOfReactants>
+                                <speciesReference species="x" stoichiometry="1" constant="true"/>
+                            </listOfReactants>
+                        </reaction>
+                    </listOfReactions>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        let model = doc.model().get().unwrap();
+        let reactions = model.reactions().get().unwrap();
+        assert_eq!(reactions.get(0).is_mass_balanced(&model), Some(true));
+        assert_eq!(reactions.get(1).is_mass_balanced(&model), Some(false));
+        assert_eq!(reactions.get(2).is_mass_balanced(&model), None);
+
+        let issues = doc.validate();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule, "lint-fbc-mass-balance");
+        assert!(issues[0].message.contains("'unbalanced'"));
+    }
+
+    #[test]
+    pub fn test_tool_annotation_kv() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model id="model">
+                    <annotation>
+                        <other:info xmlns:other="http://example.org/other">keep me</other:info>
+                    </annotation>
+                    <listOfParameters>
+                        <parameter id="k" constant="true">
+                            <notes><p xmlns="http://www.w3.org/1999/xhtml">A rate.</p></notes>
+                        </parameter>
+                    </listOfParameters>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+        let ns = "http://example.org/my-tool";
+
+        let model = doc.model().get().unwrap();
+        assert_eq!(model.get_tool_kv(ns, "layout"), None);
+        model.set_tool_kv(ns, "layout", "circle");
+        model.set_tool_kv(ns, "layout", "grid");
+        model.set_tool_kv(ns, "color", "red");
+        assert_eq!(model.get_tool_kv(ns, "layout"), Some("grid".to_string()));
+        assert_eq!(model.tool_annotation(ns).unwrap().child_elements().len(), 2);
+
+        // Parameter has no annotation yet; it must be created after the notes.
+        let parameter = model.parameters().get().unwrap().get(0);
+        parameter.set_tool_kv(ns, "prior", "lognormal");
+
+        let doc = Sbml::read_str(doc.to_xml_string().unwrap().as_str()).unwrap();
+        assert!(doc.validate().is_empty());
+        let model = doc.model().get().unwrap();
+        assert_eq!(model.get_tool_kv(ns, "color"), Some("red".to_string()));
+        let other = model.tool_annotation("http://example.org/other").unwrap();
+        assert_eq!(other.text_content(), "keep me");
+        let parameter = model.parameters().get().unwrap().get(0);
+        assert_eq!(
+            parameter.get_tool_kv(ns, "prior"),
+            Some("lognormal".to_string())
+        );
+        let children = parameter.children_names();
+        assert_eq!(children, vec!["notes", "annotation"]);
+    }
+
+    #[test]
+    pub fn test_zero_size_compartment() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model id="model">
+                    <listOfCompartments>
+                        <compartment id="c" spatialDimensions="3" size="0" constant="true"/>
+                    </listOfCompartments>
+                    <listOfSpecies>
+                        <species id="s1" compartment="c" initialAmount="10" hasOnlySubstanceUnits="false"
+                                 boundaryCondition="false" constant="false"/>
+                        <species id="s2" compartment="c" initialAmount="0" hasOnlySubstanceUnits="false"
+                                 boundaryCondition="false" constant="false"/>
+                    </listOfSpecies>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        let model = doc.model().get().unwrap();
+        assert_eq!(model.species_by_compartment("c").len(), 2);
+        let issues = doc.validate();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule, "lint-zero-size-compartment");
+        assert_eq!(issues[0].severity, SbmlIssueSeverity::Warning);
+        assert!(issues[0].message.contains("'s1'"));
+    }
+
+    #[test]
+    pub fn test_auto_fix_unused_definitions() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model id="model">
+                    <listOfFunctionDefinitions>
+                        <functionDefinition id="f">
+                            <math xmlns="http://www.w3.org/1998/Math/MathML">
+                                <lambda><bvar><ci> x </ci></bvar><ci> x </ci></lambda>
+                            </math>
+                        </functionDefinition>
+                        <functionDefinition id="g">
+                            <math xmlns="http://www.w3.org/1998/Math/MathML">
+                                <lambda><bvar><ci> x </ci></bvar><apply><ci> f </ci><ci> x </ci></apply></lambda>
+                            </math>
+                        </functionDefinition>
+                        <functionDefinition id="h">
+                            <math xmlns="http://www.w3.org/1998/Math/MathML">
+                                <lambda><bvar><ci> x </ci></bvar><ci> x </ci></lambda>
+                            </math>
+                        </functionDefinition>
+                    </listOfFunctionDefinitions>
+                    <listOfUnitDefinitions>
+                        <unitDefinition id="mM">
+                            <listOfUnits><unit kind="mole" exponent="1" scale="-3" multiplier="1"/></listOfUnits>
+                        </unitDefinition>
+                        <unitDefinition id="per_second">
+                            <listOfUnits><unit kind="second" exponent="-1" scale="0" multiplier="1"/></listOfUnits>
+                        </unitDefinition>
+                    </listOfUnitDefinitions>
+                    <listOfParameters>
+                        <parameter id="p" value="1" units="mM" constant="false"/>
+                    </listOfParameters>
+                    <listOfRules>
+                        <assignmentRule variable="p">
+                            <math xmlns="http://www.w3.org/1998/Math/MathML">
+                                <apply><ci> h </ci><cn type="integer"> 1 </cn></apply>
+                            </math>
+                        </assignmentRule>
+                    </listOfRules>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        // `g` is unused, and once it is removed, `f` is unused as well.
+        assert_eq!(doc.auto_fix_unused_definitions(), 3);
+        assert_eq!(doc.auto_fix_unused_definitions(), 0);
+        let model = doc.model().get().unwrap();
+        let functions = model.function_definitions().get().unwrap();
+        assert_eq!(functions.len(), 1);
+        assert_eq!(functions.get(0).id().get(), Some("h".to_string()));
+        let units = model.unit_definitions().get().unwrap();
+        assert_eq!(units.len(), 1);
+        assert_eq!(units.get(0).id().get(), Some("mM".to_string()));
+    }
+
+    #[test]
+    pub fn test_auto_fix_annotations() {
+        let doc = Sbml::read_str(
+            r##"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model id="model" metaid="m1">
+                    <annotation>
+                        <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+                            <rdf:Description rdf:about="#old"/>
+                        </rdf:RDF>
+                    </annotation>
+                    <listOfParameters>
+                        <parameter id="p1" constant="true"><annotation/></parameter>
+                        <parameter id="p2" constant="true">
+                            <annotation>
+                                <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+                                    <rdf:Description rdf:about="#p2"/>
+                                </rdf:RDF>
+                            </annotation>
+                        </parameter>
+                    </listOfParameters>
+                </model>
+            </sbml>"##,
+        )
+        .unwrap();
+
+        // The model is fixed, the empty annotation of `p1` is removed, and `p2` has no metaid.
+        assert_eq!(doc.auto_fix_annotations(), 2);
+        assert_eq!(doc.auto_fix_annotations(), 0);
+        let model = doc.model().get().unwrap();
+        let parameters = model.parameters().get().unwrap();
+        assert!(parameters.get(0).annotation().get().is_none());
+        assert!(parameters.get(1).annotation().get().is_some());
+        let rules = doc
+            .validate()
+            .into_iter()
+            .filter(|it| it.rule == "lint-rdf-about")
+            .map(|it| XmlElement::new_raw(doc.xml.clone(), it.element).tag_name())
+            .collect::<Vec<_>>();
+        assert_eq!(rules, vec!["parameter".to_string()]);
+
+        // Once `p2` has a metaid, its annotation is fixed as well.
+        assert_eq!(
+            doc.apply_fixes(&[AutoFix::MetaIds, AutoFix::Annotations]),
+            vec![(AutoFix::MetaIds, 1), (AutoFix::Annotations, 1)]
+        );
+        assert!(doc.validate().is_empty());
+    }
+
+    #[test]
+    pub fn test_auto_fix_duplicate_ids() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model id="model">
+                    <listOfUnitDefinitions>
+                        <unitDefinition id="k">
+                            <listOfUnits><unit kind="second" exponent="-1" scale="0" multiplier="1"/></listOfUnits>
+                        </unitDefinition>
+                    </listOfUnitDefinitions>
+                    <listOfParameters>
+                        <parameter id="k" value="1" constant="true"/>
+                        <parameter id="k" value="2" constant="true"/>
+                        <parameter id="k_1" value="3" constant="true"/>
+                    </listOfParameters>
+                    <listOfReactions>
+                        <reaction id="r" reversible="false">
+                            <kineticLaw>
+                                <math xmlns="http://www.w3.org/1998/Math/MathML"><ci> k </ci></math>
+                                <listOfLocalParameters>
+                                    <localParameter id="k" value="1"/>
+                                    <localParameter id="k" value="2"/>
+                                </listOfLocalParameters>
+                            </kineticLaw>
+                        </reaction>
+                    </listOfReactions>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+        assert!(doc.validate().iter().any(|it| it.rule == "10301"));
+
+        // The unit definition and the first local parameter are in a different scope.
+        assert_eq!(doc.auto_fix_duplicate_ids(), 2);
+        assert_eq!(doc.auto_fix_duplicate_ids(), 0);
+        let model = doc.model().get().unwrap();
+        let parameters = model.parameters().get().unwrap();
+        assert_eq!(parameters.get(0).id().get(), "k");
+        assert_eq!(parameters.get(1).id().get(), "k_2");
+        let reaction = model.reactions().get().unwrap().get(0);
+        let law = reaction.kinetic_law().get().unwrap();
+        let locals = law.local_parameters().get().unwrap();
+        assert_eq!(locals.get(0).id().get(), "k");
+        assert_eq!(locals.get(1).id().get(), "k_3");
+        assert!(doc.validate().iter().all(|it| it.rule != "10301"));
+    }
+
+    #[test]
+    pub fn test_apply_fixes() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model id="model" metaid="metaid_1">
+                    <annotation><x:info xmlns:x="http://example.org/x"/></annotation>
+                    <listOfParameters>
+                        <parameter id="p" constant="true">
+                            <annotation><x:info xmlns:x="http://example.org/x"/></annotation>
+                        </parameter>
+                    </listOfParameters>
+                    <listOfInitialAssignments>
+                        <initialAssignment symbol="p">
+                            <math><cn> 1 </cn></math>
+                        </initialAssignment>
+                    </listOfInitialAssignments>
+                </model>
+            </sbml>"#;
+        let doc = Sbml::read_str(xml).unwrap();
+
+        let fixes = [AutoFix::MathNamespace, AutoFix::MetaIds];
+        assert_eq!(
+            doc.apply_fixes(&fixes),
+            vec![(AutoFix::MathNamespace, 1), (AutoFix::MetaIds, 1)]
+        );
+        assert_eq!(
+            doc.apply_fixes(&fixes),
+            vec![(AutoFix::MathNamespace, 0), (AutoFix::MetaIds, 0)]
+        );
+        let model = doc.model().get().unwrap();
+        let parameter = model.parameters().get().unwrap().get(0);
+        assert!(doc.validate().is_empty());
+
+        // The fix assigns the same value as `ensure_meta_id_deterministic`.
+        let expected = Sbml::read_str(xml).unwrap();
+        let model = expected.model().get().unwrap();
+        let expected = model
+            .parameters()
+            .get()
+            .unwrap()
+            .get(0)
+            .ensure_meta_id_deterministic();
+        assert!(expected.starts_with("meta_"));
+        assert_eq!(parameter.meta_id().get(), Some(expected));
+    }
+
+    #[test]
+    pub fn test_qual_constant_output() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core"
+                  xmlns:qual="http://www.sbml.org/sbml/level3/version1/qual/version1"
+                  level="3" version="2" qual:required="true">
+                <model id="model">
+                    <qual:listOfQualitativeSpecies>
+                        <qual:qualitativeSpecies qual:id="a" qual:compartment="c" qual:constant="true"/>
+                        <qual:qualitativeSpecies qual:id="b" qual:compartment="c" qual:constant="false"/>
+                    </qual:listOfQualitativeSpecies>
+                    <qual:listOfTransitions>
+                        <qual:transition qual:id="t">
+                            <qual:listOfOutputs>
+                                <qual:output qual:qualitativeSpecies="a" qual:transitionEffect="assignmentLevel"/>
+                                <qual:output qual:qualitativeSpecies="b" qual:transitionEffect="assignmentLevel"/>
+                            </qual:listOfOutputs>
+                        </qual:transition>
+                    </qual:listOfTransitions>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        let issues = doc.validate();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule, "qual-20601");
+        assert!(issues[0].message.contains("'a'"));
+    }
+
+    #[test]
+    pub fn test_qual_level_bounds() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core"
+                  xmlns:qual="http://www.sbml.org/sbml/level3/version1/qual/version1"
+                  level="3" version="2" qual:required="true">
+                <model id="model">
+                    <qual:listOfQualitativeSpecies>
+                        <qual:qualitativeSpecies qual:id="a" qual:compartment="c" qual:constant="false"
+                            qual:maxLevel="1" qual:initialLevel="2"/>
+                        <qual:qualitativeSpecies qual:id="b" qual:compartment="c" qual:constant="false"
+                            qual:maxLevel="2" qual:initialLevel="1"/>
+                    </qual:listOfQualitativeSpecies>
+                    <qual:listOfTransitions>
+                        <qual:transition qual:id="t">
+                            <qual:listOfOutputs>
+                                <qual:output qual:qualitativeSpecies="b" qual:transitionEffect="assignmentLevel"/>
+                            </qual:listOfOutputs>
+                            <qual:listOfFunctionTerms>
+                                <qual:defaultTerm qual:resultLevel="0"/>
+                                <qual:functionTerm qual:resultLevel="3"/>
+                            </qual:listOfFunctionTerms>
+                        </qual:transition>
+                    </qual:listOfTransitions>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        let issues = doc.validate();
+        assert_eq!(issues.len(), 2);
+        assert!(issues.iter().all(|it| it.rule == "qual-20308"));
+        assert!(issues[0].message.contains("[qual:initialLevel] ('2')"));
+        assert!(issues[0].message.contains("'a'"));
+        assert!(issues[1].message.contains("[qual:resultLevel] ('3')"));
+        assert!(issues[1].message.contains("'b'"));
+    }
+
+    #[test]
+    pub fn test_import_into() {
+        let qual = "http://www.sbml.org/sbml/level3/version1/qual/version1";
+        let source = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core"
+                  xmlns:qual="http://www.sbml.org/sbml/level3/version1/qual/version1"
+                  level="3" version="2" qual:required="true">
+                <model id="model">
+                    <notes><p xmlns="http://www.w3.org/1999/xhtml">Some notes.</p></notes>
+                    <qual:listOfQualitativeSpecies>
+                        <qual:qualitativeSpecies qual:id="a" qual:compartment="c" qual:constant="true"/>
+                    </qual:listOfQualitativeSpecies>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+        let source_model = source.model().get().unwrap();
+        let qual_species = |model: &Model| {
+            model
+                .optional_child::<XmlList<XmlElement>>("listOfQualitativeSpecies", qual)
+                .get()
+                .map(|it| it.len())
+        };
+
+        // The qual namespace is declared on the source <sbml> element, hence it must be
+        // re-declared in the target document.
+        let target = Sbml::default();
+        let model = source_model.clone_into(&target);
+        assert!(std::sync::Arc::ptr_eq(
+            &model.document(),
+            &target.document()
+        ));
+        assert_eq!(qual_species(&model), Some(1));
+
+        let target = Sbml::read_str(target.to_xml_string().unwrap().as_str()).unwrap();
+        let model = target.model().get().unwrap();
+        assert_eq!(model.id().get(), Some("model".to_string()));
+        assert_eq!(qual_species(&model), Some(1));
+        let species = model
+            .optional_child::<XmlList<XmlElement>>("listOfQualitativeSpecies", qual)
+            .get()
+            .unwrap()
+            .get(0);
+        assert_eq!(
+            species.get_namespaced_attribute("id", qual),
+            Some("a".to_string())
+        );
+
+        // Copy a single element within the same document.
+        let notes = source_model.notes().get().unwrap();
+        let copy = notes.import_into(source.document());
+        assert!(copy.is_detached());
+        assert_eq!(copy.text_content(), notes.text_content());
+    }
+
+    #[test]
+    pub fn test_import_into_deep() {
+        // Copying must not recurse over the nesting depth of the copied subtree.
+        let depth = 100_000;
+        let xml = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <math xmlns="http://www.w3.org/1998/Math/MathML">{}<cn>1</cn>{}</math>"#,
+            "<apply><minus/>".repeat(depth),
+            "</apply>".repeat(depth)
+        );
+        let source = biodivine_xml_doc::Document::parse_str(xml.as_str()).unwrap();
+        let root = source.root_element().unwrap();
+        let source = std::sync::Arc::new(std::sync::RwLock::new(source));
+        let math = XmlElement::new_raw(source, root);
+
+        let target = Sbml::default();
+        let copy = math.import_into(target.document());
+        assert!(copy.is_detached());
+        assert_eq!(copy.max_depth(), depth + 1);
+        assert_eq!(copy.descendant_count(), math.descendant_count());
+        assert_eq!(copy.namespace_url(), URL_MATHML);
+    }
+
+    #[test]
+    pub fn test_unit_missing_attributes() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model id="model">
+                    <listOfUnitDefinitions>
+                        <unitDefinition id="length">
+                            <listOfUnits>
+                                <unit kind="metre"/>
+                            </listOfUnits>
+                        </unitDefinition>
+                    </listOfUnitDefinitions>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        let issues = doc.validate();
+        assert_eq!(issues.len(), 3);
+        assert!(issues.iter().all(|it| it.rule == "20421"));
+
+        // A default unit has all the required attributes.
+        let model = doc.model().get().unwrap();
+        let unit_definition = model.unit_definitions().get().unwrap().get(0);
+        let units = unit_definition.units().get().unwrap();
+        units.remove(0);
+        units.push(Unit::default(doc.document()));
+        assert!(doc.validate().is_empty());
+    }
+
+    #[test]
+    pub fn test_read_whitespace() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model id="model">
+                    <notes><pre xmlns="http://www.w3.org/1999/xhtml">  a
+    b  </pre></notes>
+                </model>
+            </sbml>"#;
+
+        let notes_text = |doc: &Sbml| {
+            let notes = doc.model().get().unwrap().notes().get().unwrap();
+            notes.child_elements()[0].text_content()
+        };
+
+        let trimmed = Sbml::read_str_with(xml, Whitespace::Trim).unwrap();
+        assert_eq!(notes_text(&trimmed), "a\n    b");
+        let preserved = Sbml::read_str_with(xml, Whitespace::Preserve).unwrap();
+        assert_eq!(notes_text(&preserved), "  a\n    b  ");
+        assert!(preserved.validate().is_empty());
+
+        // By default, only the `notes` and `annotation` subtrees are preserved.
+        let default = Sbml::read_str(xml).unwrap();
+        assert_eq!(notes_text(&default), "  a\n    b  ");
+        // The whitespace-only text around `notes` is dropped.
+        let model = default.model().get().unwrap();
+        let children = model.raw_element().children(&model.read_doc()).len();
+        assert_eq!(children, 1);
+        assert_eq!(
+            Sbml::read_bytes(xml.as_bytes()).unwrap().to_xml_string(),
+            default.to_xml_string()
+        );
+        assert!(default.validate().is_empty());
+
+        let doc =
+            Sbml::read_path("test-inputs/cholesterol_metabolism_and_atherosclerosis.xml").unwrap();
+        let xml = doc.to_xml_string().unwrap();
+        let preserved = Sbml::read_str_with(xml.as_str(), Whitespace::Preserve).unwrap();
+        assert_eq!(preserved.validate().len(), doc.validate().len());
+        let trimmed = Sbml::read_str_with(xml.as_str(), Whitespace::Trim).unwrap();
+        assert_eq!(trimmed.validate().len(), doc.validate().len());
+    }
+
+    #[test]
+    pub fn test_base_unit_si_dimensions() {
+        assert_eq!(BaseUnit::Newton.si_dimensions(), [1, 1, -2, 0, 0, 0, 0]);
+        assert_eq!(BaseUnit::Volt.si_dimensions(), [1, 2, -3, -1, 0, 0, 0]);
+        assert_eq!(BaseUnit::Katal.si_dimensions(), [0, 0, -1, 0, 0, 1, 0]);
+        assert_eq!(BaseUnit::Litre.si_dimensions(), [0, 3, 0, 0, 0, 0, 0]);
+        assert_eq!(
+            BaseUnit::Gram.si_dimensions(),
+            BaseUnit::Kilogram.si_dimensions()
+        );
+        assert_eq!(
+            BaseUnit::Gray.si_dimensions(),
+            BaseUnit::Sievert.si_dimensions()
+        );
+        assert_eq!(BaseUnit::Item.si_dimensions(), [0; 7]);
+        assert_eq!(BaseUnit::Avogadro.si_dimensions(), [0; 7]);
+
+        // Siemens is the inverse of ohm.
+        let ohm = BaseUnit::Ohm.si_dimensions();
+        let siemens = BaseUnit::Siemens.si_dimensions();
+        assert!(ohm.iter().zip(siemens.iter()).all(|(a, b)| a + b == 0));
+    }
+
+    #[test]
+    pub fn test_same_document() {
+        let first = Sbml::default();
+        let second = Sbml::default();
+        let model = Model::default(first.xml.clone());
+        first.model().set(model.clone());
+        let parameters = model.parameters().get_or_create();
+
+        let local = Parameter::new(first.xml.clone(), &"a".to_string(), true);
+        let foreign = Parameter::new(second.xml.clone(), &"b".to_string(), true);
+        assert!(local.same_document(&parameters));
+        assert!(!foreign.same_document(&parameters));
+
+        assert!(parameters.try_push(foreign.clone()).is_err());
+        assert!(foreign.is_detached());
+        assert!(parameters.try_push(local).is_ok());
+        assert_eq!(parameters.len(), 1);
+    }
+
+    #[test]
+    pub fn test_edit_guard() {
+        let doc = Sbml::default();
+        let model = Model::default(doc.xml.clone());
+        doc.model().set(model.clone());
+        let parameters = model.parameters().get_or_create();
+        for i in 0..10 {
+            parameters.push(Parameter::new(doc.xml.clone(), &format!("p{i}"), true));
+        }
+
+        // Elements must be collected before the document is locked.
+        let items = parameters.iter().collect::<Vec<_>>();
+        let written = doc.edit(|guard| {
+            for parameter in &items {
+                guard.set(&parameter.value(), Some(&2.5));
+                guard.set(&parameter.name(), Some(&"name".to_string()));
+                guard.clear(&parameter.name());
+            }
+            guard.get_checked(&items[0].value()).unwrap()
+        });
+
+        assert_eq!(written, Some(2.5));
+        for parameter in parameters.iter() {
+            assert_eq!(parameter.value().get(), Some(2.5));
+            assert!(!parameter.name().is_set());
+        }
+
+        // New elements can be created and attached while the document is locked.
+        let result = doc.edit(|guard| {
+            let parameter = guard.new_element::<Parameter>("parameter");
+            guard.set(&parameter.id(), Some(&"q".to_string()));
+            guard.set(&parameter.constant(), Some(&false));
+            guard.attach(&parameter, &parameters, Some(0))?;
+            // An attached element cannot be attached again.
+            assert!(guard.attach(&parameter, &parameters, None).is_err());
+            Ok::<_, String>(parameter)
+        });
+        let parameter = result.unwrap();
+        assert_eq!(parameters.len(), 11);
+        assert_eq!(parameters.get(0).id().get(), "q");
+        assert!(!parameter.constant().get());
+        assert!(doc.validate().is_empty());
+    }
+
+    #[test]
+    pub fn test_empty_unit_definition() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model>
+                    <listOfUnitDefinitions>
+                        <unitDefinition id="nothing"/>
+                        <unitDefinition id="empty">
+                            <listOfUnits/>
+                        </unitDefinition>
+                        <unitDefinition id="per_second">
+                            <listOfUnits>
+                                <unit kind="second" exponent="-1" scale="0" multiplier="1"/>
+                            </listOfUnits>
+                        </unitDefinition>
+                    </listOfUnitDefinitions>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        let issues = doc.validate();
+        let empty = issues
+            .iter()
+            .filter(|it| it.rule == "lint-empty-unit-definition")
+            .collect::<Vec<_>>();
+        assert_eq!(empty.len(), 2);
+        assert!(empty
+            .iter()
+            .all(|it| it.severity == SbmlIssueSeverity::Warning));
+        assert!(empty[0].message.contains("'nothing'"));
+        assert!(empty[1].message.contains("'empty'"));
+    }
+
+    #[test]
+    pub fn test_package_required() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2"
+                xmlns:fbc="http://www.sbml.org/sbml/level3/version1/fbc/version2"
+                xmlns:qual="http://www.sbml.org/sbml/level3/version1/qual/version1"
+                fbc:required="false" qual:required="true">
+                <model id="m"/>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        assert_eq!(doc.package_required(URL_PACKAGE_FBC), Some(false));
+        assert_eq!(doc.package_required(URL_PACKAGE_QUAL), Some(true));
+        assert_eq!(doc.package_required(URL_PACKAGE_COMP), None);
+        assert_eq!(doc.distrib_required(), None);
+    }
+
+    #[test]
+    pub fn test_time_in_static_math() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model>
+                    <listOfFunctionDefinitions>
+                        <functionDefinition id="f">
+                            <math xmlns="http://www.w3.org/1998/Math/MathML">
+                                <lambda>
+                                    <bvar><ci>x</ci></bvar>
+                                    <apply><times/><ci>x</ci><csymbol definitionURL="http://www.sbml.org/sbml/symbols/time"> t </csymbol></apply>
+                                </lambda>
+                            </math>
+                        </functionDefinition>
+                    </listOfFunctionDefinitions>
+                    <listOfParameters>
+                        <parameter id="p1" constant="false"/>
+                        <parameter id="p2" constant="false"/>
+                    </listOfParameters>
+                    <listOfInitialAssignments>
+                        <initialAssignment symbol="p1">
+                            <math xmlns="http://www.w3.org/1998/Math/MathML">
+                                <csymbol definitionURL="http://www.sbml.org/sbml/symbols/time"> t </csymbol>
+                            </math>
+                        </initialAssignment>
+                    </listOfInitialAssignments>
+                    <listOfRules>
+                        <assignmentRule variable="p2">
+                            <math xmlns="http://www.w3.org/1998/Math/MathML">
+                                <csymbol definitionURL="http://www.sbml.org/sbml/symbols/time"> t </csymbol>
+                            </math>
+                        </assignmentRule>
+                    </listOfRules>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        // Time is fine in the assignment rule, but suspicious in the other two.
+        let issues = doc.validate();
+        assert_eq!(issues.len(), 2);
+        assert!(issues
+            .iter()
+            .all(|it| it.rule == "lint-time-in-static-math"
+                && it.severity == SbmlIssueSeverity::Warning));
+        assert!(issues[0].message.contains("<functionDefinition>"));
+        assert!(issues[1].message.contains("<initialAssignment>"));
+    }
+
+    #[test]
+    pub fn test_misplaced_math() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model>
+                    <listOfSpecies>
+                        <math xmlns="http://www.w3.org/1998/Math/MathML"><cn>1</cn></math>
+                    </listOfSpecies>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        let issues = doc.validate();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule, "20209");
+        assert!(issues[0]
+            .message
+            .contains("not permitted in <listOfSpecies>"));
+    }
+
+    #[test]
+    pub fn test_single_math_expression() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model>
+                    <listOfParameters>
+                        <parameter id="p" constant="false"/>
+                    </listOfParameters>
+                    <listOfInitialAssignments>
+                        <initialAssignment symbol="p">
+                            <math xmlns="http://www.w3.org/1998/Math/MathML">
+                                <apply><plus/><cn>1</cn><cn>2</cn></apply>
+                                <apply><times/><cn>3</cn><cn>4</cn></apply>
+                            </math>
+                        </initialAssignment>
+                    </listOfInitialAssignments>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        let issues = doc.validate();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule, "10201");
+        assert!(issues[0]
+            .message
+            .contains("exactly one MathML expression, but 2 were found"));
+    }
+
+    #[test]
+    pub fn test_initial_assignment_symbol_target() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model>
+                    <listOfParameters>
+                        <parameter id="k" constant="true"/>
+                    </listOfParameters>
+                    <listOfInitialAssignments>
+                        <initialAssignment symbol="k">
+                            <math xmlns="http://www.w3.org/1998/Math/MathML"><cn>1</cn></math>
+                        </initialAssignment>
+                        <initialAssignment symbol="r">
+                            <math xmlns="http://www.w3.org/1998/Math/MathML"><cn>1</cn></math>
+                        </initialAssignment>
+                        <initialAssignment symbol="missing">
+                            <math xmlns="http://www.w3.org/1998/Math/MathML"><cn>1</cn></math>
+                        </initialAssignment>
+                    </listOfInitialAssignments>
+                    <listOfReactions>
+                        <reaction id="r" reversible="false"/>
+                    </listOfReactions>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        let issues: Vec<SbmlIssue> = doc
+            .validate()
+            .into_iter()
+            .filter(|it| it.rule == "20801")
+            .collect();
+        assert_eq!(issues.len(), 2);
+        assert!(issues[0].message.contains("('r')"));
+        assert!(issues[0].message.contains("refers to a <reaction>"));
+        assert!(issues[1].message.contains("('missing')"));
+        assert!(issues[1].message.contains("does not refer to any"));
+    }
+
+    #[test]
+    pub fn test_initial_assignment_unique_symbols() {
+        let validate = |initial_assignments: &[&str], rule_variable: &str| {
+            let initial_assignments = initial_assignments
+                .iter()
+                .map(|symbol| {
+                    format!(
+                        r#"<initialAssignment symbol="{symbol}">
+                            <math xmlns="http://www.w3.org/1998/Math/MathML"><cn>1</cn></math>
+                        </initialAssignment>"#
+                    )
+                })
+                .collect::<String>();
+            let doc = Sbml::read_str(
+                format!(
+                    r#"<?xml version="1.0" encoding="UTF-8"?>
+                    <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                        <model>
+                            <listOfParameters>
+                                <parameter id="a" constant="false"/>
+                                <parameter id="b" constant="false"/>
+                            </listOfParameters>
+                            <listOfInitialAssignments>{initial_assignments}</listOfInitialAssignments>
+                            <listOfRules>
+                                <assignmentRule variable="{rule_variable}">
+                                    <math xmlns="http://www.w3.org/1998/Math/MathML"><cn>2</cn></math>
+                                </assignmentRule>
+                            </listOfRules>
+                        </model>
+                    </sbml>"#
+                )
+                .as_str(),
+            )
+            .unwrap();
+            doc.validate()
+                .into_iter()
+                .map(|it| (it.rule, it.message))
+                .collect::<Vec<_>>()
+        };
+
+        // Distinct symbols which are not assigned by a rule are valid.
+        assert!(validate(&["a"], "b").is_empty());
+
+        // Rule 20802: the same symbol is assigned twice.
+        let issues = validate(&["a", "a"], "b");
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].0, "20802");
+        assert!(issues[0].1.contains("('a')"));
+
+        // Rule 20803: the symbol is also the variable of an assignment rule.
+        let issues = validate(&["a", "b"], "b");
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].0, "20803");
+        assert!(issues[0].1.contains("('b')"));
+    }
+
+    #[test]
+    pub fn test_padded_math_identifiers() {
+        // Padding is only kept in the document when whitespace is preserved.
+        let doc = Sbml::read_str_with(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model>
+                    <listOfFunctionDefinitions>
+                        <functionDefinition id="f">
+                            <math xmlns="http://www.w3.org/1998/Math/MathML">
+                                <lambda><bvar><ci> x </ci></bvar><ci>
+                                    x
+                                </ci></lambda>
+                            </math>
+                        </functionDefinition>
+                    </listOfFunctionDefinitions>
+                    <listOfParameters>
+                        <parameter id="p" value="1" constant="true"/>
+                        <parameter id="q" constant="false"/>
+                    </listOfParameters>
+                    <listOfRules>
+                        <assignmentRule variable="q">
+                            <math xmlns="http://www.w3.org/1998/Math/MathML">
+                                <apply><ci>  f  </ci><ci>
+                                    p
+                                </ci></apply>
+                            </math>
+                        </assignmentRule>
+                    </listOfRules>
+                </model>
+            </sbml>"#,
+            Whitespace::Preserve,
+        )
+        .unwrap();
+
+        assert!(doc.validate().is_empty());
+
+        let model = doc.model().get().unwrap();
+        let rule = model.rules().get().unwrap().get(0);
+        let math = rule.math().get().unwrap();
+        let ci = math.recursive_child_elements_filtered(|it| it.tag_name() == "ci");
+        assert_eq!(ci.len(), 2);
+        assert_ne!(ci[1].text_content(), "p");
+        assert_eq!(ci[1].text_content_trimmed(), "p");
+        assert_eq!(ci[1].text_as_f64(), None);
+
+        let cn = XmlElement::new_quantified(model.document(), "cn", NS_EMPTY);
+        cn.raw_element()
+            .set_text_content(model.write_doc().deref_mut(), " 2.5e-1 ");
+        assert_eq!(cn.text_as_f64(), Some(0.25));
+    }
+
+    #[test]
+    pub fn test_function_definition_calls() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model>
+                    <listOfFunctionDefinitions>
+                        <functionDefinition id="f">
+                            <math xmlns="http://www.w3.org/1998/Math/MathML">
+                                <lambda>
+                                    <bvar><ci> x </ci></bvar>
+                                    <apply><plus/><apply><ci> g </ci><ci> x </ci></apply><apply><ci> sqaure </ci><ci> x </ci></apply></apply>
+                                </lambda>
+                            </math>
+                        </functionDefinition>
+                        <functionDefinition id="g">
+                            <math xmlns="http://www.w3.org/1998/Math/MathML">
+                                <lambda><bvar><ci> y </ci></bvar><ci> y </ci></lambda>
+                            </math>
+                        </functionDefinition>
+                    </listOfFunctionDefinitions>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        let issues = doc.validate();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule, "10214");
+        assert!(issues[0].message.contains("'sqaure'"));
+    }
+
+    #[test]
+    pub fn test_rateof_targets() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model>
+                    <listOfParameters>
+                        <parameter id="p" constant="false"/>
+                        <parameter id="q" constant="false"/>
+                    </listOfParameters>
+                    <listOfRules>
+                        <assignmentRule variable="p">
+                            <math xmlns="http://www.w3.org/1998/Math/MathML">
+                                <apply>
+                                    <csymbol encoding="text" definitionURL="http://www.sbml.org/sbml/symbols/rateOf"> rateOf </csymbol>
+                                    <ci> missing </ci>
+                                </apply>
+                            </math>
+                        </assignmentRule>
+                    </listOfRules>
+                    <listOfReactions>
+                        <reaction id="r" reversible="false">
+                            <kineticLaw>
+                                <math xmlns="http://www.w3.org/1998/Math/MathML">
+                                    <apply>
+                                        <plus/>
+                                        <apply>
+                                            <csymbol encoding="text" definitionURL="http://www.sbml.org/sbml/symbols/rateOf"> rateOf </csymbol>
+                                            <ci> k </ci>
+                                        </apply>
+                                        <apply>
+                                            <csymbol encoding="text" definitionURL="http://www.sbml.org/sbml/symbols/rateOf"> rateOf </csymbol>
+                                            <ci> q </ci>
+                                        </apply>
+                                    </apply>
+                                </math>
+                                <listOfLocalParameters>
+                                    <localParameter id="k" value="1"/>
+                                </listOfLocalParameters>
+                            </kineticLaw>
+                        </reaction>
+                    </listOfReactions>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        let issues = doc
+            .validate()
+            .into_iter()
+            .filter(|it| it.rule.starts_with("102"))
+            .collect::<Vec<_>>();
+        let rules = issues.iter().map(|it| it.rule.as_str()).collect::<Vec<_>>();
+        // The undeclared target is only reported once (by rule 10215).
+        assert_eq!(rules, vec!["10215", "10226"]);
+        assert!(issues[1].message.contains("('k')"));
+        assert!(issues[1].message.contains("<localParameter>"));
+    }
+
+    #[test]
+    pub fn test_constraint_boolean_math() {
+        let constraint = |math: &str| {
+            let doc = Sbml::read_str(
+                format!(
+                    r#"<?xml version="1.0" encoding="UTF-8"?>
+                    <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                        <model>
+                            <listOfParameters>
+                                <parameter id="p" value="1" constant="true"/>
+                            </listOfParameters>
+                            <listOfConstraints>
+                                <constraint>
+                                    <math xmlns="http://www.w3.org/1998/Math/MathML">{math}</math>
+                                </constraint>
+                            </listOfConstraints>
+                        </model>
+                    </sbml>"#
+                )
+                .as_str(),
+            )
+            .unwrap();
+            doc.validate()
+                .into_iter()
+                .map(|it| (it.rule, it.severity))
+                .collect::<Vec<_>>()
+        };
+
+        let warning = vec![(
+            "lint-constraint-not-boolean".to_string(),
+            SbmlIssueSeverity::Warning,
+        )];
+        assert_eq!(
+            constraint("<apply><plus/><ci> p </ci><cn> 1 </cn></apply>"),
+            warning
+        );
+        assert_eq!(constraint("<cn> 1 </cn>"), warning);
+        assert!(constraint("<apply><lt/><ci> p </ci><cn> 1 </cn></apply>").is_empty());
+        assert!(
+            constraint("<apply><not/><apply><gt/><ci> p </ci><cn> 1 </cn></apply></apply>")
+                .is_empty()
+        );
+        assert!(constraint("<piecewise><otherwise><true/></otherwise></piecewise>").is_empty());
+    }
+
+    #[test]
+    pub fn test_undeclared_attribute_prefix() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2"
+                xmlns:layout="http://www.sbml.org/sbml/level3/version1/layout/version1"
+                layout:required="false">
+                <model id="m" layotu:extra="x">
+                    <listOfParameters>
+                        <parameter id="p" constant="true" layout:extra="x"/>
+                    </listOfParameters>
+                </model>
+            </sbml>"#,
+        );
+        let doc = doc.unwrap();
+
+        let issues = doc.validate();
+        let undeclared = issues
+            .iter()
+            .filter(|it| it.message.contains("is not a declared namespace"))
+            .collect::<Vec<_>>();
+        assert_eq!(undeclared.len(), 1);
+        assert_eq!(undeclared[0].rule, "20222");
+        assert!(undeclared[0].message.contains("layotu:extra"));
+    }
+
+    #[test]
+    pub fn test_reaction_compartment() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model>
+                    <listOfCompartments>
+                        <compartment id="c1" constant="true"/>
+                        <compartment id="c2" constant="true"/>
+                    </listOfCompartments>
+                    <listOfSpecies>
+                        <species id="a" compartment="c1" hasOnlySubstanceUnits="false" boundaryCondition="false" constant="false"/>
+                        <species id="b" compartment="c2" hasOnlySubstanceUnits="false" boundaryCondition="false" constant="false"/>
+                    </listOfSpecies>
+                    <listOfReactions>
+                        <reaction id="r1" reversible="false" compartment="c1">
+                            <listOfReactants><speciesReference species="a" constant="true"/></listOfReactants>
+                            <listOfProducts><speciesReference species="b" constant="true"/></listOfProducts>
+                        </reaction>
+                        <reaction id="r2" reversible="false">
+                            <listOfReactants><speciesReference species="a" constant="true"/></listOfReactants>
+                            <listOfProducts><speciesReference species="b" constant="true"/></listOfProducts>
+                        </reaction>
+                    </listOfReactions>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        // Only the product of `r1` is outside of the reaction compartment.
+        let issues = doc.validate();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule, "lint-reaction-compartment");
+        assert_eq!(issues[0].severity, SbmlIssueSeverity::Warning);
+        assert!(issues[0].message.contains("('b')"));
+        assert!(issues[0].message.contains("('r1')"));
+    }
+
+    #[test]
+    pub fn test_reaction_fast_attribute() {
+        let doc = Sbml::default();
+        let model = doc.model().get_or_create();
+        let reaction = Reaction::new(model.document(), &"r1".to_string(), false);
+        model.reactions().get_or_create().push(reaction.clone());
+        reaction
+            .raw_element()
+            .set_attribute(reaction.write_doc().deref_mut(), "fast", "true");
+
+        let issues = doc
+            .validate()
+            .into_iter()
+            .filter(|it| it.severity == SbmlIssueSeverity::Error)
+            .collect::<Vec<_>>();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule, "21110");
+        assert!(issues[0].message.contains("[fast]"));
+        assert!(issues[0].message.contains("Level 3 Version 2"));
+    }
+
+    #[test]
+    pub fn test_validate_subtree() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model id="model">
+                    <listOfCompartments>
+                        <compartment id="c" constant="true"/>
+                    </listOfCompartments>
+                    <listOfSpecies>
+                        <species id="s" compartment="c" hasOnlySubstanceUnits="false"
+                            boundaryCondition="false" constant="false"/>
+                    </listOfSpecies>
+                    <listOfReactions>
+                        <reaction id="r" reversible="false">
+                            <listOfReactants>
+                                <speciesReference species="s" constant="true"/>
+                            </listOfReactants>
+                        </reaction>
+                    </listOfReactions>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+        let model = doc.model().get().unwrap();
+        let species = model.species().get().unwrap().get(0);
+        let reaction = model.reactions().get().unwrap().get(0);
+        let reactant = reaction.reactants().get().unwrap().get(0);
+
+        assert!(reaction.validate_subtree().is_empty());
+        assert!(species.validate_subtree().is_empty());
+
+        // The reference now clashes with the identifier of the species.
+        reactant.id().set_some(&"s".to_string());
+        let issues = reaction.validate_subtree();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule, "10301");
+        assert_eq!(reactant.xml_element().raw_element(), issues[0].element);
+
+        // Type check failures are reported without further validation.
+        reactant
+            .raw_element()
+            .set_attribute(reactant.write_doc().deref_mut(), "constant", "maybe");
+        let issues = reaction.validate_subtree();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule, "SANITY_CHECK");
+    }
+
+    #[test]
+    pub fn test_species_reference_identifiers() {
+        let xml = |rule_target: &str, constant: &str| {
+            format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+                <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                    <model id="model">
+                        <listOfCompartments>
+                            <compartment id="c" constant="true"/>
+                        </listOfCompartments>
+                        <listOfSpecies>
+                            <species id="s" compartment="c" hasOnlySubstanceUnits="false"
+                                boundaryCondition="false" constant="false"/>
+                        </listOfSpecies>
+                        <listOfRules>
+                            <assignmentRule variable="{rule_target}">
+                                <math xmlns="http://www.w3.org/1998/Math/MathML"><cn>2</cn></math>
+                            </assignmentRule>
+                        </listOfRules>
+                        <listOfReactions>
+                            <reaction id="r" reversible="false">
+                                <listOfReactants>
+                                    <speciesReference id="ref" species="s" constant="{constant}"/>
+                                </listOfReactants>
+                            </reaction>
+                        </listOfReactions>
+                    </model>
+                </sbml>"#
+            )
+        };
+
+        let doc = Sbml::read_str(xml("ref", "false").as_str()).unwrap();
+        assert!(doc.validate().is_empty());
+
+        let doc = Sbml::read_str(xml("ref", "true").as_str()).unwrap();
+        let issues = doc.validate();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule, "20903");
+
+        // Species reference identifiers share the SId namespace with other components.
+        let doc = Sbml::read_str(
+            xml("ref", "false")
+                .replace("id=\"ref\"", "id=\"s\"")
+                .as_str(),
+        )
+        .unwrap();
+        let issues = doc.validate();
+        assert!(issues.iter().any(|it| it.rule == "10301"));
+    }
+
+    #[test]
+    pub fn test_statistics() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2"
+                xmlns:fbc="http://www.sbml.org/sbml/level3/version1/fbc/version2"
+                fbc:required="false">
+                <model id="model">
+                    <listOfCompartments>
+                        <compartment id="c" constant="true"/>
+                    </listOfCompartments>
+                    <listOfSpecies>
+                        <species id="s" compartment="c" hasOnlySubstanceUnits="false"
+                            boundaryCondition="false" constant="false"/>
+                    </listOfSpecies>
+                    <listOfParameters>
+                        <parameter id="p" constant="false"/>
+                        <parameter id="q" constant="false"/>
+                    </listOfParameters>
+                    <listOfRules>
+                        <assignmentRule variable="p">
+                            <math xmlns="http://www.w3.org/1998/Math/MathML"><cn>2</cn></math>
+                        </assignmentRule>
+                        <rateRule variable="q">
+                            <math xmlns="http://www.w3.org/1998/Math/MathML"><cn>1</cn></math>
+                        </rateRule>
+                    </listOfRules>
+                    <listOfReactions>
+                        <reaction id="r" reversible="false">
+                            <listOfReactants>
+                                <speciesReference species="s" constant="true"/>
+                            </listOfReactants>
+                            <kineticLaw>
+                                <math xmlns="http://www.w3.org/1998/Math/MathML"><ci>k</ci></math>
+                                <listOfLocalParameters>
+                                    <localParameter id="k" value="1"/>
+                                </listOfLocalParameters>
+                            </kineticLaw>
+                        </reaction>
+                    </listOfReactions>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        let stats = doc.statistics();
+        assert_eq!(stats.compartments, 1);
+        assert_eq!(stats.species, 1);
+        assert_eq!(stats.parameters, 2);
+        assert_eq!(stats.local_parameters, 1);
+        assert_eq!(stats.reactions, 1);
+        assert_eq!(stats.assignment_rules, 1);
+        assert_eq!(stats.rate_rules, 1);
+        assert_eq!(stats.algebraic_rules, 0);
+        assert_eq!(stats.rules(), 2);
+        assert_eq!(stats.events, 0);
+        assert_eq!(stats.packages, vec!["fbc".to_string()]);
+        assert_eq!(
+            stats.to_string(),
+            "1 compartments, 1 species, 1 reactions, 2 parameters (1 local), \
+            0 initial assignments, 2 rules (1 assignment, 1 rate, 0 algebraic), 0 constraints, \
+            0 events, 0 function definitions, 0 unit definitions, packages: fbc"
+        );
+
+        assert_eq!(Sbml::default().statistics(), ModelStats::default());
+    }
+
+    #[test]
+    pub fn test_apply_operator() {
+        let xml = |math: &str| {
+            format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+                <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                    <model id="model">
+                        <listOfParameters>
+                            <parameter id="p" constant="false"/>
+                        </listOfParameters>
+                        <listOfRules>
+                            <assignmentRule variable="p">
+                                <math xmlns="http://www.w3.org/1998/Math/MathML">{math}</math>
+                            </assignmentRule>
+                        </listOfRules>
+                    </model>
+                </sbml>"#
+            )
+        };
+
+        let doc = Sbml::read_str(&xml("<apply><plus/><cn>1</cn><cn>2</cn></apply>")).unwrap();
+        assert!(doc.validate().is_empty());
+
+        let doc = Sbml::read_str(&xml("<apply><cn>2</cn><cn>1</cn></apply>")).unwrap();
+        let issues = doc.validate();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule, "10218");
+        assert!(issues[0].message.contains("<cn>"));
+    }
+
+    #[test]
+    pub fn test_species_quantity_kind() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model id="model">
+                    <listOfCompartments>
+                        <compartment id="c" spatialDimensions="3" size="2.5" constant="true"/>
+                        <compartment id="d" spatialDimensions="3" constant="true"/>
+                    </listOfCompartments>
+                    <listOfSpecies>
+                        <species id="a" compartment="c" initialAmount="3" hasOnlySubstanceUnits="true"
+                            boundaryCondition="false" constant="false"/>
+                        <species id="b" compartment="c" initialConcentration="2" hasOnlySubstanceUnits="false"
+                            boundaryCondition="false" constant="false"/>
+                        <species id="x" compartment="d" initialConcentration="2" hasOnlySubstanceUnits="true"
+                            boundaryCondition="false" constant="false"/>
+                    </listOfSpecies>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+        let model = doc.model().get().unwrap();
+        let species = model.species().get().unwrap();
+        let (a, b, x) = (species.get(0), species.get(1), species.get(2));
+
+        assert_eq!(a.quantity_kind(), QuantityKind::Amount);
+        assert!(b.is_concentration_based());
+        assert!(!x.is_concentration_based());
+        assert_eq!(a.effective_initial_amount(&model), Some(3.0));
+        assert_eq!(b.effective_initial_amount(&model), Some(5.0));
+        // The compartment of `x` has no size.
+        assert_eq!(x.effective_initial_amount(&model), None);
+    }
+
+    #[test]
+    pub fn test_custom_rule() {
+        struct ParameterPrefix;
+
+        impl CustomRule for ParameterPrefix {
+            fn check(&self, model: &Model, issues: &mut Vec<SbmlIssue>) {
+                let Some(parameters) = model.parameters().get() else {
+                    return;
+                };
+                for parameter in parameters.iter() {
+                    if !parameter.id().get().starts_with("p_") {
+                        let message = "Parameter id must start with `p_`.".to_string();
+                        issues.push(SbmlIssue::new_warning("test:prefix", &parameter, message));
+                    }
+                }
+            }
+        }
+
+        let doc = Sbml::default();
+        let model = doc.model().get_or_create();
+        let parameters = model.parameters().get_or_create();
+        parameters.push(Parameter::new(model.document(), &"p_ok".to_string(), true));
+        parameters.push(Parameter::new(model.document(), &"bad".to_string(), true));
+
+        let rules: Vec<Box<dyn CustomRule>> = vec![Box::new(ParameterPrefix)];
+        assert!(doc.validate().is_empty());
+        let issues = doc.validate_with_custom(&rules);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule, "test:prefix");
+        assert_eq!(issues[0].element, parameters.get(1).raw_element());
+    }
+
+    #[test]
+    pub fn test_species_constant_and_boundary() {
+        let xml = |constant: &str, boundary: &str, rule: &str| {
+            format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+                <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                    <model id="model">
+                        <listOfCompartments>
+                            <compartment id="c" constant="true"/>
+                        </listOfCompartments>
+                        <listOfSpecies>
+                            <species id="s" compartment="c" hasOnlySubstanceUnits="false"
+                                boundaryCondition="{boundary}" constant="{constant}"/>
+                        </listOfSpecies>
+                        <listOfRules>
+                            <{rule} variable="s">
+                                <math xmlns="http://www.w3.org/1998/Math/MathML"><cn>1</cn></math>
+                            </{rule}>
+                        </listOfRules>
+                        <listOfReactions>
+                            <reaction id="r" reversible="false">
+                                <listOfReactants>
+                                    <speciesReference species="s" constant="true"/>
+                                </listOfReactants>
+                            </reaction>
+                        </listOfReactions>
+                    </model>
+                </sbml>"#
+            )
+        };
+        let rules = |constant: &str, boundary: &str, rule: &str| {
+            let doc = Sbml::read_str(&xml(constant, boundary, rule)).unwrap();
+            let mut rules = doc
+                .validate()
+                .into_iter()
+                .map(|it| it.rule)
+                .collect::<Vec<_>>();
+            rules.sort();
+            rules
+        };
+
+        assert!(rules("false", "true", "rateRule").is_empty());
+        assert_eq!(rules("false", "false", "rateRule"), vec!["20610"]);
+        assert_eq!(rules("true", "true", "assignmentRule"), vec!["20903"]);
+        assert_eq!(rules("true", "false", "rateRule"), vec!["20611", "20904"]);
+    }
+
+    #[test]
+    pub fn test_sbml_root_identifiers() {
+        let xml = |model_meta_id: &str| {
+            format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+                <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2"
+                    id="model_id" metaid="meta_root">
+                    <model id="model_id" metaid="{model_meta_id}"/>
+                </sbml>"#
+            )
+        };
+
+        // The `id` of the root is not part of the model SId namespace.
+        let doc = Sbml::read_str(&xml("meta_model")).unwrap();
+        assert!(doc.validate().is_empty());
+
+        // But the `metaid` must be unique in the whole document.
+        let doc = Sbml::read_str(&xml("meta_root")).unwrap();
+        let issues = doc.validate();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule, "10307");
+    }
+
+    #[test]
+    pub fn test_ode_system() {
+        fn expression(element: &XmlElement) -> String {
+            let name = element.tag_name();
+            if name == "ci" || name == "cn" {
+                return element.text_content().trim().to_string();
+            }
+            let children = element.child_elements();
+            if children.is_empty() {
+                return name;
+            }
+            let children = children.iter().map(expression).collect::<Vec<_>>();
+            if name == "math" {
+                children.join(" ")
+            } else {
+                format!("({})", children.join(" "))
+            }
+        }
+
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model id="model">
+                    <listOfCompartments>
+                        <compartment id="c" size="2" constant="true"/>
+                    </listOfCompartments>
+                    <listOfSpecies>
+                        <species id="a" compartment="c" initialConcentration="3"
+                            hasOnlySubstanceUnits="false" boundaryCondition="false" constant="false"/>
+                        <species id="b" compartment="c" initialAmount="1"
+                            hasOnlySubstanceUnits="true" boundaryCondition="false" constant="false"/>
+                        <species id="x" compartment="c" initialAmount="1"
+                            hasOnlySubstanceUnits="true" boundaryCondition="true" constant="false"/>
+                    </listOfSpecies>
+                    <listOfParameters>
+                        <parameter id="p" value="5" constant="false"/>
+                    </listOfParameters>
+                    <listOfRules>
+                        <rateRule variable="p">
+                            <math xmlns="http://www.w3.org/1998/Math/MathML"><ci>b</ci></math>
+                        </rateRule>
+                    </listOfRules>
+                    <listOfReactions>
+                        <reaction id="r" reversible="false">
+                            <listOfReactants>
+                                <speciesReference species="a" stoichiometry="2" constant="true"/>
+                            </listOfReactants>
+                            <listOfProducts>
+                                <speciesReference species="b" constant="true"/>
+                            </listOfProducts>
+                            <kineticLaw>
+                                <math xmlns="http://www.w3.org/1998/Math/MathML">
+                                    <apply><times/><ci>k</ci><ci>a</ci></apply>
+                                </math>
+                                <listOfLocalParameters>
+                                    <localParameter id="k" value="0.5"/>
+                                </listOfLocalParameters>
+                            </kineticLaw>
+                        </reaction>
+                    </listOfReactions>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+        let model = doc.model().get().unwrap();
+        let system = model.to_ode_system();
+
+        let variables = system.equations.iter().map(|(id, _)| id.as_str());
+        assert_eq!(variables.collect::<Vec<_>>(), vec!["a", "b", "p"]);
+        let a = system.equation("a").unwrap();
+        assert_eq!(
+            expression(a.xml_element()),
+            "(divide (times -2 (times r.k a)) c)"
+        );
+        let b = system.equation("b").unwrap();
+        assert_eq!(expression(b.xml_element()), "(times 1 (times r.k a))");
+        let p = system.equation("p").unwrap();
+        assert_eq!(expression(p.xml_element()), "b");
+        assert_eq!(a.namespace_url(), URL_MATHML);
+        assert_eq!(a.child_elements()[0].namespace_url(), URL_MATHML);
+
+        assert_eq!(system.environment.get("c"), Some(&2.0));
+        assert_eq!(system.environment.get("a"), Some(&3.0));
+        assert_eq!(system.environment.get("b"), Some(&1.0));
+        assert_eq!(system.environment.get("r.k"), Some(&0.5));
+        assert_eq!(system.environment.get("p"), Some(&5.0));
+
+        // The original model is not modified.
+        assert!(doc.validate().is_empty());
+    }
+
+    #[test]
+    pub fn test_rdf_about() {
+        // The BioModels files follow the `rdf:about="#<metaid>"` convention.
+        let doc = Sbml::read_path("test-inputs/Mukandavire2020.xml").unwrap();
+        assert!(doc.validate().iter().all(|it| it.rule != "lint-rdf-about"));
+
+        let xml = |meta_id: &str, about: &str| {
+            format!(
+                r##"<?xml version="1.0" encoding="UTF-8"?>
+                <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                    <model id="model" {meta_id}>
+                        <annotation>
+                            <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+                                <rdf:Description rdf:about="{about}"/>
+                            </rdf:RDF>
+                        </annotation>
+                    </model>
+                </sbml>"##
+            )
+        };
+
+        let doc = Sbml::read_str(&xml(r#"metaid="m1""#, "#m1")).unwrap();
+        assert!(doc.validate().is_empty());
+
+        let doc = Sbml::read_str(&xml(r#"metaid="m1""#, "#m2")).unwrap();
+        let issues = doc.validate();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule, "lint-rdf-about");
+        assert_eq!(issues[0].severity, SbmlIssueSeverity::Warning);
+        assert!(issues[0].message.contains("#m2"));
+
+        let doc = Sbml::read_str(&xml("", "#m1")).unwrap();
+        let issues = doc.validate();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule, "lint-rdf-about");
+        assert!(issues[0].message.contains("no [metaid]"));
+    }
+
+    #[test]
+    pub fn test_irreversible_rate_law() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model>
+                    <listOfCompartments>
+                        <compartment id="c" size="1" constant="true"/>
+                    </listOfCompartments>
+                    <listOfSpecies>
+                        <species id="a" compartment="c" hasOnlySubstanceUnits="false"
+                            boundaryCondition="false" constant="false"/>
+                    </listOfSpecies>
+                    <listOfReactions>
+                        <reaction id="r1" reversible="false">
+                            <listOfReactants>
+                                <speciesReference species="a" constant="true"/>
+                            </listOfReactants>
+                            <kineticLaw>
+                                <math xmlns="http://www.w3.org/1998/Math/MathML">
+                                    <apply><minus/><ci>a</ci><cn>1</cn></apply>
+                                </math>
+                            </kineticLaw>
+                        </reaction>
+                        <reaction id="r2" reversible="true">
+                            <listOfReactants>
+                                <speciesReference species="a" constant="true"/>
+                            </listOfReactants>
+                            <kineticLaw>
+                                <math xmlns="http://www.w3.org/1998/Math/MathML">
+                                    <apply><minus/><ci>a</ci><cn>1</cn></apply>
+                                </math>
+                            </kineticLaw>
+                        </reaction>
+                    </listOfReactions>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        let issues = doc.validate();
+        let heuristic = issues
+            .iter()
+            .filter(|it| it.rule == "lint-irreversible-rate-law")
+            .collect::<Vec<_>>();
+        assert_eq!(heuristic.len(), 1);
+        assert_eq!(heuristic[0].severity, SbmlIssueSeverity::Info);
+        assert!(heuristic[0].message.contains("r1"));
+    }
+
+    #[test]
+    pub fn test_non_finite_values() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model>
+                    <listOfCompartments>
+                        <compartment id="c" size="INF" constant="true"/>
+                    </listOfCompartments>
+                    <listOfParameters>
+                        <parameter id="p" value="NaN" constant="true"/>
+                        <parameter id="q" value="1.5" constant="true"/>
+                    </listOfParameters>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        // The severity depends on the attribute: an infinite compartment size is an error,
+        // while a NaN parameter value is only suspicious.
+        let lints = doc
+            .validate()
+            .into_iter()
+            .filter(|it| it.rule == "lint-non-finite-value")
+            .collect::<Vec<_>>();
+        assert_eq!(lints.len(), 2);
+        assert_eq!(lints[0].severity, SbmlIssueSeverity::Error);
+        assert_eq!(lints[1].severity, SbmlIssueSeverity::Warning);
+        assert!(lints[0].message.contains("[size]"));
+        assert!(lints[1].message.contains("[value]"));
+    }
+
+    #[test]
+    pub fn test_model_definitions() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2"
+                xmlns:comp="http://www.sbml.org/sbml/level3/version1/comp/version1"
+                comp:required="true">
+                <model id="main"/>
+                <comp:listOfModelDefinitions>
+                    <comp:modelDefinition id="first">
+                        <listOfParameters>
+                            <parameter id="p" constant="true"/>
+                        </listOfParameters>
+                    </comp:modelDefinition>
+                    <comp:modelDefinition id="second"/>
+                </comp:listOfModelDefinitions>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        let definitions = doc.model_definitions();
+        let ids = definitions.iter().map(|it| it.id().get().unwrap());
+        assert_eq!(ids.collect::<Vec<_>>(), vec!["first", "second"]);
+
+        let first = doc.model_definition_by_id("first").unwrap();
+        assert_eq!(first.parameter_identifiers(), vec!["p".to_string()]);
+        assert!(doc.model_definition_by_id("main").is_none());
+        assert!(Sbml::default().model_definitions().is_empty());
+    }
+
+    #[test]
+    pub fn test_comp_replacements() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2"
+                xmlns:comp="http://www.sbml.org/sbml/level3/version1/comp/version1"
+                comp:required="true">
+                <model id="main">
+                    <listOfParameters>
+                        <parameter id="ok" constant="true">
+                            <comp:listOfReplacedElements>
+                                <comp:replacedElement comp:submodelRef="A" comp:portRef="x_port"/>
+                            </comp:listOfReplacedElements>
+                        </parameter>
+                        <parameter id="no_submodel" constant="true">
+                            <comp:listOfReplacedElements>
+                                <comp:replacedElement comp:submodelRef="B" comp:idRef="x"/>
+                            </comp:listOfReplacedElements>
+                        </parameter>
+                        <parameter id="no_target" constant="true">
+                            <comp:listOfReplacedElements>
+                                <comp:replacedElement comp:submodelRef="A" comp:idRef="y"/>
+                            </comp:listOfReplacedElements>
+                        </parameter>
+                        <parameter id="both" constant="true">
+                            <comp:listOfReplacedElements>
+                                <comp:replacedElement comp:submodelRef="A" comp:portRef="y_port"/>
+                            </comp:listOfReplacedElements>
+                            <comp:replacedBy comp:submodelRef="A" comp:idRef="x"/>
+                        </parameter>
+                        <parameter id="no_replacing_submodel" constant="true">
+                            <comp:replacedBy comp:submodelRef="C" comp:idRef="x"/>
+                        </parameter>
+                    </listOfParameters>
+                    <comp:listOfSubmodels>
+                        <comp:submodel comp:id="A" comp:modelRef="sub"/>
+                    </comp:listOfSubmodels>
+                </model>
+                <comp:listOfModelDefinitions>
+                    <comp:modelDefinition id="sub">
+                        <listOfParameters>
+                            <parameter id="x" constant="true"/>
+                        </listOfParameters>
+                        <comp:listOfPorts>
+                            <comp:port comp:id="x_port" comp:idRef="x"/>
+                        </comp:listOfPorts>
+                    </comp:modelDefinition>
+                </comp:listOfModelDefinitions>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        let mut issues = Vec::new();
+        doc.model()
+            .get()
+            .unwrap()
+            .apply_comp_replacement_rules(&mut issues);
+        let rules = issues.iter().map(|it| it.rule.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            rules,
+            vec![
+                "comp-20701",
+                "comp-20303",
+                "comp-20302",
+                "comp-20801",
+                "lint-comp-replaced-and-replacing"
+            ]
+        );
+        assert!(issues[0].message.contains("('B')"));
+        assert!(issues[1].message.contains("('y')"));
+        assert!(issues[2].message.contains("('y_port')"));
+        assert!(issues[3].message.contains("('C')"));
+        assert!(issues[4].message.contains("both replaced"));
+    }
+
+    #[test]
+    pub fn test_flatten_comp() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2"
+                xmlns:comp="http://www.sbml.org/sbml/level3/version1/comp/version1"
+                comp:required="true">
+                <model id="main">
+                    <listOfParameters>
+                        <parameter id="k_global" value="2" constant="true">
+                            <comp:listOfReplacedElements>
+                                <comp:replacedElement comp:submodelRef="A" comp:portRef="k_port"/>
+                            </comp:listOfReplacedElements>
+                        </parameter>
+                        <parameter id="obsolete" constant="false">
+                            <comp:replacedBy comp:submodelRef="A" comp:idRef="x"/>
+                        </parameter>
+                        <parameter id="y" constant="false"/>
+                    </listOfParameters>
+                    <listOfRules>
+                        <assignmentRule variable="y">
+                            <math xmlns="http://www.w3.org/1998/Math/MathML"><ci> obsolete </ci></math>
+                        </assignmentRule>
+                    </listOfRules>
+                    <comp:listOfSubmodels>
+                        <comp:submodel comp:id="A" comp:modelRef="cell">
+                            <comp:listOfDeletions>
+                                <comp:deletion comp:idRef="junk"/>
+                            </comp:listOfDeletions>
+                        </comp:submodel>
+                        <comp:submodel comp:id="B" comp:modelRef="cell"/>
+                    </comp:listOfSubmodels>
+                </model>
+                <comp:listOfModelDefinitions>
+                    <comp:modelDefinition id="cell">
+                        <listOfFunctionDefinitions>
+                            <functionDefinition id="sq">
+                                <math xmlns="http://www.w3.org/1998/Math/MathML">
+                                    <lambda>
+                                        <bvar><ci> k </ci></bvar>
+                                        <apply><times/><ci> k </ci><ci> k </ci></apply>
+                                    </lambda>
+                                </math>
+                            </functionDefinition>
+                        </listOfFunctionDefinitions>
+                        <listOfParameters>
+                            <parameter id="k" value="1" constant="true"/>
+                            <parameter id="x" constant="false"/>
+                            <parameter id="junk" constant="true"/>
+                        </listOfParameters>
+                        <listOfRules>
+                            <assignmentRule variable="x">
+                                <math xmlns="http://www.w3.org/1998/Math/MathML">
+                                    <apply><ci> sq </ci><ci> k </ci></apply>
+                                </math>
+                            </assignmentRule>
+                        </listOfRules>
+                        <comp:listOfPorts>
+                            <comp:port comp:id="k_port" comp:idRef="k"/>
+                        </comp:listOfPorts>
+                    </comp:modelDefinition>
+                </comp:listOfModelDefinitions>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        let flat = doc.flatten_comp().unwrap();
+        let model = flat.model().get().unwrap();
+        assert_eq!(
+            model.parameter_identifiers(),
+            vec!["k_global", "y", "A__x", "B__k", "B__x", "B__junk"]
+        );
+        assert_eq!(
+            model.function_definition_identifiers(),
+            vec!["A__sq", "B__sq"]
+        );
+
+        let rules = model.rules().get().unwrap();
+        let rules = rules
+            .iter()
+            .map(|rule| {
+                let math = rule.math().get().unwrap();
+                let names = math
+                    .recursive_child_elements_filtered(|it| it.tag_name() == "ci")
+                    .iter()
+                    .map(|it| it.text_content().trim().to_string())
+                    .collect::<Vec<_>>();
+                (rule.get_attribute("variable").unwrap(), names)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rules,
+            vec![
+                ("y".to_string(), vec!["A__x".to_string()]),
+                (
+                    "A__x".to_string(),
+                    vec!["A__sq".to_string(), "k_global".to_string()]
+                ),
+                (
+                    "B__x".to_string(),
+                    vec!["B__sq".to_string(), "B__k".to_string()]
+                ),
+            ]
+        );
+
+        // The bound variable of the function definition is not renamed.
+        let function = model.function_definitions().get().unwrap().get(0);
+        let math = function.math().get().unwrap();
+        assert!(math
+            .recursive_child_elements_filtered(|it| it.tag_name() == "ci")
+            .iter()
+            .all(|it| it.text_content().trim() == "k"));
+
+        let xml = flat.to_xml_string().unwrap();
+        assert!(!xml.contains("comp"));
+        let errors = flat
+            .validate()
+            .into_iter()
+            .filter(|it| it.severity == SbmlIssueSeverity::Error)
+            .collect::<Vec<_>>();
+        assert!(errors.is_empty(), "{errors:?}");
+
+        // The original document is not modified.
+        assert_eq!(doc.model_definitions().len(), 1);
+    }
+
+    #[test]
+    pub fn test_flatten_comp_errors() {
+        let flatten = |submodel: &str| {
+            let xml = format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+                <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2"
+                    xmlns:comp="http://www.sbml.org/sbml/level3/version1/comp/version1"
+                    comp:required="true">
+                    <model id="main">
+                        <comp:listOfSubmodels>{submodel}</comp:listOfSubmodels>
+                    </model>
+                    <comp:listOfModelDefinitions>
+                        <comp:modelDefinition id="loop">
+                            <comp:listOfSubmodels>
+                                <comp:submodel comp:id="inner" comp:modelRef="loop"/>
+                            </comp:listOfSubmodels>
+                        </comp:modelDefinition>
+                        <comp:modelDefinition id="empty"/>
+                    </comp:listOfModelDefinitions>
+                </sbml>"#
+            );
+            Sbml::read_str(xml.as_str()).unwrap().flatten_comp()
+        };
+
+        let error = flatten(r#"<comp:submodel comp:id="A" comp:modelRef="loop"/>"#);
+        assert!(error.unwrap_err().contains("instantiated recursively"));
+        let error = flatten(r#"<comp:submodel comp:id="A" comp:modelRef="missing"/>"#);
+        assert!(error.unwrap_err().contains("unknown model ('missing')"));
+        let error = flatten(
+            r#"<comp:submodel comp:id="A" comp:modelRef="empty">
+                <comp:listOfDeletions><comp:deletion comp:idRef="x"/></comp:listOfDeletions>
+            </comp:submodel>"#,
+        );
+        assert!(error.unwrap_err().contains("Invalid deletion"));
+        assert!(flatten(r#"<comp:submodel comp:id="A" comp:modelRef="empty"/>"#).is_ok());
+    }
+
+    #[test]
+    pub fn test_flatten_comp_package_references() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2"
+                xmlns:comp="http://www.sbml.org/sbml/level3/version1/comp/version1"
+                xmlns:fbc="http://www.sbml.org/sbml/level3/version1/fbc/version2"
+                comp:required="true" fbc:required="false">
+                <model id="main" fbc:strict="false">
+                    <comp:listOfSubmodels>
+                        <comp:submodel comp:id="A" comp:modelRef="cell"/>
+                    </comp:listOfSubmodels>
+                </model>
+                <comp:listOfModelDefinitions>
+                    <comp:modelDefinition id="cell">
+                        <listOfParameters>
+                            <parameter id="lb" value="0" constant="true"/>
+                            <parameter id="ub" value="10" constant="true"/>
+                        </listOfParameters>
+                        <listOfReactions>
+                            <reaction id="r" reversible="false"
+                                fbc:lowerFluxBound="lb" fbc:upperFluxBound="ub"/>
+                        </listOfReactions>
+                    </comp:modelDefinition>
+                </comp:listOfModelDefinitions>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        let flat = doc.flatten_comp().unwrap();
+        let model = flat.model().get().unwrap();
+        let reaction = model.reactions().get().unwrap().get(0);
+        assert_eq!(reaction.id().get(), "A__r");
+        assert_eq!(
+            reaction.get_namespaced_attribute("lowerFluxBound", URL_PACKAGE_FBC),
+            Some("A__lb".to_string())
+        );
+        assert_eq!(
+            reaction.get_namespaced_attribute("upperFluxBound", URL_PACKAGE_FBC),
+            Some("A__ub".to_string())
+        );
+    }
+
+    #[test]
+    pub fn test_undeclared_symbols() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                <model id="model">
+                    <listOfFunctionDefinitions>
+                        <functionDefinition id="f">
+                            <math xmlns="http://www.w3.org/1998/Math/MathML">
+                                <lambda>
+                                    <bvar><ci> x </ci></bvar>
+                                    <apply><times/><ci> x </ci><ci> x </ci></apply>
+                                </lambda>
+                            </math>
+                        </functionDefinition>
+                    </listOfFunctionDefinitions>
+                    <listOfParameters>
+                        <parameter id="p" constant="true"/>
+                    </listOfParameters>
+                    <listOfReactions>
+                        <reaction id="r" reversible="false">
+                            <kineticLaw>
+                                <math xmlns="http://www.w3.org/1998/Math/MathML">
+                                    <apply>
+                                        <times/>
+                                        <ci> k </ci>
+                                        <apply><ci> f </ci><ci> p </ci></apply>
+                                        <apply><ci> g </ci><ci> missing </ci></apply>
+                                    </apply>
+                                </math>
+                                <listOfLocalParameters>
+                                    <localParameter id="k"/>
+                                </listOfLocalParameters>
+                            </kineticLaw>
+                        </reaction>
+                    </listOfReactions>
+                </model>
+            </sbml>"#,
+        )
+        .unwrap();
+
+        let model = doc.model().get().unwrap();
+        let undeclared = model
+            .undeclared_symbols()
+            .into_iter()
+            .map(|(_, id)| id)
+            .collect::<Vec<_>>();
+        assert_eq!(undeclared, vec!["g", "missing"]);
+    }
+
+    #[test]
+    pub fn test_namespace_matches_level_and_version() {
+        let doc = Sbml::read_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="1"/>"#,
+        )
+        .unwrap();
+
+        assert_eq!(doc.level_and_version(), (3, 1));
+        let issues = doc.validate();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule, "20101");
+
+        assert!(Sbml::default().validate().is_empty());
+    }
+
+    #[test]
+    pub fn test_default_document_version() {
+        // The default document is SBML Level 3 Version 2 (the namespace and the attributes
+        // must agree, see rule 20101).
+        let doc = Sbml::default();
+        assert_eq!(doc.level_and_version(), (3, 2));
+        assert_eq!(
+            doc.sbml_root.namespace_url(),
+            "http://www.sbml.org/sbml/level3/version2/core"
+        );
+        let xml = doc.to_xml_string().unwrap();
+        assert!(xml.contains(r#"xmlns="http://www.sbml.org/sbml/level3/version2/core""#));
+        assert!(xml.contains(r#"level="3""#));
+        assert!(xml.contains(r#"version="2""#));
+    }
+
+    #[test]
+    pub fn test_float_round_trip() {
+        let doc = Sbml::default();
+        let parameter = Parameter::new(doc.document(), &"p".to_string(), true);
+        for value in [
+            0.1 + 0.2,
+            1e-300,
+            -123456.789e10,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+        ] {
+            parameter.value().set_some(&value);
+            assert_eq!(parameter.value().get().unwrap().to_bits(), value.to_bits());
+        }
+        assert_eq!(parameter.value().get_raw().unwrap(), "-INF");
+
+        assert_eq!(FloatFormat::ShortestRoundTrip.format(0.5), "0.5");
+        assert_eq!(FloatFormat::FixedPrecision(3).format(1.0 / 3.0), "0.333");
+        assert_eq!(FloatFormat::FixedPrecision(3).format(f64::NAN), "NaN");
+
+        // The format is chosen for each write, so other values still round-trip exactly.
+        let other = Parameter::new(doc.document(), &"q".to_string(), true);
+        parameter
+            .value()
+            .set_float(1.0 / 3.0, FloatFormat::FixedPrecision(3));
+        other.value().set_some(&(1.0 / 3.0));
+        assert_eq!(parameter.value().get_raw().unwrap(), "0.333");
+        assert_eq!(
+            other.value().get().unwrap().to_bits(),
+            (1.0f64 / 3.0).to_bits()
+        );
+        parameter.value().set_some(&(1.0 / 3.0));
+        assert_eq!(parameter.value().get(), other.value().get());
+    }
+
+    #[test]
+    pub fn test_assignment_rule_evaluation_order() {
+        fn rules_doc(rules: &str) -> Sbml {
+            let xml = format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+                <sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+                    <model id="model"><listOfRules>{rules}</listOfRules></model>
+                </sbml>"#
+            );
+            Sbml::read_str(xml.as_str()).unwrap()
+        }
+        fn rule(variable: &str, ci: &str) -> String {
+            format!(
+                r#"<assignmentRule variable="{variable}">
+                    <math xmlns="http://www.w3.org/1998/Math/MathML"><ci>{ci}</ci></math>
+                </assignmentRule>"#
+            )
+        }
+
+        let doc = rules_doc(&[rule("a", "b"), rule("b", "p"), rule("c", "a")].concat());
+        let order = doc
+            .model()
+            .get()
+            .unwrap()
+            .assignment_rule_evaluation_order()
+            .unwrap()
+            .into_iter()
+            .map(|it| it.variable().get())
+            .collect::<Vec<_>>();
+        assert_eq!(order, vec!["b", "a", "c"]);
+
+        let doc = rules_doc(&[rule("a", "b"), rule("b", "a")].concat());
+        let error = doc
+            .model()
+            .get()
+            .unwrap()
+            .assignment_rule_evaluation_order()
+            .unwrap_err();
+        assert!(error.contains("a -> b -> a"));
+    }
+
     #[test]
     pub fn test_constraints() {
         let doc = Sbml::read_path("test-inputs/Mukandavire2020.xml").unwrap();
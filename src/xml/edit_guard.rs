@@ -0,0 +1,151 @@
+use crate::constants::namespaces::NS_SBML_CORE;
+use crate::core::sbase::SBase;
+use crate::xml::xml_wrapper::attach_raw;
+use crate::xml::{XmlDocument, XmlElement, XmlProperty, XmlPropertyType, XmlWrapper};
+use biodivine_xml_doc::{Document, Element};
+use std::cell::RefCell;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, RwLockWriteGuard};
+
+/// An [EditGuard] holds the write lock of an [XmlDocument] and provides the basic read/write
+/// operations of [XmlProperty] objects without acquiring the lock again for each operation
+/// (see [crate::Sbml::edit]).
+///
+/// # Deadlocks
+///
+/// The document lock is *not* reentrant. While the guard exists, any operation which locks the
+/// same document by itself will block forever. This includes the methods of [XmlProperty]
+/// (e.g. `property.get()`), creating new elements (e.g. `Parameter::new`; use
+/// [EditGuard::new_element] instead), reading or writing children (use [EditGuard::attach]
+/// instead), and also [crate::Sbml::validate]. Inside the guarded closure, the document
+/// should be only accessed through the methods of the [EditGuard].
+pub struct EditGuard<'a> {
+    document: &'a XmlDocument,
+    guard: RefCell<RwLockWriteGuard<'a, Document>>,
+}
+
+impl<'a> EditGuard<'a> {
+    /// Acquire the write lock of the given `document`.
+    pub(crate) fn new(document: &'a XmlDocument) -> EditGuard<'a> {
+        let guard = document
+            .write()
+            .expect("Underlying document lock is corrupted. Cannot recover.");
+        EditGuard {
+            document,
+            guard: RefCell::new(guard),
+        }
+    }
+
+    /// Read the "raw" value of the given `property` (see [XmlProperty::get_raw]).
+    pub fn get_raw<T: XmlPropertyType, P: XmlProperty<T>>(&self, property: &P) -> Option<String> {
+        let element = self.checked_element(property.element());
+        let doc = self.guard.borrow();
+        element
+            .attribute(doc.deref(), property.name())
+            .map(|it| it.to_string())
+    }
+
+    /// Read the value of the given `property` (see [XmlProperty::get_checked]).
+    pub fn get_checked<T: XmlPropertyType, P: XmlProperty<T>>(
+        &self,
+        property: &P,
+    ) -> Result<Option<T>, String> {
+        let element = self.checked_element(property.element());
+        let doc = self.guard.borrow();
+        XmlPropertyType::try_get(element.attribute(doc.deref(), property.name()))
+    }
+
+    /// Write the value of the given `property`. A `None` value (or a value which is represented
+    /// by a missing attribute) removes the attribute, as in
+    /// [OptionalXmlProperty::set](crate::xml::OptionalXmlProperty::set).
+    pub fn set<T: XmlPropertyType, P: XmlProperty<T>>(&self, property: &P, value: Option<&T>) {
+        match value.and_then(|it| it.set()) {
+            None => self.clear(property),
+            Some(value) => self.set_raw(property, value),
+        }
+    }
+
+    /// Write a raw `value` into the given `property` (see [XmlProperty::set_raw]).
+    pub fn set_raw<T: XmlPropertyType, P: XmlProperty<T>>(&self, property: &P, value: String) {
+        let element = self.checked_element(property.element());
+        let mut doc = self.guard.borrow_mut();
+        element.set_attribute(doc.deref_mut(), property.name(), value);
+    }
+
+    /// Remove the underlying attribute of the given `property` (see [XmlProperty::clear]).
+    pub fn clear<T: XmlPropertyType, P: XmlProperty<T>>(&self, property: &P) {
+        let element = self.checked_element(property.element());
+        let mut doc = self.guard.borrow_mut();
+        element
+            .mut_attributes(doc.deref_mut())
+            .remove(property.name());
+    }
+
+    /// Read the text content of the given `element`.
+    pub fn text_content<E: XmlWrapper>(&self, element: &E) -> String {
+        let element = self.checked_element(element.xml_element());
+        let doc = self.guard.borrow();
+        element.text_content(doc.deref()).to_string()
+    }
+
+    /// Replace the content of the given `element` with the given `text`.
+    pub fn set_text_content<E: XmlWrapper>(&self, element: &E, text: &str) {
+        let element = self.checked_element(element.xml_element());
+        let mut doc = self.guard.borrow_mut();
+        element.set_text_content(doc.deref_mut(), text);
+    }
+
+    /// Create a new detached element with the given `name` and `namespace` (prefix and URL)
+    /// in the locked document (see [XmlElement::new_quantified]).
+    pub fn new_xml_element(&self, name: &str, namespace: (&str, &str)) -> XmlElement {
+        let mut doc = self.guard.borrow_mut();
+        let element = Element::build(name)
+            .prefix(namespace.0)
+            .namespace_decl(namespace.0, namespace.1)
+            .finish(doc.deref_mut());
+        XmlElement::new_raw(self.document.clone(), element)
+    }
+
+    /// Create a new detached SBML element of type `T` with the given `tag_name` in the locked
+    /// document. Its properties can be then set through this guard and the element can be
+    /// inserted into the document using [EditGuard::attach].
+    ///
+    /// The caller is responsible for choosing a `tag_name` which matches the type `T`
+    /// (e.g. `"parameter"` for [crate::core::Parameter]).
+    pub fn new_element<T: SBase>(&self, tag_name: &str) -> T {
+        let element = self.new_xml_element(tag_name, NS_SBML_CORE);
+        unsafe { T::unchecked_cast(element) }
+    }
+
+    /// Attach the detached `child` into `parent` as a new child at the given `position`, or as
+    /// the last child if `position` is `None` (see [XmlWrapper::try_attach_at]).
+    ///
+    /// ### Errors
+    ///  - If `child` is not detached.
+    ///  - If `position > parent.children().len()`.
+    pub fn attach<C: XmlWrapper, P: XmlWrapper>(
+        &self,
+        child: &C,
+        parent: &P,
+        position: Option<usize>,
+    ) -> Result<(), String> {
+        let child = self.checked_element(child.xml_element());
+        let parent = self.checked_element(parent.xml_element());
+        let mut doc = self.guard.borrow_mut();
+        attach_raw(doc.deref_mut(), child, parent, position)
+    }
+
+    /// Returns the raw element of `element`, provided it belongs to the locked document.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element belongs to a different document, since its raw reference
+    /// would be meaningless in the locked document.
+    fn checked_element(&self, element: &XmlElement) -> Element {
+        assert!(
+            Arc::ptr_eq(&element.document, self.document),
+            "The element does not belong to the edited document."
+        );
+        element.raw_element()
+    }
+}
@@ -91,9 +91,51 @@ impl XmlPropertyType for u32 {
     }
 }
 
+/// Determines how `f64` values are written into XML attributes.
+///
+/// With [FloatFormat::ShortestRoundTrip] (the default), the shortest decimal representation
+/// that parses back to the *identical* `f64` is written. Hence, writing a value and reading
+/// it back (including through [crate::Sbml::to_xml_string] and [crate::Sbml::read_str]) is
+/// guaranteed to be bit-exact. [FloatFormat::FixedPrecision] writes the given number
+/// of decimal places, which is easier to read, but can lose precision.
+///
+/// The `set` methods of `f64` properties always use [FloatFormat::ShortestRoundTrip]. A different
+/// format can be chosen for each individual write using [XmlProperty::set_float].
+///
+/// [XmlProperty::set_float]: crate::xml::XmlProperty::set_float
+///
+/// Infinite values and NaN are always written as `INF`, `-INF` and `NaN`, as required by
+/// the specification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FloatFormat {
+    #[default]
+    ShortestRoundTrip,
+    FixedPrecision(usize),
+}
+
+impl FloatFormat {
+    /// Write the given `value` using this format.
+    pub fn format(&self, value: f64) -> String {
+        if value.is_nan() {
+            "NaN".to_string()
+        } else if value == f64::INFINITY {
+            "INF".to_string()
+        } else if value == f64::NEG_INFINITY {
+            "-INF".to_string()
+        } else {
+            match self {
+                FloatFormat::ShortestRoundTrip => format!("{}", value),
+                FloatFormat::FixedPrecision(precision) => format!("{:.*}", precision, value),
+            }
+        }
+    }
+}
+
 /// A "trivial" conversion between an XML attribute and a `f64` floating-point number (`double`
 /// type in the SBML specification). Missing attribute value is interpreted as an error.
 ///
+/// Values are written using [FloatFormat::ShortestRoundTrip].
+///
 /// ## Specification
 ///  - Section 3.1.5
 impl XmlPropertyType for f64 {
@@ -111,6 +153,6 @@ impl XmlPropertyType for f64 {
     }
 
     fn set(&self) -> Option<String> {
-        Some(format!("{}", self))
+        Some(FloatFormat::ShortestRoundTrip.format(*self))
     }
 }
@@ -35,6 +35,10 @@ mod impl_xml_property_type;
 /// hierarchies of [XmlWrapper] types.
 mod xml_inheritance;
 
+/// Implementation of the [EditGuard] struct.
+mod edit_guard;
+
+pub use crate::xml::edit_guard::EditGuard;
 pub use crate::xml::impl_xml_child::{
     Child, DynamicChild, OptionalChild, OptionalDynamicChild, RequiredChild, RequiredDynamicChild,
 };
@@ -42,6 +46,7 @@ pub use crate::xml::impl_xml_property::{
     DynamicProperty, OptionalDynamicProperty, OptionalProperty, Property, RequiredDynamicProperty,
     RequiredProperty,
 };
+pub use crate::xml::impl_xml_property_type::FloatFormat;
 pub use crate::xml::xml_child::{OptionalXmlChild, RequiredXmlChild, XmlChild, XmlChildDefault};
 pub use crate::xml::xml_element::XmlElement;
 pub use crate::xml::xml_inheritance::{XmlNamedSubtype, XmlSubtype, XmlSupertype};
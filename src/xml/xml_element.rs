@@ -1,7 +1,8 @@
 use crate::xml::XmlDocument;
 use crate::xml::XmlWrapper;
-use biodivine_xml_doc::Element;
-use std::ops::DerefMut;
+use biodivine_xml_doc::{Document, Element, Node};
+use std::collections::{HashMap, HashSet};
+use std::ops::{Deref, DerefMut};
 use std::sync::Arc;
 
 /// An [XmlElement] maintains a single thread-safe reference to an [Element] of a [biodivine_xml_doc::Document].
@@ -51,6 +52,62 @@ impl XmlElement {
         };
         XmlElement::new_raw(document, element)
     }
+
+    /// Create a deep copy of this element (including its attributes, namespace declarations,
+    /// text and child elements) in the `target` document. The copy is created in a "detached"
+    /// state and can be then inserted anywhere into `target`.
+    ///
+    /// Namespaces which are declared by the ancestors of this element (but used within its
+    /// subtree) are re-declared on the copy, such that the copy is valid on its own. Redundant
+    /// declarations are cleaned up once the copy is attached using
+    /// [XmlWrapper::try_attach_at].
+    ///
+    /// The `target` can be also the document of this element, in which case a detached
+    /// duplicate of this element is created.
+    pub fn import_into(&self, target: XmlDocument) -> XmlElement {
+        let snapshot = {
+            let doc = self.read_doc();
+            let mut snapshot = NodeSnapshot::of_subtree(doc.deref(), self.element);
+            if let Some((_, NodeSnapshot::Element { namespaces, .. })) = snapshot.first_mut() {
+                for prefix in collect_external_prefixes(doc.deref(), self.element) {
+                    if let Some(url) = self.element.namespace_for_prefix(doc.deref(), &prefix) {
+                        let url = url.to_string();
+                        namespaces.entry(prefix).or_insert(url);
+                    }
+                }
+            }
+            snapshot
+        };
+        let element = {
+            let mut doc = target.write().unwrap();
+            NodeSnapshot::build_subtree(snapshot, doc.deref_mut())
+        };
+        XmlElement::new_raw(target, element)
+    }
+
+    /// Move this element into the namespace given by `url`.
+    ///
+    /// The element prefix is removed and `url` is declared as its default namespace. As a
+    /// consequence, all unprefixed descendants which inherited the original default
+    /// namespace are moved into the new namespace as well. Descendants with an explicit
+    /// prefix keep their namespace.
+    pub fn set_namespace(&self, url: &str) {
+        let mut doc = self.write_doc();
+        self.element.set_prefix(doc.deref_mut(), "");
+        self.element.set_namespace_decl(doc.deref_mut(), "", url);
+    }
+
+    /// Same as [XmlWrapper::text_content], but without leading and trailing whitespace
+    /// (e.g. `S1` for `<ci> S1 </ci>`).
+    pub fn text_content_trimmed(&self) -> String {
+        self.text_content().trim().to_string()
+    }
+
+    /// Parse the [trimmed](XmlElement::text_content_trimmed) text content of this element
+    /// as a floating point number. Returns `None` if the content is not a valid number.
+    pub fn text_as_f64(&self) -> Option<f64> {
+        self.text_content().trim().parse().ok()
+    }
 }
 
 /// Every [XmlElement] trivially implements [XmlWrapper] as well.
@@ -63,3 +120,110 @@ impl XmlWrapper for XmlElement {
         element.xml_element().clone()
     }
 }
+
+/// Collect namespace prefixes which are used by the element and attribute names in the subtree
+/// of `element`, but not declared within the subtree itself.
+///
+/// This is similar to [Element::collect_external_namespace_decls], but it also considers
+/// prefixed attributes (e.g. `qual:id`). The subtree is traversed using an explicit stack,
+/// such that deeply nested elements cannot overflow the call stack.
+fn collect_external_prefixes(doc: &Document, element: Element) -> HashSet<String> {
+    let mut result = HashSet::new();
+    let mut stack = vec![(element, HashSet::new())];
+    while let Some((element, mut declared)) = stack.pop() {
+        declared.extend(element.namespace_decls(doc).keys().cloned());
+        let attribute_prefixes = element
+            .attributes(doc)
+            .keys()
+            .filter_map(|name| name.split_once(':').map(|(prefix, _)| prefix));
+        for prefix in attribute_prefixes.chain([element.prefix(doc)]) {
+            if prefix != "xml" && prefix != "xmlns" && !declared.contains(prefix) {
+                result.insert(prefix.to_string());
+            }
+        }
+        for child in element.child_elements(doc) {
+            stack.push((child, declared.clone()));
+        }
+    }
+    result
+}
+
+/// Create an owned copy of a single `node` (the children of an element are not copied).
+fn copy_node(node: &Node) -> Node {
+    match node {
+        Node::Element(element) => Node::Element(*element),
+        Node::Text(text) => Node::Text(text.clone()),
+        Node::Comment(text) => Node::Comment(text.clone()),
+        Node::CData(text) => Node::CData(text.clone()),
+        Node::PI(text) => Node::PI(text.clone()),
+        Node::DocType(text) => Node::DocType(text.clone()),
+    }
+}
+
+/// An owned copy of a single XML node, used to transfer content between documents
+/// (see [XmlElement::import_into]).
+enum NodeSnapshot {
+    Element {
+        full_name: String,
+        attributes: HashMap<String, String>,
+        namespaces: HashMap<String, String>,
+    },
+    Other(Node),
+}
+
+impl NodeSnapshot {
+    /// Copy the subtree of `element` into a list of nodes in document order (pre-order),
+    /// where each node refers to the index of its parent. The first node is `element` itself.
+    ///
+    /// The subtree is traversed using an explicit stack, such that deeply nested elements
+    /// cannot overflow the call stack.
+    fn of_subtree(doc: &Document, element: Element) -> Vec<(Option<usize>, NodeSnapshot)> {
+        let mut result = Vec::new();
+        let mut stack = vec![(None, Node::Element(element))];
+        while let Some((parent, node)) = stack.pop() {
+            let snapshot = match node {
+                Node::Element(element) => {
+                    let index = Some(result.len());
+                    let children = element.children(doc).iter().rev();
+                    stack.extend(children.map(|child| (index, copy_node(child))));
+                    NodeSnapshot::Element {
+                        full_name: element.full_name(doc).to_string(),
+                        attributes: element.attributes(doc).clone(),
+                        namespaces: element.namespace_decls(doc).clone(),
+                    }
+                }
+                other => NodeSnapshot::Other(other),
+            };
+            result.push((parent, snapshot));
+        }
+        result
+    }
+
+    /// Create the nodes of a subtree snapshot (see [NodeSnapshot::of_subtree]) in `doc` and
+    /// return the (detached) root element.
+    fn build_subtree(nodes: Vec<(Option<usize>, NodeSnapshot)>, doc: &mut Document) -> Element {
+        let mut elements: Vec<Option<Element>> = Vec::with_capacity(nodes.len());
+        for (parent, snapshot) in nodes {
+            let (node, element) = match snapshot {
+                NodeSnapshot::Other(node) => (node, None),
+                NodeSnapshot::Element {
+                    full_name,
+                    attributes,
+                    namespaces,
+                } => {
+                    let element = Element::new(doc, full_name);
+                    *element.mut_attributes(doc) = attributes;
+                    *element.mut_namespace_decls(doc) = namespaces;
+                    (Node::Element(element), Some(element))
+                }
+            };
+            if let Some(parent) = parent.and_then(|it| elements[it]) {
+                parent
+                    .push_child(doc, node)
+                    .expect("A newly created node is always detached.");
+            }
+            elements.push(element);
+        }
+        elements[0].expect("The snapshot of an element is always an element.")
+    }
+}
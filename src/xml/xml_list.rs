@@ -1,5 +1,6 @@
 use crate::core::SBase;
 use crate::xml::{XmlElement, XmlWrapper};
+use std::collections::HashSet;
 use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
 
@@ -105,10 +106,17 @@ impl<Type: XmlWrapper> XmlList<Type> {
     /// # Panics
     ///
     /// Panics if `index > len`, or when `value` cannot be attached to the list tag
-    /// (it already has a parent, or is itself the root container tag).
+    /// (see [XmlList::try_insert]).
     pub fn insert(&self, index: usize, value: Type) {
+        self.try_insert(index, value).unwrap()
+    }
+
+    /// Insert a new element into the list, or return an error if the element cannot be
+    /// attached to the list tag: it already has a parent, is itself the root container tag,
+    /// or belongs to a different document (see [XmlWrapper::same_document]).
+    pub fn try_insert(&self, index: usize, value: Type) -> Result<(), String> {
         let index = self.remap_index(index).unwrap_or(self.len());
-        value.try_attach_at(self, Some(index)).unwrap();
+        value.try_attach_at(self, Some(index))
     }
 
     /// Remove an element at the given position and return the removed value.
@@ -136,15 +144,85 @@ impl<Type: XmlWrapper> XmlList<Type> {
         }
     }
 
+    /// Swap the elements at positions `i` and `j`. The remaining content of the list tag
+    /// (e.g. whitespace between elements) keeps its position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i >= len` or `j >= len`.
+    pub fn swap(&self, i: usize, j: usize) {
+        if i == j {
+            assert!(i < self.len(), "Item at position {i} does not exist.");
+            return;
+        }
+        let (i, j) = (i.min(j), i.max(j));
+        let Some(inside_i) = self.remap_index(i) else {
+            panic!("Item at position {i} does not exist.");
+        };
+        let Some(inside_j) = self.remap_index(j) else {
+            panic!("Item at position {j} does not exist.");
+        };
+
+        let mut doc = self.write_doc();
+        let element = self.raw_element();
+        // Remove the later node first, so that `inside_i` stays valid.
+        let node_j = element.remove_child(doc.deref_mut(), inside_j);
+        let node_i = element.remove_child(doc.deref_mut(), inside_i);
+        // The unwraps are safe, because we are just putting the nodes back into the same parent.
+        element
+            .insert_child(doc.deref_mut(), inside_i, node_j)
+            .unwrap();
+        element
+            .insert_child(doc.deref_mut(), inside_j, node_i)
+            .unwrap();
+    }
+
+    /// Move the element at position `from` such that it ends up at position `to`, shifting
+    /// the elements in between accordingly.
+    ///
+    /// Both positions are checked before the list is modified, and the element is moved
+    /// while holding a single document lock, i.e. the list is never observed with the element
+    /// missing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `from >= len` or `to >= len`. In that case, the list is left unchanged.
+    pub fn move_to(&self, from: usize, to: usize) {
+        let Some(inside_from) = self.remap_index(from) else {
+            panic!("Item at position {from} does not exist.");
+        };
+        let Some(inside_to) = self.remap_index(to) else {
+            let len = self.len();
+            panic!("Position {to} is out of bounds for length {len}.");
+        };
+
+        // Moving forward, the element is inserted right after the element currently at `to`,
+        // whose index decreases by one once the moved element is removed. Moving backward,
+        // it is inserted right before the element at `to`, whose index does not change.
+        // In both cases, the moved element ends up at `inside_to`.
+        let mut doc = self.write_doc();
+        let element = self.raw_element();
+        let node = element.remove_child(doc.deref_mut(), inside_from);
+        // The unwrap is safe, because we are just putting the node back into the same parent.
+        element
+            .insert_child(doc.deref_mut(), inside_to, node)
+            .unwrap();
+    }
+
     /// Insert a new element into the list at the last position similarly as in stack.
     ///
     /// # Panics
-    /// Fails if `value` cannot be attached to the list tag (it already has a parent,
-    /// or is itself the root container tag).
+    /// Fails if `value` cannot be attached to the list tag (see [XmlList::try_insert]).
     pub fn push(&self, value: Type) {
         self.insert(self.len(), value)
     }
 
+    /// Same as [XmlList::push], but returns an error instead of panicking
+    /// (see [XmlList::try_insert]).
+    pub fn try_push(&self, value: Type) -> Result<(), String> {
+        self.try_insert(self.len(), value)
+    }
+
     /// Remove an element from the last position similarly as in stack, and return it.
     ///
     /// # Panics
@@ -192,6 +270,21 @@ impl<Type: XmlWrapper> XmlList<Type> {
             index: 0,
         }
     }
+
+    /// Iterate over the `(index, item)` pairs of this list.
+    ///
+    /// Unlike `iter().enumerate()`, which looks up every item by its index, this iterator
+    /// walks the child nodes of the list tag only once. Hence, it is preferable for
+    /// operations which need both the items and their positions.
+    ///
+    /// The list should not be modified while the iterator is in use.
+    pub fn iter_indexed(&self) -> XmlListIndexedIterator<Type> {
+        XmlListIndexedIterator {
+            list: self,
+            index: 0,
+            position: 0,
+        }
+    }
 }
 
 // TODO:
@@ -203,6 +296,38 @@ impl<Type: XmlWrapper> XmlList<Type> {
 //   do not use `SBase`.
 impl<T: XmlWrapper> SBase for XmlList<T> {}
 
+impl<T: SBase> XmlList<T> {
+    /// Returns the `id` values of all items of this list, in the order of the items.
+    /// Items without an `id` are skipped.
+    ///
+    /// The result is the same as collecting `item.id().get()` for every item, but the
+    /// attributes are read directly from the document while holding a single lock, which
+    /// avoids creating a wrapper object for each item.
+    pub fn collect_ids(&self) -> Vec<String> {
+        let doc = self.read_doc();
+        self.raw_element()
+            .child_elements(doc.deref())
+            .into_iter()
+            .filter_map(|it| it.attribute(doc.deref(), "id").map(|id| id.to_string()))
+            .collect()
+    }
+
+    /// Returns the `id` values which appear more than once among the items of this list.
+    ///
+    /// Each duplicate `id` is reported once, in the order of its first repeated occurrence.
+    /// Items without an `id` are ignored.
+    pub fn duplicate_ids(&self) -> Vec<String> {
+        let mut seen = HashSet::new();
+        let mut duplicates = Vec::new();
+        for id in self.collect_ids() {
+            if !seen.insert(id.clone()) && !duplicates.contains(&id) {
+                duplicates.push(id);
+            }
+        }
+        duplicates
+    }
+}
+
 /// A helper structure which allows us to iterate over the elements of a [XmlList].
 pub struct XmlListIterator<'a, T: XmlWrapper> {
     list: &'a XmlList<T>,
@@ -222,3 +347,33 @@ impl<T: XmlWrapper> Iterator for XmlListIterator<'_, T> {
         }
     }
 }
+
+/// A helper structure which allows us to iterate over the elements of a [XmlList] together
+/// with their indices (see [XmlList::iter_indexed]).
+pub struct XmlListIndexedIterator<'a, T: XmlWrapper> {
+    list: &'a XmlList<T>,
+    /// The index of the next item.
+    index: usize,
+    /// The position of the next child node (including non-element nodes) to inspect.
+    position: usize,
+}
+
+impl<T: XmlWrapper> Iterator for XmlListIndexedIterator<'_, T> {
+    type Item = (usize, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let doc = self.list.read_doc();
+        let children = self.list.raw_element().children(doc.deref());
+        while let Some(node) = children.get(self.position) {
+            self.position += 1;
+            if let Some(element) = node.as_element() {
+                let element = XmlElement::new_raw(self.list.document(), element);
+                let index = self.index;
+                self.index += 1;
+                // TODO: This really is not safe at the moment (same as `XmlList::get`).
+                return Some((index, unsafe { T::unchecked_cast(element) }));
+            }
+        }
+        None
+    }
+}
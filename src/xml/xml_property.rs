@@ -1,4 +1,4 @@
-use crate::xml::{XmlElement, XmlPropertyType, XmlWrapper};
+use crate::xml::{FloatFormat, XmlElement, XmlPropertyType, XmlWrapper};
 use std::ops::{Deref, DerefMut};
 
 /// Any [XmlProperty] object provides type-safe access to a single XML attribute
@@ -93,6 +93,18 @@ pub trait XmlProperty<T: XmlPropertyType>: Sized {
             .raw_element()
             .set_attribute(doc.deref_mut(), name, value);
     }
+
+    /// Write a floating-point `value` into this [XmlProperty] using the given `format`.
+    ///
+    /// The `set` methods always write [FloatFormat::ShortestRoundTrip], which can be read back
+    /// bit-exact. This method can be used to write a more readable (but possibly imprecise)
+    /// value instead.
+    fn set_float(&self, value: f64, format: FloatFormat)
+    where
+        Self: XmlProperty<f64>,
+    {
+        XmlProperty::<f64>::set_raw(self, format.format(value));
+    }
 }
 
 /// A variant of [XmlProperty] that covers a property that can be missing in a valid document.
@@ -125,6 +137,46 @@ pub trait OptionalXmlProperty<T: XmlPropertyType>: XmlProperty<T> {
             Some(value) => self.set_raw(value),
         }
     }
+
+    /// A variant of [OptionalXmlProperty::set] that only modifies the document if the current
+    /// value of the property differs from `value`. Returns `true` if the document was modified.
+    ///
+    /// An invalid current value is always overwritten.
+    fn set_if_changed(&self, value: Option<&T>) -> bool
+    where
+        T: PartialEq,
+    {
+        // Some types report a missing value as an error, hence it is checked explicitly.
+        let current = if self.is_set() {
+            self.get_checked()
+        } else {
+            Ok(None)
+        };
+        if let Ok(current) = current {
+            if current.as_ref() == value {
+                return false;
+            }
+        }
+        self.set(value);
+        true
+    }
+
+    /// Read the value of an optional XML property, or write and return `default` when
+    /// the property is not set.
+    ///
+    /// An invalid current value is overwritten with `default` as well.
+    fn get_or_set_default(&self, default: &T) -> T
+    where
+        T: Clone,
+    {
+        if self.is_set() {
+            if let Ok(Some(value)) = self.get_checked() {
+                return value;
+            }
+        }
+        self.set_some(default);
+        default.clone()
+    }
 }
 
 /// A variant of [XmlProperty] that covers a property that is required to have a value in
@@ -159,4 +211,31 @@ pub trait RequiredXmlProperty<T: XmlPropertyType>: XmlProperty<T> {
             Some(value) => self.set_raw(value),
         };
     }
+
+    /// A variant of [RequiredXmlProperty::set] that only modifies the document if the current
+    /// value of the property differs from `value`. Returns `true` if the document was modified.
+    ///
+    /// An invalid current value is always overwritten.
+    fn set_if_changed(&self, value: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        if let Ok(Some(current)) = self.get_checked() {
+            if &current == value {
+                return false;
+            }
+        }
+        self.set(value);
+        true
+    }
+
+    /// Write `default` into a required XML property, but only if the property currently
+    /// has no value (i.e. [RequiredXmlProperty::get] would panic). A property that has a valid
+    /// value is left unchanged, while a missing or invalid value is replaced.
+    fn ensure(&self, default: &T) {
+        if let Ok(Some(_)) = self.get_checked() {
+            return;
+        }
+        self.set(default);
+    }
 }
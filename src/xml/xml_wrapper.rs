@@ -5,7 +5,7 @@ use crate::xml::{
 use biodivine_xml_doc::{Document, Element};
 use std::collections::HashMap;
 use std::ops::{Deref, DerefMut};
-use std::sync::{RwLockReadGuard, RwLockWriteGuard};
+use std::sync::{Arc, RwLockReadGuard, RwLockWriteGuard};
 
 /// [XmlWrapper] is a trait implemented by all types that can behave as an [XmlElement]
 /// (including [XmlElement] itself). In other words, instances of [XmlWrapper] provide
@@ -47,6 +47,15 @@ pub trait XmlWrapper: Into<XmlElement> {
         self.xml_element().document.clone()
     }
 
+    /// Returns `true` if `self` and `other` belong to the same [XmlDocument].
+    ///
+    /// Raw [Element] references are only meaningful within their own document, hence elements
+    /// of different documents must never be combined (see [XmlWrapper::try_attach_at]).
+    /// To copy an element into another document, use [XmlElement::import_into].
+    fn same_document<W: XmlWrapper>(&self, other: &W) -> bool {
+        Arc::ptr_eq(&self.xml_element().document, &other.xml_element().document)
+    }
+
     /// Get the [Element] instance for the underlying [XmlElement].
     ///
     /// Note that directly interfacing with `xml-doc` elements is strongly discouraged,
@@ -153,6 +162,24 @@ pub trait XmlWrapper: Into<XmlElement> {
         None
     }
 
+    /// Return the raw value of the specified attribute, but only if its prefix resolves to
+    /// the given `namespace_url`. Unprefixed attributes never match, since they have no
+    /// namespace in XML.
+    fn get_namespaced_attribute(&self, name: &str, namespace_url: &str) -> Option<String> {
+        let doc = self.read_doc();
+        let element = self.raw_element();
+        for (full_name, value) in element.attributes(doc.deref()) {
+            let (prefix, attr_name) = Element::separate_prefix_name(full_name);
+            if prefix.is_empty() || attr_name != name {
+                continue;
+            }
+            if element.namespace_for_prefix(doc.deref(), prefix) == Some(namespace_url) {
+                return Some(value.clone());
+            }
+        }
+        None
+    }
+
     /// Return the text content of this element and all its children.
     fn text_content(&self) -> String {
         let doc = self.read_doc();
@@ -167,6 +194,20 @@ pub trait XmlWrapper: Into<XmlElement> {
             .map(|it| XmlElement::new_raw(self.document(), it))
     }
 
+    /// Returns a slash-separated path of tag names from the document root to this element,
+    /// e.g. `sbml/model/listOfReactions/reaction/kineticLaw/math`.
+    ///
+    /// See also [Self::tag_path_with_ids].
+    fn tag_path(&self) -> String {
+        tag_path(self.xml_element(), false)
+    }
+
+    /// Same as [Self::tag_path], but every element with an `id` attribute is disambiguated
+    /// using this identifier, e.g. `sbml/model[@id=m]/listOfReactions/reaction[@id=r1]`.
+    fn tag_path_with_ids(&self) -> String {
+        tag_path(self.xml_element(), true)
+    }
+
     /// Returns the vector of children referenced within this [XmlWrapper] as a collection
     /// of [Element] objects. This method skips any child nodes that are not elements (such as
     /// text or comments).
@@ -209,10 +250,12 @@ pub trait XmlWrapper: Into<XmlElement> {
 
     /// Version of [Self::child_elements] that recursively traverses all child nodes, not just
     /// the immediate descendants.
+    ///
+    /// The elements are returned in document order (pre-order). The traversal is iterative,
+    /// so it cannot overflow the stack even on very deeply nested documents.
     fn recursive_child_elements(&self) -> Vec<XmlElement> {
         let doc = self.read_doc();
-        self.raw_element()
-            .child_elements_recursive(doc.deref())
+        descendant_elements(doc.deref(), self.raw_element())
             .into_iter()
             .map(|it| XmlElement::new_raw(self.document(), it))
             .collect()
@@ -225,14 +268,46 @@ pub trait XmlWrapper: Into<XmlElement> {
         predicate: P,
     ) -> Vec<XmlElement> {
         let doc = self.read_doc();
-        self.raw_element()
-            .child_elements_recursive(doc.deref())
+        descendant_elements(doc.deref(), self.raw_element())
             .into_iter()
             .map(|it| XmlElement::new_raw(self.document(), it))
             .filter(predicate)
             .collect()
     }
 
+    /// The number of elements nested (transitively) within this element, i.e. the length of
+    /// [Self::recursive_child_elements], but without allocating the wrapper objects.
+    fn descendant_count(&self) -> usize {
+        let doc = self.read_doc();
+        let mut count = 0;
+        let mut stack = vec![self.raw_element()];
+        while let Some(top) = stack.pop() {
+            let children = top.child_elements(doc.deref());
+            count += children.len();
+            stack.extend(children);
+        }
+        count
+    }
+
+    /// The maximal nesting depth of elements within this element. An element without child
+    /// elements has depth `0`, an element with only leaf children has depth `1`, etc.
+    ///
+    /// Like [Self::recursive_child_elements], the traversal is iterative.
+    fn max_depth(&self) -> usize {
+        let doc = self.read_doc();
+        let mut result = 0;
+        let mut stack = vec![(self.raw_element(), 0)];
+        while let Some((top, depth)) = stack.pop() {
+            result = result.max(depth);
+            stack.extend(
+                top.child_elements(doc.deref())
+                    .into_iter()
+                    .map(|it| (it, depth + 1)),
+            );
+        }
+        result
+    }
+
     /// Returns the vector of names of children referenced within this [XmlWrapper].
     ///
     /// Note that these are "plain" names without namespace prefixes.
@@ -358,6 +433,7 @@ pub trait XmlWrapper: Into<XmlElement> {
     /// `xmlns=""` set.
     ///
     /// ### Errors
+    ///  - If `self` and `parent` do not belong to the [same document](XmlWrapper::same_document).
     ///  - If `self` is not detached or is the container element.
     ///  - If `position > parent.children().len()`.
     fn try_attach_at<W: XmlWrapper>(
@@ -365,88 +441,136 @@ pub trait XmlWrapper: Into<XmlElement> {
         parent: &W,
         position: Option<usize>,
     ) -> Result<(), String> {
-        // !! See `try_detach` note about deadlocks and self methods. !!
-        let element = self.raw_element();
-        let parent_element = parent.raw_element();
-        let mut doc = self.write_doc();
-
-        // First, check that everything is ok.
-
-        if element.parent(doc.deref()).is_some() {
+        if !self.same_document(parent) {
+            // Attaching an element of another document would silently corrupt both documents,
+            // because the raw element reference is resolved in the wrong document.
             return Err(format!(
-                "Cannot attach `{}`. Not detached.",
-                element.name(doc.deref())
-            ));
-        }
-        let child_count = parent_element.children(doc.deref()).len();
-        let position = position.unwrap_or(child_count);
-        if position > child_count {
-            return Err(format!(
-                "Cannot attach `{}`. Invalid position `{} > {}`.",
-                element.name(doc.deref()),
-                position,
-                child_count
+                "Cannot attach `{}`. The element belongs to a different document.",
+                self.tag_name()
             ));
         }
+        // !! See `try_detach` note about deadlocks and self methods. !!
+        let mut doc = self.write_doc();
+        attach_raw(
+            doc.deref_mut(),
+            self.raw_element(),
+            parent.raw_element(),
+            position,
+        )
+    }
+}
 
-        // Now we can actually attach the child.
+/// Attach the detached `element` into `parent` at the given `position` using an already
+/// locked `doc` (see [XmlWrapper::try_attach_at]).
+pub(crate) fn attach_raw(
+    doc: &mut Document,
+    element: Element,
+    parent_element: Element,
+    position: Option<usize>,
+) -> Result<(), String> {
+    // First, check that everything is ok.
+
+    if element.parent(doc).is_some() {
+        return Err(format!(
+            "Cannot attach `{}`. Not detached.",
+            element.name(doc)
+        ));
+    }
+    let child_count = parent_element.children(doc).len();
+    let position = position.unwrap_or(child_count);
+    if position > child_count {
+        return Err(format!(
+            "Cannot attach `{}`. Invalid position `{} > {}`.",
+            element.name(doc),
+            position,
+            child_count
+        ));
+    }
 
-        if let Err(e) = parent_element.insert_child(doc.deref_mut(), position, element.as_node()) {
-            return Err(format!(
-                "Cannot detach `{}`. Internal XML error: `{}`.",
-                element.name(doc.deref()),
-                e
-            ));
-        }
+    // Now we can actually attach the child.
 
-        // And finally, we can clean up the namespaces.
-
-        let applicable_namespaces = parent_element.collect_applicable_namespace_decls(doc.deref());
-        let child_namespaces = element.namespace_decls(doc.deref()).clone();
-        let top_element = parent_element.top_parent(doc.deref());
-        for (prefix, namespace) in child_namespaces {
-            if prefix.is_empty() {
-                // Default namespace with empty prefix cannot be propagated.
-                if let Some(default) = applicable_namespaces.get(&prefix) {
-                    if *default == namespace {
-                        // However, if the same default namespace already exists, we can remove it.
-                        element
-                            .mut_namespace_decls(doc.deref_mut())
-                            .remove(prefix.as_str());
-                    }
-                } else {
-                    // Or, if the default namespace does not exist, we can remove if as well,
-                    // because it is implied.
-                    element
-                        .mut_namespace_decls(doc.deref_mut())
-                        .remove(prefix.as_str());
+    if let Err(e) = parent_element.insert_child(doc, position, element.as_node()) {
+        return Err(format!(
+            "Cannot detach `{}`. Internal XML error: `{}`.",
+            element.name(doc),
+            e
+        ));
+    }
+
+    // And finally, we can clean up the namespaces.
+
+    let applicable_namespaces = parent_element.collect_applicable_namespace_decls(doc);
+    let child_namespaces = element.namespace_decls(doc).clone();
+    let top_element = parent_element.top_parent(doc);
+    for (prefix, namespace) in child_namespaces {
+        if prefix.is_empty() {
+            // Default namespace with empty prefix cannot be propagated.
+            if let Some(default) = applicable_namespaces.get(&prefix) {
+                if *default == namespace {
+                    // However, if the same default namespace already exists, we can remove it.
+                    element.mut_namespace_decls(doc).remove(prefix.as_str());
                 }
-                continue;
+            } else {
+                // Or, if the default namespace does not exist, we can remove if as well,
+                // because it is implied.
+                element.mut_namespace_decls(doc).remove(prefix.as_str());
             }
-            if let Some(declared) = applicable_namespaces.get(&prefix) {
-                if *declared == namespace {
-                    // The prefix is already declared with the same URL. Hence, we can remove
-                    // the declaration on the child element because it is redundant.
-                    element
-                        .mut_namespace_decls(doc.deref_mut())
-                        .remove(prefix.as_str());
-                } else {
-                    // Otherwise we must keep the declaration on the child element, because
-                    // the prefix is already used for other namespaces elsewhere in the document.
-                    continue;
-                }
+            continue;
+        }
+        if let Some(declared) = applicable_namespaces.get(&prefix) {
+            if *declared == namespace {
+                // The prefix is already declared with the same URL. Hence, we can remove
+                // the declaration on the child element because it is redundant.
+                element.mut_namespace_decls(doc).remove(prefix.as_str());
             } else {
-                // The prefix is not used in this document yet. We can re-declare it in the
-                // root to make it easier to work with.
-                element
-                    .mut_namespace_decls(doc.deref_mut())
-                    .remove(prefix.as_str());
-                top_element.set_namespace_decl(doc.deref_mut(), prefix, namespace);
+                // Otherwise we must keep the declaration on the child element, because
+                // the prefix is already used for other namespaces elsewhere in the document.
+                continue;
             }
+        } else {
+            // The prefix is not used in this document yet. We can re-declare it in the
+            // root to make it easier to work with.
+            element.mut_namespace_decls(doc).remove(prefix.as_str());
+            top_element.set_namespace_decl(doc, prefix, namespace);
         }
+    }
 
-        Ok(())
+    Ok(())
+}
+
+/// Build the tag path of the given element (see [XmlWrapper::tag_path]). The internal
+/// document container is not part of the path.
+fn tag_path(element: &XmlElement, with_ids: bool) -> String {
+    let mut segments = Vec::new();
+    let mut current = Some(element.clone());
+    while let Some(element) = current {
+        if element.raw_element().is_container() {
+            break;
+        }
+        let mut segment = element.tag_name();
+        if with_ids {
+            if let Some(id) = element.get_attribute("id") {
+                segment = format!("{segment}[@id={id}]");
+            }
+        }
+        segments.push(segment);
+        current = element.parent();
+    }
+    segments.reverse();
+    segments.join("/")
+}
+
+/// Collect all descendant elements of `element` in document order (pre-order), using
+/// an explicit stack instead of recursion.
+fn descendant_elements(doc: &Document, element: Element) -> Vec<Element> {
+    let mut result = Vec::new();
+    let mut stack = element.child_elements(doc);
+    stack.reverse();
+    while let Some(top) = stack.pop() {
+        result.push(top);
+        stack.extend(top.child_elements(doc).into_iter().rev());
     }
+    result
 }
 
 /// [XmlDefault] extends the functionality of [XmlWrapper] by providing a method that can build